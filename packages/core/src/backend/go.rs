@@ -0,0 +1,488 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    path::{Path, PathBuf, StripPrefixError},
+};
+
+use regex::Regex;
+
+use super::{
+    check_field_names, parse_bool, parse_naming, parse_string, parse_tabsize, LanguageBackend,
+};
+use crate::{
+    compiler::{
+        main::render_files,
+        types::{Module, OutputFile},
+    },
+    diagnostic::{Code, Diagnostic, Diagnostics},
+    generator::{
+        lang::go::{field_name, generate_go, generate_go_validate, NAME_TAGS},
+        types::{
+            ast_type_alias::{Node, UnionNode},
+            Import, Imports, AST, DEFAULT_TABSIZE,
+        },
+        utils::capitalize,
+    },
+    path::{diff_paths, normalize_path, to_relative},
+    types::{
+        config::{Config, GoOptions, GoTag, Naming, OutputGo},
+        spec::Spec,
+    },
+};
+
+pub const ID: &str = "go";
+
+#[derive(Debug, Default)]
+pub struct Go {
+    config: Option<OutputGo>,
+}
+
+impl LanguageBackend for Go {
+    fn id(&self) -> &str {
+        ID
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["go", "golang"]
+    }
+
+    fn file_extension(&self) -> &str {
+        "go"
+    }
+
+    fn parse_config(&mut self, value: &serde_yaml::Value) -> Result<(), Diagnostic> {
+        self.config = Some(OutputGo {
+            mod_name: parse_string(value, "golang", "mod_name")?,
+            root: parse_string(value, "golang", "root")?,
+            output: parse_string(value, "golang", "output")?,
+            tabsize: parse_tabsize(value, DEFAULT_TABSIZE.go)?,
+            generics: parse_bool(value, "generics", true)?,
+            validate: parse_bool(value, "validate", false)?,
+            options: GoOptions {
+                // other strategies give unexported fields.
+                naming: parse_naming(value, "golang", &[Naming::Pascal])?,
+                tags: parse_tags(value)?,
+            },
+        });
+        Ok(())
+    }
+
+    fn is_configured(&self) -> bool {
+        self.config.is_some()
+    }
+
+    fn output_dir(&self) -> &str {
+        &self.output_config().output
+    }
+
+    fn instantiates_generics(&self) -> bool {
+        self.config.as_ref().is_some_and(|o| !o.generics)
+    }
+
+    fn compile(&self, config: &Config, modules: &[Module]) -> Result<Vec<OutputFile>, Diagnostics> {
+        if !self.instantiates_generics() {
+            return render_files(self, config, modules);
+        }
+
+        let modules: Vec<&Module> = modules.iter().collect();
+        render_files(self, config, &instantiate_modules(&modules))
+    }
+
+    fn check(&self, ast: &AST, path: &[String]) -> Diagnostics {
+        let mut diagnostics: Diagnostics = vec![];
+        if let AST::TypeAlias(t) = ast {
+            t.body.walk(path, &mut |node, path| {
+                if let Node::Union(UnionNode {
+                    discriminator: None,
+                    ..
+                }) = node
+                {
+                    diagnostics.push(
+                        Diagnostic::warning(
+                            Code::Syntax,
+                            "Union is interface{} in go, set a discriminator to get a typed one.",
+                        )
+                        .at(path),
+                    );
+                }
+            });
+        }
+        let naming = self.config.as_ref().and_then(|o| o.options.naming);
+        diagnostics.extend(check_field_names(ast, path, ID, |p| field_name(p, naming)));
+        diagnostics
+    }
+
+    fn render_type(&self, ast: &AST, imports: &mut Imports) -> Result<String, Diagnostic> {
+        let Some(o) = &self.config else {
+            return generate_go(ast, imports, DEFAULT_TABSIZE.go, &GoOptions::default());
+        };
+        let mut result = generate_go(ast, imports, o.tabsize, &o.options)?;
+        if o.validate {
+            result += &generate_go_validate(ast, imports, o.tabsize, &o.options)?;
+        }
+        Ok(result)
+    }
+
+    fn render_imports(
+        &self,
+        current: &str,
+        imports: &Imports,
+        config: &Config,
+    ) -> Result<String, Diagnostic> {
+        let mut froms = String::new();
+        for (idx, import) in imports.iter().enumerate() {
+            match import {
+                Import::Dyn(di) => {
+                    let indent = " ".repeat(self.output_config().tabsize);
+                    let mut f = format!("{}\"{}\"", indent, di.from);
+                    let is_last = idx == imports.len() - 1;
+                    if !is_last {
+                        f += "\n"
+                    }
+
+                    froms += &f;
+                }
+                Import::Ref(ri) => {
+                    let mut f = self.process_from(current, &ri.from, config)?;
+                    if !f.is_empty() {
+                        let is_last = idx == imports.len() - 1;
+                        if !is_last {
+                            f += "\n"
+                        }
+                        froms += &f;
+                    }
+                }
+            };
+        }
+
+        Ok(format!(
+            "import (\n{}\n)\n\n",
+            remove_duplicate_import(&froms)
+        ))
+    }
+
+    fn preamble(&self, content: &str, spec: &Spec, config: &Config) -> Result<String, Diagnostic> {
+        let mut result = String::new();
+        let mut _content = content.to_owned();
+
+        let p = PathBuf::from(&spec.path);
+        let parent = p.parent().unwrap();
+
+        let mut package_name = match parent.file_name() {
+            Some(n) => n.to_str().unwrap(),
+            None => {
+                return Err(Diagnostic::error(
+                    Code::Io,
+                    format!("Can not get package name from path {}", spec.path),
+                ));
+            }
+        };
+        if parent == &config.spec.root {
+            package_name = self.get_root_pkg_name();
+        }
+
+        result += &format!("package {}\n\n", package_name);
+
+        let tokens = find_match_tokens(&_content);
+        if !tokens.is_empty() {
+            for token in tokens {
+                let pkg_name = self.get_ref_pkg_name(&token.path, &spec.path, config);
+                if pkg_name.is_empty() {
+                    _content = _content.replace(&token.token, "");
+                } else {
+                    _content = _content.replace(&token.token, &format!("{}.", pkg_name));
+                }
+            }
+        }
+
+        // definitions may end with a blank line to separate them from the next one.
+        result += _content.trim_end();
+        result += "\n";
+        Ok(result)
+    }
+}
+
+impl Go {
+    fn output_config(&self) -> &OutputGo {
+        self.config
+            .as_ref()
+            .expect("Maybe you want to generate Go code but forget to set it in config file.")
+    }
+
+    pub fn get_root_pkg_name(&self) -> &str {
+        self.output_config().output.split('/').next_back().unwrap()
+    }
+
+    /// replace selien root with package name.
+    ///
+    /// **@return** Result<PathBuf, Error>
+    ///
+    /// Error will be return when relative path out of selien-root.
+    ///
+    /// `e.g.` If config is:
+    ///
+    /// ```yaml
+    /// spec:
+    ///   root: selien-spec
+    /// output:
+    ///   go:
+    ///     mod_name: selien
+    ///     root: packages/server
+    ///     output: packages/server/api/selien_spec
+    /// ```
+    /// we will get file path like `selien-spec/foo/bar`, (last bar is file name)
+    ///
+    /// so, we replace `selien-spec` with `selien/api/selien_spec`
+    pub fn replace_selien_root(&self, p: &Path, s: &Config) -> Result<PathBuf, StripPrefixError> {
+        let o = self.output_config();
+
+        let import_root = PathBuf::from(&o.mod_name);
+
+        let goroot_to_output =
+            diff_paths(&o.output, &o.root).expect("Can not diff path from go-root to output");
+
+        let striped = p.strip_prefix(&s.spec.root)?;
+        Ok(import_root.join(goroot_to_output).join(striped))
+    }
+
+    /// `return` empty string if self import.
+    fn process_from(
+        &self,
+        current: &str,
+        from: &Path,
+        config: &Config,
+    ) -> Result<String, Diagnostic> {
+        let o = self.output_config();
+        let indent = " ".repeat(o.tabsize);
+        let cp = PathBuf::from(current);
+
+        // selien $ref abs path must be start with `/`, even windows
+        let result_path = if from.starts_with("/") {
+            let goroot_to_output =
+                diff_paths(&o.output, &o.root).expect("Can not diff path from go-root to output");
+
+            // because from is absolute path from <selien-root>
+            // so just add `mod_name/middle/path/to/pkg_name` to head
+            let mut result = PathBuf::from(&o.mod_name).join(goroot_to_output);
+            // a file right under selien-root is in the root package.
+            let pkg = to_relative(from.parent().unwrap());
+            if !pkg.as_os_str().is_empty() {
+                result = result.join(pkg);
+            }
+            result
+        } else {
+            let joined = cp.parent().unwrap().join(from);
+            let normalized = normalize_path(&joined);
+
+            let replaced = match self.replace_selien_root(&normalized, config) {
+                Ok(r) => r,
+                Err(err) => {
+                    return Err(Diagnostic::error(
+                        Code::InvalidRef,
+                        format!(
+                            "relative path {} out of selien-root: {}",
+                            from.to_str().unwrap(),
+                            err
+                        ),
+                    ));
+                }
+            };
+            replaced.parent().unwrap().to_path_buf()
+        };
+
+        let ref_pkg = result_path.file_name().unwrap();
+        let cuurent_pkg = cp.parent().unwrap().file_name().unwrap().to_str().unwrap();
+        if ref_pkg == cuurent_pkg {
+            Ok(String::new())
+        } else {
+            Ok(format!(
+                "{}\"{}\"",
+                indent,
+                result_path.to_str().unwrap().replace('\\', "/")
+            ))
+        }
+    }
+
+    fn get_ref_pkg_name(&self, path: &String, current: &String, config: &Config) -> String {
+        let p = PathBuf::from(path);
+
+        let cp = PathBuf::from(current);
+        let current_pkg = cp.parent().unwrap();
+
+        let result = if p.is_absolute() {
+            // a file right under selien-root is in the root package.
+            match p.parent().unwrap().file_name() {
+                Some(name) => name.to_str().unwrap().to_string(),
+                None => return self.get_root_pkg_name().to_string(),
+            }
+        } else {
+            let joined = current_pkg.join(p);
+            let normalized = normalize_path(&joined);
+            normalized
+                .parent()
+                .unwrap()
+                .file_name()
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_string()
+        };
+
+        if result == config.spec.root {
+            return self.get_root_pkg_name().to_string();
+        }
+        if result == current_pkg.file_name().unwrap().to_str().unwrap() {
+            return String::new();
+        }
+
+        result
+    }
+}
+
+/// Read `tags` of `output.go`, a key or a mapping with `key` and `naming` for each tag.
+fn parse_tags(value: &serde_yaml::Value) -> Result<Vec<GoTag>, Diagnostic> {
+    let Some(v) = value.get("tags") else {
+        return Ok(vec![]);
+    };
+    let items = v.as_sequence().ok_or_else(|| {
+        Diagnostic::error(Code::Config, "tags must be a array (sequence).")
+            .at(&[String::from("tags")])
+    })?;
+    let mut tags: Vec<GoTag> = vec![];
+    for (idx, item) in items.iter().enumerate() {
+        let path = [String::from("tags"), idx.to_string()];
+        let invalid = |message: String| Err(Diagnostic::error(Code::Config, message).at(&path));
+        let tag = match item {
+            serde_yaml::Value::String(key) => GoTag {
+                key: key.clone(),
+                naming: None,
+            },
+            serde_yaml::Value::Mapping(_) => GoTag {
+                key: parse_string(item, "golang tag", "key").map_err(|d| d.at(&path))?,
+                naming: parse_naming(item, "golang tag", &Naming::ALL).map_err(|d| {
+                    d.at(&[
+                        String::from("tags"),
+                        idx.to_string(),
+                        String::from("naming"),
+                    ])
+                })?,
+            },
+            _ => return invalid(String::from("A tag must be a key or a mapping.")),
+        };
+        if !is_tag_key(&tag.key) {
+            return invalid(format!("Invalid tag key: {:?}.", tag.key));
+        }
+        if tag.key == "json" {
+            return invalid(String::from(
+                "The json tag is always emitted, set the key in JSON by wireName.",
+            ));
+        }
+        if tag.naming.is_some() && !NAME_TAGS.contains(&tag.key.as_str()) {
+            return invalid(format!(
+                "Tag {} has no names, naming is only for {}.",
+                tag.key,
+                NAME_TAGS.join(", ")
+            ));
+        }
+        if tags.iter().any(|t| t.key == tag.key) {
+            return invalid(format!("Tag {} is declared twice.", tag.key));
+        }
+        tags.push(tag);
+    }
+    Ok(tags)
+}
+
+/// Whether `key` is valid as the key of a struct tag, `reflect.StructTag` stops at spaces,
+/// quotes and colons.
+pub fn is_tag_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| !c.is_control() && !matches!(c, ' ' | '"' | ':' | '`'))
+}
+
+/// Generic definitions are dropped and instances are added, every `$ref` with args
+/// points to a instance in the same package.
+///
+/// Every file of a directory is one package, a instance used by several of them is
+/// in the first one by path, or replaces the alias of it in place.
+pub fn instantiate_modules<'a>(modules: &[&Module<'a>]) -> Vec<Module<'a>> {
+    let package = |m: &Module| PathBuf::from(&m.spec.path).parent().map(Path::to_path_buf);
+    let is_alias =
+        |m: &Module, i: &AST| m.ast_list.iter().any(|a| a.identifier() == i.identifier());
+
+    let mut declared: HashSet<(Option<PathBuf>, String)> = modules
+        .iter()
+        .flat_map(|m| {
+            m.instances
+                .iter()
+                .filter(|i| is_alias(m, i))
+                .map(|i| (package(m), capitalize(i.identifier())))
+        })
+        .collect();
+    let mut sorted: Vec<&Module> = modules.to_vec();
+    sorted.sort_by(|a, b| a.spec.path.cmp(&b.spec.path));
+    let mut appended: HashMap<&str, Vec<&AST>> = HashMap::new();
+    for m in sorted {
+        for i in m.instances.iter().filter(|i| !is_alias(m, i)) {
+            if declared.insert((package(m), capitalize(i.identifier()))) {
+                appended.entry(m.spec.path.as_str()).or_default().push(i);
+            }
+        }
+    }
+
+    modules
+        .iter()
+        .map(|m| {
+            let appended = appended.remove(m.spec.path.as_str()).unwrap_or_default();
+            instantiate_module(m, appended)
+        })
+        .collect()
+}
+
+/// `module` with generic definitions dropped, aliases of instances replaced by them
+/// and `appended` added.
+fn instantiate_module<'a>(module: &Module<'a>, appended: Vec<&AST>) -> Module<'a> {
+    let mut ast_list: Vec<AST> = vec![];
+    for ast in module.ast_list.iter() {
+        if matches!(ast, AST::TypeAlias(t) if !t.type_parameters.is_empty()) {
+            continue;
+        }
+        // an alias of a instance is replaced by it in place.
+        match module
+            .instances
+            .iter()
+            .find(|i| i.identifier() == ast.identifier())
+        {
+            Some(i) => ast_list.push(i.instantiate()),
+            None => ast_list.push(ast.instantiate()),
+        }
+    }
+    ast_list.extend(appended.into_iter().map(|i| i.instantiate()));
+
+    Module {
+        spec: module.spec,
+        ast_list,
+        instances: vec![],
+    }
+}
+
+pub fn remove_duplicate_import(s: &str) -> String {
+    let lines: BTreeSet<&str> = s.lines().collect();
+    lines.into_iter().collect::<Vec<&str>>().join("\n")
+}
+
+#[derive(Debug)]
+struct MatchToken {
+    token: String,
+    path: String,
+}
+
+fn find_match_tokens(text: &str) -> Vec<MatchToken> {
+    let re = Regex::new(r"\[selien-ref\](.*?)\[selien-ref\]").unwrap();
+    re.captures_iter(text)
+        .map(|cap| MatchToken {
+            token: cap[0].to_string(),
+            path: cap[1].to_string(),
+        })
+        .collect()
+}
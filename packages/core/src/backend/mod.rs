@@ -0,0 +1,306 @@
+use std::fmt;
+
+use crate::compiler::{
+    main::render_files,
+    types::{Module, OutputFile},
+};
+use crate::diagnostic::{key_path, Code, Diagnostic, Diagnostics};
+use crate::generator::types::{
+    ast_type_alias::{Node, Property, TypeAliasAst},
+    Imports, AST,
+};
+use crate::types::{
+    config::{Config, Naming},
+    spec::Spec,
+};
+
+pub mod go;
+pub mod plugin;
+pub mod python;
+pub mod rust;
+pub mod typescript;
+
+/// A target language of selien.
+///
+/// Everything language specific lives behind this trait,
+/// the transformer and compiler only talk to it. Built-in languages are
+/// registered in `Backends::builtin`, an in-house language can be added with
+/// `Backends::register` without touching selien itself.
+///
+/// The compiler calls it in this order for every spec file:
+///
+/// ```text
+/// check (each definition, before compiling)
+/// render_type (each definition) -> render_imports -> preamble -> notice
+/// ```
+///
+/// and then `extra_files` once. A backend can also take over the whole
+/// compilation by overriding `compile`, like plugins do.
+///
+/// Problems of user input are returned as `Diagnostic`, so that selien can
+/// report all of them at once instead of stopping at the first one.
+pub trait LanguageBackend {
+    /// Unique id, used as the key of `SplitNode`.
+    fn id(&self) -> &str;
+
+    /// Names can be used as `output.<alias>` and `split.<alias>` in selien files,
+    /// or `selien gen -o <alias>`.
+    fn aliases(&self) -> Vec<&str>;
+
+    /// Extension of generated files, without dot.
+    fn file_extension(&self) -> &str;
+
+    /// Line comment, used to write the notice at the head of generated files.
+    fn comment(&self) -> &str {
+        "//"
+    }
+
+    /// Parse `output.<alias>` in config file.
+    ///
+    /// Key path of the returned diagnostic is relative to `output.<alias>`.
+    fn parse_config(&mut self, value: &serde_yaml::Value) -> Result<(), Diagnostic>;
+
+    /// Return true after `parse_config` is called.
+    fn is_configured(&self) -> bool;
+
+    /// `output.<alias>.output` in config file.
+    fn output_dir(&self) -> &str;
+
+    /// Problems of one definition in this language only, like a type it has nothing for.
+    ///
+    /// Called with the other checks before compiling, split node is resolved before.
+    /// `path` is the key path of `ast` in the spec file, a split body is under the key
+    /// of this language.
+    fn check(&self, _ast: &AST, _path: &[String]) -> Diagnostics {
+        vec![]
+    }
+
+    /// Render one definition, push what it needs to `imports`.
+    ///
+    /// Split node is resolved before, so the body of `ast` will never be a split.
+    /// A error is a node `transformer` does not let through, like a nested split.
+    fn render_type(&self, ast: &AST, imports: &mut Imports) -> Result<String, Diagnostic>;
+
+    /// Render collected imports of the spec file at `current`.
+    fn render_imports(
+        &self,
+        current: &str,
+        imports: &Imports,
+        config: &Config,
+    ) -> Result<String, Diagnostic>;
+
+    /// Things must be placed at the head of file, like `package` in go.
+    ///
+    /// Receives the whole content (imports included) and returns the new one.
+    fn preamble(
+        &self,
+        content: &str,
+        _spec: &Spec,
+        _config: &Config,
+    ) -> Result<String, Diagnostic> {
+        Ok(content.to_owned())
+    }
+
+    /// Files which is not came from spec files, like `mod.rs` of rust.
+    ///
+    /// Paths must be under selien-root like spec files, they will be moved
+    /// to output and have the extension replaced.
+    fn extra_files(&self, _modules: &[Module], _config: &Config) -> Vec<OutputFile> {
+        vec![]
+    }
+
+    /// Return true if every generic definition is replaced by `Module::instances`,
+    /// for a language without generics.
+    fn instantiates_generics(&self) -> bool {
+        false
+    }
+
+    /// Return true if `Module::instances` of `generic` are needed,
+    /// for a language which can not express some generic definitions.
+    fn instantiates(&self, _generic: &TypeAliasAst) -> bool {
+        self.instantiates_generics()
+    }
+
+    /// Every file to be written, paths are under selien-root.
+    fn compile(&self, config: &Config, modules: &[Module]) -> Result<Vec<OutputFile>, Diagnostics> {
+        render_files(self, config, modules)
+    }
+}
+
+/// All languages selien knows.
+#[derive(Default)]
+pub struct Backends {
+    list: Vec<Box<dyn LanguageBackend>>,
+}
+
+impl Backends {
+    pub fn builtin() -> Self {
+        let mut backends = Self::default();
+        let builtin: Vec<Box<dyn LanguageBackend>> = vec![
+            Box::<typescript::TypeScript>::default(),
+            Box::<go::Go>::default(),
+            Box::<rust::Rust>::default(),
+            Box::<python::Python>::default(),
+        ];
+        for backend in builtin {
+            backends
+                .register(backend)
+                .expect("Aliases of builtin languages must not conflict.");
+        }
+        backends
+    }
+
+    pub fn register(&mut self, backend: Box<dyn LanguageBackend>) -> Result<(), Diagnostic> {
+        for alias in backend.aliases() {
+            if let Some(exists) = self.find(alias) {
+                return Err(Diagnostic::error(
+                    Code::Config,
+                    format!(
+                        "Language alias {} of {} is already used by {}.",
+                        alias,
+                        backend.id(),
+                        exists.id()
+                    ),
+                ));
+            }
+        }
+        self.list.push(backend);
+        Ok(())
+    }
+
+    pub fn find(&self, alias: &str) -> Option<&dyn LanguageBackend> {
+        self.list
+            .iter()
+            .find(|b| b.aliases().contains(&alias))
+            .map(|b| b.as_ref())
+    }
+
+    pub fn find_mut(&mut self, alias: &str) -> Option<&mut Box<dyn LanguageBackend>> {
+        self.list.iter_mut().find(|b| b.aliases().contains(&alias))
+    }
+
+    /// Backends defined in config file.
+    pub fn configured(&self) -> impl Iterator<Item = &dyn LanguageBackend> {
+        self.list
+            .iter()
+            .filter(|b| b.is_configured())
+            .map(|b| b.as_ref())
+    }
+}
+
+impl fmt::Debug for Backends {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.list.iter().map(|b| b.id()))
+            .finish()
+    }
+}
+
+/// Read `tabsize` of `output.<alias>`, `default` if not set.
+pub fn parse_tabsize(value: &serde_yaml::Value, default: usize) -> Result<usize, Diagnostic> {
+    match value.get("tabsize") {
+        Some(r) => r.as_u64().map(|t| t as usize).ok_or_else(|| {
+            Diagnostic::error(Code::Config, "tabsize must be a positive integer.")
+                .at(&[String::from("tabsize")])
+        }),
+        None => Ok(default),
+    }
+}
+
+/// Read a boolean field of `output.<alias>`, `default` if not set.
+pub fn parse_bool(value: &serde_yaml::Value, key: &str, default: bool) -> Result<bool, Diagnostic> {
+    match value.get(key) {
+        Some(v) => v.as_bool().ok_or_else(|| {
+            Diagnostic::error(Code::Config, format!("{} must be a boolean.", key))
+                .at(&[key.to_string()])
+        }),
+        None => Ok(default),
+    }
+}
+
+/// Read `naming` of `output.<alias>`, `None` if not set.
+///
+/// `allowed` are the strategies giving valid names in the language.
+pub fn parse_naming(
+    value: &serde_yaml::Value,
+    lang: &str,
+    allowed: &[Naming],
+) -> Result<Option<Naming>, Diagnostic> {
+    let Some(v) = value.get("naming") else {
+        return Ok(None);
+    };
+    match allowed.iter().find(|n| v.as_str() == Some(n.name())) {
+        Some(n) => Ok(Some(*n)),
+        None => {
+            let names: Vec<&str> = allowed.iter().map(|n| n.name()).collect();
+            Err(Diagnostic::error(
+                Code::Config,
+                format!(
+                    "Invalid {} naming: {:?}, expected {}.",
+                    lang,
+                    v.as_str().unwrap_or_default(),
+                    names.join(" or ")
+                ),
+            )
+            .at(&[String::from("naming")]))
+        }
+    }
+}
+
+/// Read a required string field of `output.<alias>`.
+pub fn parse_string(
+    value: &serde_yaml::Value,
+    lang: &str,
+    key: &str,
+) -> Result<String, Diagnostic> {
+    match value.get(key) {
+        Some(v) => v.as_str().map(|v| v.to_string()).ok_or_else(|| {
+            Diagnostic::error(Code::Config, format!("{} must be a string.", key))
+                .at(&[key.to_string()])
+        }),
+        None => Err(Diagnostic::error(
+            Code::Config,
+            format!("selien {} config is missing {}.", lang, key),
+        )),
+    }
+}
+
+/// Properties of one object in `ast` which get the same field name in `lang`,
+/// `field` is the name a property gets there.
+///
+/// Naming like `PascalCase` can make two keys one, as `userId` and `userID`.
+/// `path` is the key path of `ast`, as of `LanguageBackend::check`.
+pub fn check_field_names(
+    ast: &AST,
+    path: &[String],
+    lang: &str,
+    field: impl Fn(&Property) -> String,
+) -> Diagnostics {
+    let mut diagnostics: Diagnostics = vec![];
+    let AST::TypeAlias(t) = ast else {
+        return diagnostics;
+    };
+    t.body.walk(path, &mut |node, path| {
+        let Node::Object(o) = node else {
+            return;
+        };
+        let mut seen: Vec<(String, &str)> = vec![];
+        for p in o.values.iter() {
+            let name = field(p);
+            match seen.iter().find(|(n, _)| n == &name) {
+                Some((_, other)) => diagnostics.push(
+                    Diagnostic::error(
+                        Code::DuplicateIdentifier,
+                        format!(
+                            "Properties {} and {} are both {} in {}.",
+                            other, p.identifier, name, lang
+                        ),
+                    )
+                    .at(&key_path(&key_path(path, "properties"), &p.identifier)),
+                ),
+                None => seen.push((name, &p.identifier)),
+            }
+        }
+    });
+    diagnostics
+}
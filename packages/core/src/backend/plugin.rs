@@ -0,0 +1,344 @@
+//! External generator over a stdin/stdout JSON protocol.
+//!
+//! A plugin is declared in config file with `output.<name>.plugin`.
+//! Selien runs the executable once, writes a request to its stdin:
+//!
+//! ```json
+//! {
+//!   "version": 2,
+//!   "name": "cpp",
+//!   "config": { "spec_root": "selien-spec", "output": { "plugin": "./gen", "output": "dist" } },
+//!   "files": [{ "path": "nested/middle.yaml", "ast": [], "imports": [] }]
+//! }
+//! ```
+//!
+//! and reads a list of `{ "path", "content" }` from its stdout,
+//! paths are relative to `output.<name>.output`.
+//!
+//! `version` is `AST_VERSION`, see `generator::types` for the representation of `ast` and `imports`.
+
+use std::{
+    io::Write,
+    path::{Component, PathBuf},
+    process::{Command, Stdio},
+    thread,
+};
+
+use serde::Serialize;
+
+use super::{parse_string, LanguageBackend};
+use crate::{
+    compiler::types::{Module, OutputFile},
+    diagnostic::{Code, Diagnostic, Diagnostics},
+    generator::types::{
+        ast_type_alias::Node, DynImport, Import, Imports, RefImport, AST, AST_VERSION,
+    },
+    types::config::{Config, OutputPlugin},
+};
+
+#[derive(Debug)]
+pub struct Plugin {
+    name: String,
+    config: Option<OutputPlugin>,
+}
+
+#[derive(Serialize)]
+struct Request<'a> {
+    version: u32,
+    name: &'a str,
+    config: RequestConfig<'a>,
+    files: Vec<RequestFile>,
+}
+
+#[derive(Serialize)]
+struct RequestConfig<'a> {
+    spec_root: &'a str,
+    output: &'a serde_yaml::Value,
+}
+
+#[derive(Serialize)]
+struct RequestFile {
+    /// Relative to selien-root.
+    path: String,
+    ast: Vec<AST>,
+    imports: Imports,
+}
+
+impl Plugin {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            config: None,
+        }
+    }
+
+    fn output_config(&self) -> Result<&OutputPlugin, Diagnostic> {
+        self.config.as_ref().ok_or_else(|| {
+            Diagnostic::error(
+                Code::Config,
+                format!(
+                    "Plugin {} is not set in config output, add output.{} to run it.",
+                    self.name, self.name
+                ),
+            )
+        })
+    }
+
+    fn run(&self, request: &Request) -> Result<Vec<OutputFile>, Diagnostic> {
+        let o = self.output_config()?;
+
+        let input = serde_json::to_vec(request).expect("Can not serialize plugin request.");
+
+        let mut child = Command::new(&o.plugin)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|err| {
+                self.error(format!(
+                    "Can not run plugin {} ({}): {}",
+                    self.name, o.plugin, err
+                ))
+            })?;
+
+        // write in another thread, plugin may write stdout before reading all of stdin.
+        let mut stdin = child.stdin.take().expect("Can not open stdin of plugin.");
+        let writer = thread::spawn(move || stdin.write_all(&input));
+
+        let output = child.wait_with_output().map_err(|err| {
+            self.error(format!(
+                "Can not read output of plugin {}: {}",
+                self.name, err
+            ))
+        })?;
+
+        if !output.status.success() {
+            return Err(self.error(format!(
+                "Plugin {} exited with {}.",
+                self.name, output.status
+            )));
+        }
+
+        if let Ok(Err(err)) = writer.join() {
+            return Err(self.error(format!(
+                "Can not write request to plugin {}: {}",
+                self.name, err
+            )));
+        }
+
+        serde_json::from_slice(&output.stdout).map_err(|err| {
+            self.error(format!(
+                "Can not parse output of plugin {}, expected a list of {{path, content}}: {}",
+                self.name, err
+            ))
+        })
+    }
+
+    fn error(&self, message: String) -> Diagnostic {
+        Diagnostic::error(Code::Plugin, message)
+    }
+}
+
+impl LanguageBackend for Plugin {
+    fn id(&self) -> &str {
+        &self.name
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec![&self.name]
+    }
+
+    /// Plugins name files themselves.
+    fn file_extension(&self) -> &str {
+        ""
+    }
+
+    fn parse_config(&mut self, value: &serde_yaml::Value) -> Result<(), Diagnostic> {
+        self.config = Some(OutputPlugin {
+            plugin: parse_string(value, &self.name, "plugin")?,
+            output: parse_string(value, &self.name, "output")?,
+            options: value.clone(),
+        });
+        Ok(())
+    }
+
+    fn is_configured(&self) -> bool {
+        self.config.is_some()
+    }
+
+    /// Empty if the plugin is not set in config output.
+    fn output_dir(&self) -> &str {
+        self.config.as_ref().map_or("", |o| &o.output)
+    }
+
+    // plugins take over `compile`, the steps below are never called.
+
+    fn render_type(&self, _ast: &AST, _imports: &mut Imports) -> Result<String, Diagnostic> {
+        Err(self.error(format!("Plugin {} renders types itself.", self.name)))
+    }
+
+    fn render_imports(
+        &self,
+        _current: &str,
+        _imports: &Imports,
+        _config: &Config,
+    ) -> Result<String, Diagnostic> {
+        Err(self.error(format!("Plugin {} renders imports itself.", self.name)))
+    }
+
+    fn compile(&self, config: &Config, modules: &[Module]) -> Result<Vec<OutputFile>, Diagnostics> {
+        let options = &self.output_config().map_err(|d| vec![d])?.options;
+        let mut files: Vec<RequestFile> = vec![];
+        for module in modules {
+            let ast: Vec<AST> = module
+                .ast_list
+                .iter()
+                .filter_map(|ast| ast.resolve_split(self.id()))
+                .map(|ast| ast.into_owned())
+                .collect();
+
+            let mut imports: Imports = vec![];
+            for a in ast.iter() {
+                if let AST::TypeAlias(type_alias_ast) = a {
+                    collect_imports(&type_alias_ast.body, &mut imports);
+                }
+            }
+
+            let p = PathBuf::from(&module.spec.path);
+            let path = p.strip_prefix(&config.spec.root).unwrap_or(&p);
+            files.push(RequestFile {
+                path: path.to_string_lossy().replace('\\', "/"),
+                ast,
+                imports,
+            });
+        }
+
+        let request = Request {
+            version: AST_VERSION,
+            name: &self.name,
+            config: RequestConfig {
+                spec_root: &config.spec.root,
+                output: options,
+            },
+            files,
+        };
+
+        let mut result: Vec<OutputFile> = vec![];
+        let mut diagnostics: Diagnostics = vec![];
+        for file in self.run(&request).map_err(|d| vec![d])? {
+            let p = PathBuf::from(&file.path);
+            let is_inside = p
+                .components()
+                .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+            if !is_inside {
+                diagnostics.push(self.error(format!(
+                    "Plugin {} returned path {} out of its output, paths must be relative to output without `..`.",
+                    self.name, file.path
+                )));
+                continue;
+            }
+
+            // file_creater takes paths under selien-root.
+            result.push(OutputFile {
+                path: PathBuf::from(&config.spec.root)
+                    .join(p)
+                    .to_string_lossy()
+                    .to_string(),
+                content: file.content,
+            });
+        }
+
+        if diagnostics.is_empty() {
+            Ok(result)
+        } else {
+            Err(diagnostics)
+        }
+    }
+}
+
+/// `$ref` and `$dyn` used by a node.
+fn collect_imports(node: &Node, imports: &mut Imports) {
+    match node {
+        Node::Ref(node) => {
+            if !node.path.is_empty() {
+                imports.push(Import::Ref(RefImport {
+                    name: node.name.clone(),
+                    from: PathBuf::from(&node.path),
+                }));
+            }
+            node.args.iter().for_each(|a| collect_imports(a, imports));
+        }
+        Node::Dyn(node) => imports.push(Import::Dyn(DynImport {
+            name: node.name.clone(),
+            from: node.from.clone(),
+        })),
+        Node::Array(node) => collect_imports(&node.items, imports),
+        Node::Object(node) => {
+            for base in node.extends.iter() {
+                collect_imports(&Node::Ref(base.clone()), imports);
+            }
+            node.values
+                .iter()
+                .for_each(|p| collect_imports(&p.body, imports));
+        }
+        Node::Map(node) => {
+            collect_imports(&node.keys, imports);
+            collect_imports(&node.values, imports);
+        }
+        Node::Tuple(node) => node.items.iter().for_each(|t| collect_imports(t, imports)),
+        Node::Union(node) => node.types.iter().for_each(|t| collect_imports(t, imports)),
+        Node::Split(node) => node
+            .nodes
+            .values()
+            .for_each(|n| collect_imports(n, imports)),
+        Node::StringLiteral(_)
+        | Node::NumberLiteral(_)
+        | Node::Keyword(_)
+        | Node::Param(_)
+        | Node::Enum(_)
+        | Node::Empty => {}
+    }
+}
+
+// unit test here
+#[cfg(test)]
+mod test {
+    use super::{Request, RequestConfig, RequestFile};
+    use crate::generator::{lang::for_test, types::AST_VERSION};
+
+    #[test]
+    fn nested_object_representation() {
+        let ast = for_test::nested_object_ast();
+        insta::assert_yaml_snapshot!(ast);
+    }
+
+    #[test]
+    fn string_enum_representation() {
+        let ast = for_test::string_enum_ast();
+        insta::assert_yaml_snapshot!(ast);
+    }
+
+    #[test]
+    fn request_representation() {
+        let request = Request {
+            version: AST_VERSION,
+            name: "cpp",
+            config: RequestConfig {
+                spec_root: "selien-spec",
+                output: &serde_yaml::Value::Null,
+            },
+            files: vec![RequestFile {
+                path: String::from("user.yaml"),
+                ast: vec![
+                    for_test::constrained_ast(),
+                    for_test::defaults_ast(),
+                    for_test::wire_name_ast(),
+                    for_test::tagged_ast(),
+                    for_test::generic_ref_ast(),
+                ],
+                imports: vec![],
+            }],
+        };
+        insta::assert_yaml_snapshot!(request);
+    }
+}
@@ -0,0 +1,344 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Component, Path, PathBuf},
+};
+
+use super::{
+    check_field_names, parse_naming, parse_string, parse_tabsize, typescript, LanguageBackend,
+};
+use crate::{
+    compiler::{
+        main::render_files,
+        types::{Module, OutputFile},
+        utils::sub_modules,
+    },
+    diagnostic::{Code, Diagnostic, Diagnostics},
+    generator::{
+        lang::python::{field_name, generate_python, order_definitions, TYPE_VARS},
+        types::{
+            ast_type_alias::{Node, RefNode, TypeAliasAst},
+            Import, Imports, AST, DEFAULT_TABSIZE,
+        },
+        utils::capitalize,
+    },
+    path::add_dot,
+    types::{
+        config::{Config, Naming, OutputPython, PythonModel},
+        spec::Spec,
+    },
+};
+
+pub const ID: &str = "python";
+
+#[derive(Debug, Default)]
+pub struct Python {
+    config: Option<OutputPython>,
+}
+
+impl LanguageBackend for Python {
+    fn id(&self) -> &str {
+        ID
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["py", "python"]
+    }
+
+    fn file_extension(&self) -> &str {
+        "py"
+    }
+
+    fn comment(&self) -> &str {
+        "#"
+    }
+
+    fn parse_config(&mut self, value: &serde_yaml::Value) -> Result<(), Diagnostic> {
+        let model = match value.get("model").map(|m| m.as_str()) {
+            None | Some(Some("dataclass")) => PythonModel::Dataclass,
+            Some(Some("pydantic")) => PythonModel::Pydantic,
+            Some(m) => {
+                return Err(Diagnostic::error(
+                    Code::Config,
+                    format!(
+                        "Invalid python model: {:?}, expected dataclass or pydantic.",
+                        m
+                    ),
+                )
+                .at(&[String::from("model")]));
+            }
+        };
+
+        self.config = Some(OutputPython {
+            output: parse_string(value, ID, "output")?,
+            tabsize: parse_tabsize(value, DEFAULT_TABSIZE.python)?,
+            model,
+            naming: parse_naming(value, ID, &Naming::ALL)?,
+        });
+        Ok(())
+    }
+
+    fn is_configured(&self) -> bool {
+        self.config.is_some()
+    }
+
+    fn output_dir(&self) -> &str {
+        &self
+            .config
+            .as_ref()
+            .expect("Maybe you want to generate Python code but forget to set it in config file.")
+            .output
+    }
+
+    /// Two properties must not be one attribute after `naming`.
+    fn check(&self, ast: &AST, path: &[String]) -> Diagnostics {
+        let naming = self.config.as_ref().and_then(|o| o.naming);
+        check_field_names(ast, path, ID, |p| field_name(p, naming))
+    }
+
+    fn render_type(&self, ast: &AST, imports: &mut Imports) -> Result<String, Diagnostic> {
+        match &self.config {
+            Some(o) => generate_python(ast, imports, o.tabsize, &o.model, o.naming),
+            None => generate_python(
+                ast,
+                imports,
+                DEFAULT_TABSIZE.python,
+                &PythonModel::Dataclass,
+                None,
+            ),
+        }
+    }
+
+    fn render_imports(
+        &self,
+        current: &str,
+        imports: &Imports,
+        config: &Config,
+    ) -> Result<String, Diagnostic> {
+        let mut result = String::new();
+        let mut froms: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let mut refs: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let mut type_vars: BTreeSet<&str> = BTreeSet::new();
+        for import in imports {
+            match import {
+                Import::Dyn(di) if di.from == TYPE_VARS => {
+                    type_vars.insert(&di.name);
+                    froms
+                        .entry(String::from("typing"))
+                        .or_default()
+                        .insert(String::from("TypeVar"));
+                }
+                Import::Dyn(di) => {
+                    froms
+                        .entry(di.from.clone())
+                        .or_default()
+                        .insert(di.name.clone());
+                }
+                Import::Ref(ri) => {
+                    let mut from = ri.from.clone();
+                    if !from.starts_with("/") && !from.starts_with(".") {
+                        from = add_dot(&from);
+                    }
+                    let f = process_from(current, &from, config)?;
+                    if !f.is_empty() {
+                        refs.entry(f).or_default().insert(ri.name.clone());
+                    }
+                }
+            };
+        }
+
+        let render = |m: &BTreeMap<String, BTreeSet<String>>| -> String {
+            m.iter()
+                .map(|(from, names)| {
+                    let names: Vec<&str> = names.iter().map(|n| n.as_str()).collect();
+                    format!("from {} import {}\n", from, names.join(", "))
+                })
+                .collect()
+        };
+
+        result += &render(&froms);
+        if !froms.is_empty() && !refs.is_empty() {
+            result += "\n";
+        }
+        result += &render(&refs);
+        if !type_vars.is_empty() {
+            result += "\n";
+        }
+        for name in type_vars {
+            result += &format!("{} = TypeVar(\"{}\")\n", name, name);
+        }
+        Ok(result)
+    }
+
+    fn preamble(
+        &self,
+        content: &str,
+        _spec: &Spec,
+        _config: &Config,
+    ) -> Result<String, Diagnostic> {
+        let mut result = String::from("from __future__ import annotations\n");
+        if content.starts_with("from ") {
+            result += "\n";
+        }
+        result += content;
+        Ok(result)
+    }
+
+    /// A alias of a generic union is not a generic class and can not be subscripted,
+    /// a concrete alias is declared for each set of args instead.
+    fn instantiates(&self, generic: &TypeAliasAst) -> bool {
+        let body = match &generic.body {
+            Node::Split(split) => split.nodes.get(ID).map(|n| n.as_ref()),
+            body => Some(body),
+        };
+        matches!(body, Some(Node::Union(_)))
+    }
+
+    /// Instances are added and definitions are reordered by `order_definitions`
+    /// before rendering.
+    fn compile(&self, config: &Config, modules: &[Module]) -> Result<Vec<OutputFile>, Diagnostics> {
+        let ordered: Vec<Module> = modules
+            .iter()
+            .map(|m| {
+                let ast_list = instantiate_module(m);
+                Module {
+                    spec: m.spec,
+                    ast_list: order_definitions(&ast_list).into_iter().cloned().collect(),
+                    instances: vec![],
+                }
+            })
+            .collect();
+        render_files(self, config, &ordered)
+    }
+
+    /// Create an empty `__init__.py` for each directory under selien-root,
+    /// so that the output is a regular python package.
+    fn extra_files(&self, modules: &[Module], config: &Config) -> Vec<OutputFile> {
+        sub_modules(modules, config)
+            .keys()
+            .map(|dir| OutputFile {
+                path: PathBuf::from(&config.spec.root)
+                    .join(dir)
+                    .join("__init__.yaml")
+                    .to_str()
+                    .unwrap()
+                    .to_string(),
+                content: String::new(),
+            })
+            .collect()
+    }
+}
+
+/// Definitions of `module` with the instances of unions and the ones they use,
+/// a `$ref` with args having one points to it.
+///
+/// `Module::instances` has every instance if another language `instantiates_generics`.
+/// A alias of a instance is replaced by it in place, the others are added.
+fn instantiate_module(module: &Module) -> Vec<AST> {
+    let find = |name: &str| {
+        module
+            .instances
+            .iter()
+            .find(|i| capitalize(i.identifier()) == name)
+    };
+    let is_union = |ast: &AST| matches!(ast.resolve_split(ID).as_deref(), Some(AST::TypeAlias(t)) if matches!(t.body, Node::Union(_)));
+
+    let mut needed: Vec<&AST> = module
+        .ast_list
+        .iter()
+        .filter_map(|ast| match ast {
+            AST::TypeAlias(t) if t.type_parameters.is_empty() => Some(t.body.generic_refs()),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|r| find(&r.instance_name()))
+        .filter(|i| is_union(i))
+        .collect();
+    // refs in a instance are to other instances.
+    let mut idx = 0;
+    while let Some(i) = needed.get(idx) {
+        idx += 1;
+        let mut used: Vec<&AST> = vec![];
+        if let AST::TypeAlias(t) = i {
+            t.body.walk(&[], &mut |node, _| {
+                if let Node::Ref(r) = node {
+                    used.extend(find(&capitalize(&r.name)).filter(|_| r.path.is_empty()));
+                }
+            });
+        }
+        for u in used {
+            if !needed.iter().any(|n| std::ptr::eq(*n, u)) {
+                needed.push(u);
+            }
+        }
+    }
+
+    let has_instance = |r: &RefNode| {
+        let name = r.instance_name();
+        needed.iter().any(|i| capitalize(i.identifier()) == name)
+    };
+    let is_alias = |i: &AST| {
+        module
+            .ast_list
+            .iter()
+            .any(|a| a.identifier() == i.identifier())
+    };
+    let mut ast_list: Vec<AST> = module
+        .ast_list
+        .iter()
+        .map(
+            |ast| match needed.iter().find(|i| i.identifier() == ast.identifier()) {
+                Some(i) => (*i).clone(),
+                None => match ast {
+                    AST::TypeAlias(t) => AST::TypeAlias(TypeAliasAst {
+                        body: t.body.instantiate_if(&has_instance),
+                        ..t.clone()
+                    }),
+                    _ => ast.clone(),
+                },
+            },
+        )
+        .collect();
+    ast_list.extend(needed.iter().filter(|i| !is_alias(i)).map(|i| (*i).clone()));
+    ast_list
+}
+
+/// `return` empty string if self import.
+fn process_from(current: &str, from: &Path, config: &Config) -> Result<String, Diagnostic> {
+    // selien $ref abs path must be start with `/`, even windows
+    if from.starts_with("/") {
+        // relative path is computed in the same way as typescript,
+        // then converted to a python relative module.
+        let ts_from = typescript::process_from(current, from, config)?;
+        return Ok(relative_module_or_self(&PathBuf::from(ts_from), current));
+    }
+    Ok(relative_module_or_self(from, current))
+}
+
+/// Convert a relative path (from current file dir) to a python relative module.
+///
+/// `e.g.` ./nested/nested -> .nested.nested, ../../test -> ...test
+pub fn to_relative_module(p: &Path) -> String {
+    let mut result = String::from(".");
+    let mut names: Vec<&str> = vec![];
+
+    for c in p.components() {
+        match c {
+            Component::ParentDir => result.push('.'),
+            Component::Normal(n) => names.push(n.to_str().unwrap()),
+            _ => {}
+        }
+    }
+
+    result + &names.join(".")
+}
+
+/// Same as `to_relative_module`, but return empty string when `p` points to current file.
+pub fn relative_module_or_self(p: &Path, current: &str) -> String {
+    let module = to_relative_module(p);
+    let cp = PathBuf::from(current);
+    let current_module = format!(".{}", cp.file_stem().unwrap().to_str().unwrap());
+    if module == current_module {
+        return String::new();
+    }
+    module
+}
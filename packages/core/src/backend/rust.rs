@@ -0,0 +1,348 @@
+use std::{
+    collections::{BTreeSet, HashSet},
+    path::{Component, Path, PathBuf},
+};
+
+use super::{check_field_names, parse_naming, parse_string, parse_tabsize, LanguageBackend};
+use crate::{
+    checker::{definition_path, module_path, value_cycle_refs},
+    compiler::{
+        main::render_files,
+        types::{Module, OutputFile},
+        utils::sub_modules,
+    },
+    diagnostic::{key_path, Code, Diagnostic, Diagnostics},
+    generator::{
+        lang::rust::{escape_keyword, field_name, generate_rust},
+        types::{
+            ast_enum,
+            ast_type_alias::{
+                Node, ObjectNode, Property, RefNode, SplitNode, TupleNode, TypeAliasAst, UnionNode,
+            },
+            Import, Imports, AST, DEFAULT_TABSIZE,
+        },
+    },
+    path::{diff_paths, normalize_path, to_relative},
+    types::config::{Config, Naming, OutputRust},
+};
+
+pub const ID: &str = "rust";
+
+#[derive(Debug, Default)]
+pub struct Rust {
+    config: Option<OutputRust>,
+}
+
+impl LanguageBackend for Rust {
+    fn id(&self) -> &str {
+        ID
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["rs", "rust"]
+    }
+
+    fn file_extension(&self) -> &str {
+        "rs"
+    }
+
+    fn parse_config(&mut self, value: &serde_yaml::Value) -> Result<(), Diagnostic> {
+        self.config = Some(OutputRust {
+            root: parse_string(value, ID, "root")?,
+            output: parse_string(value, ID, "output")?,
+            tabsize: parse_tabsize(value, DEFAULT_TABSIZE.rust)?,
+            naming: parse_naming(value, ID, &Naming::ALL)?,
+        });
+        Ok(())
+    }
+
+    fn is_configured(&self) -> bool {
+        self.config.is_some()
+    }
+
+    fn output_dir(&self) -> &str {
+        &self.output_config().output
+    }
+
+    /// Members of a number enum are the discriminants of a rust enum, which are integers.
+    /// Two properties must not be one field after `naming`.
+    fn check(&self, ast: &AST, path: &[String]) -> Diagnostics {
+        fn check_members(members: &ast_enum::Members, path: &[String]) -> Diagnostics {
+            members
+                .iter()
+                .filter(|m| m.value.parse::<i64>().is_err())
+                .map(|m| {
+                    Diagnostic::error(
+                        Code::InvalidEnum,
+                        format!(
+                            "Number enum member {} is {}, only integers can be a rust enum.",
+                            m.identifier, m.value
+                        ),
+                    )
+                    .at(path)
+                })
+                .collect()
+        }
+
+        let mut diagnostics: Diagnostics = vec![];
+        match ast {
+            AST::Enum(e) if matches!(e.r#type, ast_enum::MembersType::Number) => {
+                diagnostics.extend(check_members(&e.members, &key_path(path, "members")));
+            }
+            AST::Enum(_) => {}
+            AST::TypeAlias(t) => {
+                t.body.walk(path, &mut |node, path| {
+                    if let Node::Enum(e) = node {
+                        if matches!(e.r#type, ast_enum::MembersType::Number) {
+                            let members_path = key_path(path, "members");
+                            diagnostics.extend(check_members(&e.members, &members_path));
+                        }
+                    }
+                });
+            }
+        }
+        let naming = self.config.as_ref().and_then(|o| o.naming);
+        diagnostics.extend(check_field_names(ast, path, ID, |p| field_name(p, naming)));
+        diagnostics
+    }
+
+    fn render_type(&self, ast: &AST, imports: &mut Imports) -> Result<String, Diagnostic> {
+        match &self.config {
+            Some(o) => generate_rust(ast, imports, o.tabsize, o.naming),
+            None => generate_rust(ast, imports, DEFAULT_TABSIZE.rust, None),
+        }
+    }
+
+    fn render_imports(
+        &self,
+        current: &str,
+        imports: &Imports,
+        config: &Config,
+    ) -> Result<String, Diagnostic> {
+        // names by module, in the order modules are first used.
+        let mut uses: Vec<(String, BTreeSet<String>)> = vec![];
+        for import in imports {
+            let (from, name) = match import {
+                Import::Dyn(di) => (di.from.clone(), di.name.clone()),
+                Import::Ref(ri) => {
+                    let f = self.process_from(current, &ri.from, config)?;
+                    if f.is_empty() {
+                        continue;
+                    }
+                    (f, ri.name.clone())
+                }
+            };
+            match uses.iter_mut().find(|(f, _)| f == &from) {
+                Some((_, names)) => {
+                    names.insert(name);
+                }
+                None => uses.push((from, BTreeSet::from([name]))),
+            }
+        }
+
+        let lines: String = uses
+            .iter()
+            .map(|(from, names)| match names.len() {
+                1 => format!("use {}::{};\n", from, names.first().unwrap()),
+                _ => {
+                    let names: Vec<&str> = names.iter().map(|n| n.as_str()).collect();
+                    format!("use {}::{{{}}};\n", from, names.join(", "))
+                }
+            })
+            .collect();
+        Ok(lines)
+    }
+
+    /// A struct or enum holding itself inline has no size, every `$ref` on a cycle of
+    /// definitions is boxed.
+    fn compile(&self, config: &Config, modules: &[Module]) -> Result<Vec<OutputFile>, Diagnostics> {
+        let cycles = value_cycle_refs(config, modules, ID);
+        let modules: Vec<Module> = modules
+            .iter()
+            .map(|m| {
+                let Some(refs) = cycles.get(&module_path(&m.spec.path)) else {
+                    return Module {
+                        spec: m.spec,
+                        ast_list: m.ast_list.clone(),
+                        instances: m.instances.clone(),
+                    };
+                };
+                let ast_list = m
+                    .ast_list
+                    .iter()
+                    .map(|ast| match ast {
+                        AST::TypeAlias(t) => {
+                            let path = definition_path(m.spec, ast, Some(ID), &config.output);
+                            let body = match &t.body {
+                                Node::Split(split) => Node::Split(SplitNode {
+                                    nodes: split
+                                        .nodes
+                                        .iter()
+                                        .map(|(id, node)| match id.as_str() {
+                                            ID => (id.clone(), Box::new(boxed(node, &path, refs))),
+                                            _ => (id.clone(), node.clone()),
+                                        })
+                                        .collect(),
+                                }),
+                                body => boxed(body, &path, refs),
+                            };
+                            AST::TypeAlias(TypeAliasAst { body, ..t.clone() })
+                        }
+                        AST::Enum(_) => ast.clone(),
+                    })
+                    .collect();
+                Module {
+                    spec: m.spec,
+                    ast_list,
+                    instances: m.instances.clone(),
+                }
+            })
+            .collect();
+        render_files(self, config, &modules)
+    }
+
+    /// Rust needs a `mod.rs` declaring every sub module,
+    /// so we create one for each directory under selien-root.
+    ///
+    /// The returned path is `<selien-root>/<dir>/mod.yaml`,
+    /// extension will be replaced to `.rs` like other files.
+    fn extra_files(&self, modules: &[Module], config: &Config) -> Vec<OutputFile> {
+        sub_modules(modules, config)
+            .iter()
+            .map(|(dir, children)| OutputFile {
+                path: PathBuf::from(&config.spec.root)
+                    .join(dir)
+                    .join("mod.yaml")
+                    .to_str()
+                    .unwrap()
+                    .to_string(),
+                content: children
+                    .iter()
+                    .map(|c| format!("pub mod {};\n", escape_keyword(c)))
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+impl Rust {
+    fn output_config(&self) -> &OutputRust {
+        self.config
+            .as_ref()
+            .expect("Maybe you want to generate Rust code but forget to set it in config file.")
+    }
+
+    /// Convert a path relative to selien-root to a rust module path.
+    ///
+    /// `e.g.` If config is:
+    ///
+    /// ```yaml
+    /// output:
+    ///   rust:
+    ///     root: packages/server/src
+    ///     output: packages/server/src/api/selien_spec
+    /// ```
+    /// `nested/middle` will be `crate::api::selien_spec::nested::middle`
+    pub fn to_module_path(&self, p: &Path) -> String {
+        let o = self.output_config();
+
+        let root_to_output =
+            diff_paths(&o.output, &o.root).expect("Can not diff path from rust-root to output");
+
+        let mut result = vec![String::from("crate")];
+        for c in root_to_output.join(p).with_extension("").components() {
+            if let Component::Normal(n) = c {
+                result.push(escape_keyword(n.to_str().unwrap()));
+            }
+        }
+        result.join("::")
+    }
+
+    /// Module path of the spec file currently compiling.
+    pub fn current_module_path(&self, current: &str, s: &Config) -> String {
+        let cp = PathBuf::from(current);
+        let striped = cp
+            .strip_prefix(&s.spec.root)
+            .expect("Current file is not start with spec root in config.");
+        self.to_module_path(striped)
+    }
+
+    /// `return` empty string if self import.
+    fn process_from(
+        &self,
+        current: &str,
+        from: &Path,
+        config: &Config,
+    ) -> Result<String, Diagnostic> {
+        // selien $ref abs path must be start with `/`, even windows
+        let striped = if from.starts_with("/") {
+            to_relative(from)
+        } else {
+            let cp = PathBuf::from(current);
+            let joined = cp.parent().unwrap().join(from);
+            let normalized = normalize_path(&joined);
+
+            match normalized.strip_prefix(&config.spec.root) {
+                Ok(r) => r.to_path_buf(),
+                Err(err) => {
+                    return Err(Diagnostic::error(
+                        Code::InvalidRef,
+                        format!(
+                            "relative path {} out of selien-root: {}",
+                            from.to_str().unwrap(),
+                            err
+                        ),
+                    ));
+                }
+            }
+        };
+
+        let module = self.to_module_path(&striped);
+        if module == self.current_module_path(current, config) {
+            return Ok(String::new());
+        }
+        Ok(module)
+    }
+}
+
+/// `node` at `path` with the refs at `refs` boxed, the same walk as `checker::inline_refs`.
+/// `Box<T>` is a ref of `Box` with `T` as argument.
+fn boxed(node: &Node, path: &[String], refs: &HashSet<Vec<String>>) -> Node {
+    let at = |keys: &[&str]| keys.iter().fold(path.to_vec(), |p, key| key_path(&p, key));
+    match node {
+        Node::Ref(_) if refs.contains(path) => Node::Ref(RefNode {
+            name: String::from("Box"),
+            path: String::new(),
+            args: vec![node.clone()],
+        }),
+        Node::Object(o) => Node::Object(ObjectNode {
+            values: o
+                .values
+                .iter()
+                .map(|p| Property {
+                    body: boxed(&p.body, &at(&["properties", &p.identifier]), refs),
+                    ..p.clone()
+                })
+                .collect(),
+            ..o.clone()
+        }),
+        Node::Tuple(t) => Node::Tuple(TupleNode {
+            items: t
+                .items
+                .iter()
+                .enumerate()
+                .map(|(idx, item)| boxed(item, &at(&["items", &idx.to_string()]), refs))
+                .collect(),
+        }),
+        Node::Union(u) => Node::Union(UnionNode {
+            types: u
+                .types
+                .iter()
+                .enumerate()
+                .map(|(idx, t)| boxed(t, &at(&["types", &idx.to_string()]), refs))
+                .collect(),
+            ..u.clone()
+        }),
+        _ => node.clone(),
+    }
+}
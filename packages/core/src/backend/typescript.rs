@@ -0,0 +1,307 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use super::{
+    check_field_names, parse_bool, parse_naming, parse_string, parse_tabsize, LanguageBackend,
+};
+use crate::{
+    compiler::types::{Module, OutputFile},
+    diagnostic::{Code, Diagnostic, Diagnostics},
+    generator::{
+        lang::typescript::{format_type, generate_typescript, property_key},
+        types::{
+            ast_type_alias::{Node, StringFormat},
+            Import, Imports, AST, DEFAULT_TABSIZE,
+        },
+    },
+    path::{add_dot, diff_paths, to_relative},
+    types::config::{
+        Config, Int64Type, Naming, OutputTypescript, StringFormatType, TypescriptOptions,
+    },
+};
+
+pub const ID: &str = "typescript";
+
+/// File at the root of output declaring the types of string formats, see `format_type`.
+pub const FORMATS_MODULE: &str = "selien_formats";
+
+#[derive(Debug, Default)]
+pub struct TypeScript {
+    config: Option<OutputTypescript>,
+}
+
+impl LanguageBackend for TypeScript {
+    fn id(&self) -> &str {
+        ID
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        vec!["ts", "typescript"]
+    }
+
+    fn file_extension(&self) -> &str {
+        "ts"
+    }
+
+    fn parse_config(&mut self, value: &serde_yaml::Value) -> Result<(), Diagnostic> {
+        self.config = Some(OutputTypescript {
+            output: parse_string(value, ID, "output")?,
+            tabsize: parse_tabsize(value, DEFAULT_TABSIZE.typescript)?,
+            options: TypescriptOptions {
+                int64: parse_int64(value)?,
+                string_format: parse_string_format(value)?,
+                validate: parse_bool(value, "validate", false)?,
+                naming: parse_naming(value, ID, &Naming::ALL)?,
+            },
+        });
+        Ok(())
+    }
+
+    fn is_configured(&self) -> bool {
+        self.config.is_some()
+    }
+
+    fn output_dir(&self) -> &str {
+        &self
+            .config
+            .as_ref()
+            .expect("You may want to generate TypeScript code but forget to defiend it in config.")
+            .output
+    }
+
+    /// `<selien-root>/selien_formats.yaml`, if any string has a `format`.
+    fn extra_files(&self, modules: &[Module], config: &Config) -> Vec<OutputFile> {
+        let is_used = modules
+            .iter()
+            .flat_map(|m| m.ast_list.iter())
+            .any(|ast| match ast {
+                AST::TypeAlias(ast) => uses_string_format(&ast.body),
+                AST::Enum(_) => false,
+            });
+        if !is_used {
+            return vec![];
+        }
+
+        let branded = self
+            .config
+            .as_ref()
+            .is_some_and(|o| o.options.string_format == StringFormatType::Branded);
+        let content = StringFormat::ALL
+            .iter()
+            .map(|f| {
+                if branded {
+                    format!(
+                        "export type {} = string & {{ readonly __format: \"{}\" }};\n",
+                        format_type(*f),
+                        f.name()
+                    )
+                } else {
+                    format!("export type {} = string;\n", format_type(*f))
+                }
+            })
+            .collect();
+        vec![OutputFile {
+            path: PathBuf::from(&config.spec.root)
+                .join(format!("{}.yaml", FORMATS_MODULE))
+                .to_str()
+                .unwrap()
+                .to_string(),
+            content,
+        }]
+    }
+
+    /// Two properties must not be one key after `naming`.
+    fn check(&self, ast: &AST, path: &[String]) -> Diagnostics {
+        let default = TypescriptOptions::default();
+        let options = self.config.as_ref().map_or(&default, |o| &o.options);
+        check_field_names(ast, path, ID, |p| property_key(p, options))
+    }
+
+    fn render_type(&self, ast: &AST, imports: &mut Imports) -> Result<String, Diagnostic> {
+        match &self.config {
+            Some(o) => generate_typescript(ast, imports, o.tabsize, &o.options),
+            None => generate_typescript(
+                ast,
+                imports,
+                DEFAULT_TABSIZE.typescript,
+                &TypescriptOptions::default(),
+            ),
+        }
+    }
+
+    fn render_imports(
+        &self,
+        current: &str,
+        imports: &Imports,
+        config: &Config,
+    ) -> Result<String, Diagnostic> {
+        let mut result = String::new();
+        let r_imports = reduce_imports(imports);
+
+        for (idx, import) in r_imports.iter().enumerate() {
+            match import {
+                Import::Dyn(di) => {
+                    let mut s = format!("import {{ {} }} from \"{}\";\n", di.name, di.from,);
+                    let is_last = idx == r_imports.len() - 1;
+                    if is_last {
+                        s += "\n"
+                    }
+
+                    result += &s;
+                }
+                Import::Ref(ri) => {
+                    let mut s = format!(
+                        "import {{ {} }} from \"{}\";\n",
+                        ri.name,
+                        process_from(current, &ri.from, config)?
+                    );
+                    let is_last = idx == r_imports.len() - 1;
+                    if is_last {
+                        s += "\n"
+                    }
+
+                    result += &s;
+                }
+            };
+        }
+
+        Ok(result)
+    }
+}
+
+/// `output.ts.int64`, one of number, bigint or string.
+fn parse_int64(value: &serde_yaml::Value) -> Result<Int64Type, Diagnostic> {
+    match value.get("int64").map(|m| m.as_str()) {
+        None | Some(Some("number")) => Ok(Int64Type::Number),
+        Some(Some("bigint")) => Ok(Int64Type::Bigint),
+        Some(Some("string")) => Ok(Int64Type::String),
+        Some(m) => Err(Diagnostic::error(
+            Code::Config,
+            format!(
+                "Invalid typescript int64: {:?}, expected number, bigint or string.",
+                m
+            ),
+        )
+        .at(&[String::from("int64")])),
+    }
+}
+
+/// `output.ts.string_format`, alias or branded.
+fn parse_string_format(value: &serde_yaml::Value) -> Result<StringFormatType, Diagnostic> {
+    match value.get("string_format").map(|m| m.as_str()) {
+        None | Some(Some("alias")) => Ok(StringFormatType::Alias),
+        Some(Some("branded")) => Ok(StringFormatType::Branded),
+        Some(m) => Err(Diagnostic::error(
+            Code::Config,
+            format!(
+                "Invalid typescript string_format: {:?}, expected alias or branded.",
+                m
+            ),
+        )
+        .at(&[String::from("string_format")])),
+    }
+}
+
+/// Whether a node has a string with a `format` somewhere.
+fn uses_string_format(node: &Node) -> bool {
+    match node {
+        Node::Keyword(k) => k.string_format().is_some(),
+        Node::Array(n) => uses_string_format(&n.items),
+        Node::Object(n) => n.values.iter().any(|p| uses_string_format(&p.body)),
+        Node::Map(n) => uses_string_format(&n.keys) || uses_string_format(&n.values),
+        Node::Tuple(n) => n.items.iter().any(uses_string_format),
+        Node::Union(n) => n.types.iter().any(uses_string_format),
+        Node::Split(n) => n.nodes.get(ID).is_some_and(|n| uses_string_format(n)),
+        Node::Ref(n) => n.args.iter().any(uses_string_format),
+        Node::StringLiteral(_)
+        | Node::NumberLiteral(_)
+        | Node::Param(_)
+        | Node::Dyn(_)
+        | Node::Enum(_)
+        | Node::Empty => false,
+    }
+}
+
+/// Relative import path (without extension) from file `current` to `$ref` path `from`.
+///
+/// Also used by other languages importing by relative path, like python.
+pub fn process_from(current: &str, from: &Path, config: &Config) -> Result<String, Diagnostic> {
+    // selien $ref abs path must be start with `/`, even windows
+    if !from.starts_with("/") {
+        return Ok(from.to_str().unwrap().replace('\\', "/"));
+    }
+
+    let cp = PathBuf::from(current);
+    let cp_parent = cp.parent().unwrap();
+
+    // because from is absolute path from <selien-root>
+    // like /foo/bar, so add `<selien-root>/` to head
+    let f_parent = from.parent().unwrap();
+    let f_relative = PathBuf::from(&config.spec.root).join(to_relative(f_parent));
+
+    let diff = match diff_paths(&f_relative, cp_parent) {
+        Some(r) => r,
+        None => {
+            return Err(Diagnostic::error(
+                Code::InvalidRef,
+                format!(
+                    "Can not diff path {:?} to {:?}",
+                    &cp_parent.to_str().unwrap(),
+                    &f_relative.to_str().unwrap()
+                ),
+            ));
+        }
+    };
+
+    let result_path = add_dot(&diff.join(from.file_stem().unwrap()));
+
+    Ok(result_path.to_str().unwrap().replace('\\', "/"))
+}
+
+pub fn reduce_imports(ipts: &Imports) -> Imports {
+    let mut map: BTreeMap<String, Import> = BTreeMap::new();
+
+    for ipt in ipts {
+        match ipt {
+            Import::Dyn(di) => {
+                let entry = map.entry(di.from.clone()).or_insert_with(|| ipt.clone());
+                if let Import::Dyn(d) = entry {
+                    d.name = format!("{}, {}", d.name, di.name);
+                }
+            }
+            Import::Ref(ri) => {
+                let mut key = ri.from.to_str().unwrap().to_string();
+                let mut value = ri.clone();
+                let p = PathBuf::from(&ri.from);
+
+                if !p.starts_with("/") && !p.starts_with(".") && !p.starts_with("..") {
+                    key = add_dot(&p).to_str().unwrap().to_string();
+                    value.from = PathBuf::from(&key);
+                }
+                let entry = map.entry(key).or_insert_with(|| Import::Ref(value));
+                if let Import::Ref(r) = entry {
+                    r.name = format!("{}, {}", r.name, ri.name);
+                }
+            }
+        }
+    }
+
+    map.iter_mut().for_each(|(_, v)| {
+        if let Import::Dyn(d) = v {
+            d.name = remove_duplicate_import(&d.name);
+        }
+        if let Import::Ref(r) = v {
+            r.name = remove_duplicate_import(&r.name);
+        }
+    });
+
+    let result: Vec<Import> = map.into_values().collect();
+    result
+}
+
+fn remove_duplicate_import(s: &str) -> String {
+    let parts: BTreeSet<&str> = s.split(", ").collect();
+    parts.into_iter().collect::<Vec<&str>>().join(", ")
+}
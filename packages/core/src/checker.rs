@@ -0,0 +1,1602 @@
+//! Checks across spec files, used by `selien check`.
+//!
+//! The transformer only looks at one definition at a time, things like
+//! whether a `$ref` points to an existing definition are checked here.
+//! `check_extends` and `check_defaults` are also run by every command.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::backend::{
+    go::{self, instantiate_modules},
+    Backends, LanguageBackend,
+};
+use crate::compiler::types::Module;
+use crate::diagnostic::{key_path, Code, Diagnostic, Diagnostics};
+use crate::generator::{
+    lang::go::package_names,
+    types::{
+        ast_enum::MembersType,
+        ast_type_alias::{DefaultValue, Node, ObjectNode, RefNode, TypeAliasAst, UnionNode},
+        AST,
+    },
+    utils::capitalize,
+};
+use crate::path::normalize_path;
+use crate::types::{
+    config::Config,
+    spec::{Def, Spec, SpecList},
+};
+
+/// A `$ref` found in a definition body.
+struct Ref<'a> {
+    node: &'a RefNode,
+    /// Key path of the uri.
+    path: Vec<String>,
+    /// Backend id if the ref is in a split.
+    language: Option<&'a str>,
+    target: Target,
+}
+
+impl Ref<'_> {
+    /// As written in spec, `transformer` only lets this form through.
+    fn uri(&self) -> String {
+        format!("{}#/definition/{}", self.node.path, self.node.name)
+    }
+}
+
+/// What the referred definition must be.
+#[derive(Clone)]
+enum Target {
+    Any,
+    /// Used as `keys` of a map.
+    StringEnum,
+    /// Listed in `extends` of an object.
+    Base,
+    /// A type of a discriminated union, an object with `property: <value>` literal.
+    Variant {
+        property: String,
+        value: String,
+    },
+    /// Used by a property with a `default`, a enum with these members.
+    Members(Vec<String>),
+}
+
+/// Check references and identifiers of the transformed spec files.
+///
+/// `spec_list` is every spec file, refs to one which is not transformed are left
+/// to its own errors.
+pub fn checker(config: &Config, spec_list: &SpecList, modules: &[Module]) -> Diagnostics {
+    let mut modules: Vec<&Module> = modules.iter().collect();
+    modules.sort_by(|a, b| a.spec.path.cmp(&b.spec.path));
+
+    let mut diagnostics: Diagnostics = vec![];
+    check_refs(config, spec_list, &modules, &mut diagnostics);
+    let configured: Vec<&dyn LanguageBackend> = config.output.configured().collect();
+    diagnostics.extend(check_identifiers(config, &configured, &modules));
+    diagnostics
+}
+
+/// Defaults of properties in `modules` which are not members of the enum they refer to,
+/// the generated code would not compile. Run by every command, `all` is every
+/// transformed module.
+///
+/// Other problems of these refs are left to `checker`.
+pub fn check_defaults<'a>(
+    config: &Config,
+    modules: impl IntoIterator<Item = &'a Module<'a>>,
+    all: &[&Module],
+) -> Diagnostics {
+    let root = normalize_path(Path::new(&config.spec.root));
+    let transformed: HashMap<PathBuf, &Module> = all
+        .iter()
+        .map(|m| (module_path(&m.spec.path), *m))
+        .collect();
+
+    let mut diagnostics: Diagnostics = vec![];
+    for module in modules {
+        let spec = module.spec;
+        for r in refs_of(module, &config.output) {
+            if !matches!(r.target, Target::Members(_)) {
+                continue;
+            }
+            let ast = transformed
+                .get(&target_path(&r.node.path, spec, &root))
+                .and_then(|m| m.ast_list.iter().find(|a| a.identifier() == r.node.name));
+            let Some(ast) = ast else {
+                continue;
+            };
+            if let Err(d) = check_target(&r.target, ast, &r.node.name) {
+                diagnostics.push(d.at(&r.path).in_file(&spec.path).locate(&spec.source));
+            }
+        }
+    }
+    diagnostics
+}
+
+fn check_refs(
+    config: &Config,
+    spec_list: &SpecList,
+    modules: &[&Module],
+    diagnostics: &mut Diagnostics,
+) {
+    let root = normalize_path(Path::new(&config.spec.root));
+    let files: HashMap<PathBuf, &Spec> = spec_list
+        .iter()
+        .map(|s| (module_path(&s.path), s))
+        .collect();
+    let transformed: HashMap<PathBuf, &Module> = modules
+        .iter()
+        .map(|m| (module_path(&m.spec.path), *m))
+        .collect();
+    let languages: Vec<&str> = config.output.configured().map(|b| b.id()).collect();
+
+    for module in modules {
+        let spec = module.spec;
+        for r in refs_of(module, &config.output) {
+            match resolve(&r, spec, &root, &files, &transformed, &languages) {
+                Ok(Some(ast)) => {
+                    if let Some(d) = deprecated_ref(&r, module, ast) {
+                        diagnostics.push(d.in_file(&spec.path).locate(&spec.source));
+                    }
+                }
+                Ok(None) => {}
+                Err(d) => diagnostics.push(d.in_file(&spec.path).locate(&spec.source)),
+            }
+        }
+    }
+}
+
+/// Spec files `module` refers to with `$ref`, as `module_path`.
+///
+/// Whether they exist is reported by `checker`.
+pub fn dependencies(config: &Config, module: &Module) -> Vec<PathBuf> {
+    let root = normalize_path(Path::new(&config.spec.root));
+    refs_of(module, &config.output)
+        .iter()
+        .map(|r| target_path(&r.node.path, module.spec, &root))
+        .collect()
+}
+
+/// Key path of `ast` in `spec`, the definition of a enum or the body of a type alias.
+///
+/// A split body is under the key of `language` in it.
+pub fn definition_path(
+    spec: &Spec,
+    ast: &AST,
+    language: Option<&str>,
+    backends: &Backends,
+) -> Vec<String> {
+    let mut path = vec![String::from("definition"), ast.identifier().to_string()];
+    let AST::TypeAlias(t) = ast else {
+        return path;
+    };
+    path.push(String::from("body"));
+    if let (Node::Split(_), Some(language)) = (&t.body, language) {
+        let alias = spec.def.iter().find_map(|def| match def {
+            Def::TypeAlias(def) if def.identifier == t.identifier => def
+                .body
+                .keys()
+                .filter_map(|k| k.as_str())
+                .find(|k| backends.find(k).is_some_and(|b| b.id() == language)),
+            _ => None,
+        });
+        path.extend(alias.map(String::from));
+    }
+    path
+}
+
+fn refs_of<'a>(module: &'a Module, backends: &Backends) -> Vec<Ref<'a>> {
+    let mut refs: Vec<Ref> = vec![];
+    for ast in module.ast_list.iter() {
+        let AST::TypeAlias(t) = ast else {
+            continue;
+        };
+
+        match &t.body {
+            Node::Split(split) => {
+                for (language, node) in split.nodes.iter() {
+                    let path = definition_path(module.spec, ast, Some(language), backends);
+                    collect_refs(node, &path, Some(language), Target::Any, &mut refs);
+                }
+            }
+            body => {
+                let path = definition_path(module.spec, ast, None, backends);
+                collect_refs(body, &path, None, Target::Any, &mut refs);
+            }
+        }
+    }
+    refs
+}
+
+/// Refs in `node` at `path`, `target` is what a ref at `node` itself must be.
+fn collect_refs<'a>(
+    node: &'a Node,
+    path: &[String],
+    language: Option<&'a str>,
+    target: Target,
+    refs: &mut Vec<Ref<'a>>,
+) {
+    let at = |keys: &[&str]| keys.iter().fold(path.to_vec(), |p, key| key_path(&p, key));
+    match node {
+        Node::Ref(r) => {
+            refs.push(Ref {
+                node: r,
+                path: at(&["uri"]),
+                language,
+                target,
+            });
+            for (idx, arg) in r.args.iter().enumerate() {
+                let path = at(&["args", &idx.to_string()]);
+                collect_refs(arg, &path, language, Target::Any, refs);
+            }
+        }
+        Node::Object(o) => {
+            for (idx, base) in o.extends.iter().enumerate() {
+                refs.push(Ref {
+                    node: base,
+                    path: at(&["extends", &idx.to_string()]),
+                    language,
+                    target: Target::Base,
+                });
+            }
+            for p in o.values.iter() {
+                let target = match &p.default {
+                    None | Some(DefaultValue::Null) => Target::Any,
+                    Some(d) => Target::Members(default_members(d)),
+                };
+                let path = at(&["properties", &p.identifier]);
+                collect_refs(&p.body, &path, language, target, refs);
+            }
+        }
+        // the default of a array has the members of a enum in items.
+        Node::Array(a) => {
+            let target = match target {
+                Target::Members(_) => target,
+                _ => Target::Any,
+            };
+            collect_refs(&a.items, &at(&["items"]), language, target, refs);
+        }
+        Node::Map(m) => {
+            collect_refs(&m.keys, &at(&["keys"]), language, Target::StringEnum, refs);
+            collect_refs(&m.values, &at(&["values"]), language, Target::Any, refs);
+        }
+        Node::Tuple(t) => {
+            for (idx, item) in t.items.iter().enumerate() {
+                let path = at(&["items", &idx.to_string()]);
+                collect_refs(item, &path, language, Target::Any, refs);
+            }
+        }
+        Node::Union(UnionNode {
+            types,
+            discriminator,
+            ..
+        }) => {
+            for (idx, t) in types.iter().enumerate() {
+                let target = match discriminator {
+                    Some(d) => Target::Variant {
+                        property: d.property.clone(),
+                        value: d.values.get(idx).cloned().unwrap_or_default(),
+                    },
+                    None => Target::Any,
+                };
+                let path = at(&["types", &idx.to_string()]);
+                collect_refs(t, &path, language, target, refs);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The definition `r` points to, `None` if its file is not transformed.
+fn resolve<'a>(
+    r: &Ref,
+    spec: &Spec,
+    root: &Path,
+    files: &HashMap<PathBuf, &Spec>,
+    transformed: &HashMap<PathBuf, &'a Module>,
+    languages: &[&str],
+) -> Result<Option<&'a AST>, Diagnostic> {
+    let uri_path = &r.path;
+    let invalid = |message: String| Diagnostic::error(Code::InvalidRef, message).at(uri_path);
+    let name = &r.node.name;
+
+    let target_path = target_path(&r.node.path, spec, root);
+    if !target_path.starts_with(root) {
+        return Err(invalid(format!("$ref {} is out of selien-root.", r.uri())));
+    }
+
+    let target = files
+        .get(&target_path)
+        .ok_or_else(|| invalid(format!("$ref {} points to a missing file.", r.uri())))?;
+    let Some(module) = transformed.get(&target_path) else {
+        return Ok(None);
+    };
+    let ast = module
+        .ast_list
+        .iter()
+        .find(|ast| ast.identifier() == name)
+        .ok_or_else(|| invalid(format!("{} is not defined in {}.", name, target.path)))?;
+
+    let params = match ast {
+        AST::TypeAlias(t) => t.type_parameters.len(),
+        _ => 0,
+    };
+    if params != r.node.args.len() {
+        return Err(Diagnostic::error(
+            Code::Syntax,
+            format!(
+                "{} takes {} type arguments, {} given.",
+                name,
+                params,
+                r.node.args.len()
+            ),
+        )
+        .at(uri_path));
+    }
+
+    check_target(&r.target, ast, name).map_err(|d| d.at(uri_path))?;
+
+    // a split definition only exists in languages it is defined for,
+    // each of them is a definition of its own.
+    if let AST::TypeAlias(TypeAliasAst {
+        body: Node::Split(_),
+        ..
+    }) = ast
+    {
+        let defined: Vec<&String> = module
+            .ast_list
+            .iter()
+            .filter(|a| a.identifier() == name)
+            .filter_map(|a| match a {
+                AST::TypeAlias(TypeAliasAst {
+                    body: Node::Split(split),
+                    ..
+                }) => Some(split.nodes.keys()),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        let needed: Vec<&str> = match r.language {
+            Some(l) => vec![l],
+            None => languages.to_vec(),
+        };
+        if let Some(l) = needed.iter().find(|l| !defined.iter().any(|d| d == *l)) {
+            return Err(invalid(format!(
+                "{} is a split and not defined for {}.",
+                name, l
+            )));
+        }
+    }
+
+    Ok(Some(ast))
+}
+
+/// Whether `ast` named `name` is what a ref with `target` must point to,
+/// the error is not located yet.
+fn check_target(target: &Target, ast: &AST, name: &str) -> Result<(), Diagnostic> {
+    match target {
+        Target::Any => {}
+        Target::StringEnum => {
+            if !matches!(ast, AST::Enum(e) if matches!(e.r#type, MembersType::String)) {
+                return Err(Diagnostic::error(
+                    Code::Syntax,
+                    format!(
+                        "Map keys must be string, number or a $ref to a string enum, {} is not a string enum.",
+                        name
+                    ),
+                ));
+            }
+        }
+        Target::Base => {
+            if object_of(ast).is_none() {
+                return Err(Diagnostic::error(
+                    Code::Syntax,
+                    format!("{} must be a object to be extended.", name),
+                ));
+            }
+        }
+        Target::Variant { property, value } => {
+            check_variant(ast, name, property, value)
+                .map_err(|message| Diagnostic::error(Code::Syntax, message))?;
+        }
+        Target::Members(members) => {
+            let AST::Enum(e) = ast else {
+                return Err(Diagnostic::error(
+                    Code::Syntax,
+                    format!("{} must be a enum to have a default.", name),
+                ));
+            };
+            if let Some(m) = members
+                .iter()
+                .find(|m| !e.members.iter().any(|x| &x.identifier == *m))
+            {
+                return Err(Diagnostic::error(
+                    Code::Syntax,
+                    format!("default {} is not a member of {}.", m, name),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A warning if `r` in `module` points to a deprecated definition `ast`,
+/// unless the definition using it is deprecated too.
+fn deprecated_ref(r: &Ref, module: &Module, ast: &AST) -> Option<Diagnostic> {
+    let reason = ast.deprecated()?;
+    // `definition.<name>...`
+    let user = r.path.get(1)?;
+    if module
+        .ast_list
+        .iter()
+        .any(|a| a.identifier() == user && a.deprecated().is_some())
+    {
+        return None;
+    }
+
+    let message = if reason.trim().is_empty() {
+        format!("{} is deprecated.", ast.identifier())
+    } else {
+        format!("{} is deprecated: {}", ast.identifier(), reason.trim())
+    };
+    Some(Diagnostic::warning(Code::Deprecated, message).at(&r.path))
+}
+
+/// Property name conflicts of objects with `extends` in `spec`.
+///
+/// The properties of a base include the ones of its own bases. Bases which can
+/// not be resolved are left out, they are reported by `checker`.
+pub fn check_extends(config: &Config, spec_list: &SpecList, spec: &Spec) -> Diagnostics {
+    let root = normalize_path(Path::new(&config.spec.root));
+    let files: HashMap<PathBuf, &Spec> = spec_list
+        .iter()
+        .map(|s| (module_path(&s.path), s))
+        .collect();
+
+    let mut diagnostics: Diagnostics = vec![];
+    for def in spec.def.iter() {
+        let Def::TypeAlias(def) = def else {
+            continue;
+        };
+
+        let path = vec![
+            String::from("definition"),
+            def.identifier.clone(),
+            String::from("body"),
+        ];
+        let walker = ExtendsWalker {
+            spec,
+            root: &root,
+            files: &files,
+            identifier: &def.identifier,
+        };
+        walker.walk(&def.body, &path, &mut diagnostics);
+    }
+
+    diagnostics
+        .into_iter()
+        .map(|d| d.in_file(&spec.path).locate(&spec.source))
+        .collect()
+}
+
+struct ExtendsWalker<'a> {
+    spec: &'a Spec,
+    root: &'a Path,
+    files: &'a HashMap<PathBuf, &'a Spec>,
+    /// Definition being walked.
+    identifier: &'a str,
+}
+
+impl ExtendsWalker<'_> {
+    fn walk(&self, m: &serde_yaml::Mapping, path: &[String], diagnostics: &mut Diagnostics) {
+        if m.get("type").and_then(|t| t.as_str()) == Some("object") {
+            if let Some(bases) = m.get("extends").and_then(|e| e.as_sequence()) {
+                // only the root object of a definition can extend itself.
+                let is_root = path.len() == 3;
+                self.check_object(m, bases, path, is_root, diagnostics);
+            }
+        }
+
+        for (key, value) in m {
+            let Some(key) = key.as_str() else {
+                continue;
+            };
+            let path = key_path(path, key);
+            match value {
+                serde_yaml::Value::Mapping(m) => self.walk(m, &path, diagnostics),
+                serde_yaml::Value::Sequence(seq) => {
+                    for (idx, v) in seq.iter().enumerate() {
+                        if let Some(m) = v.as_mapping() {
+                            self.walk(m, &key_path(&path, &idx.to_string()), diagnostics);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn check_object(
+        &self,
+        m: &serde_yaml::Mapping,
+        bases: &serde_yaml::Sequence,
+        path: &[String],
+        is_root: bool,
+        diagnostics: &mut Diagnostics,
+    ) {
+        // property name => name of the base declaring it.
+        let mut inherited: HashMap<String, String> = HashMap::new();
+
+        for (idx, uri) in bases.iter().enumerate() {
+            let Some(uri) = uri.as_str() else {
+                continue;
+            };
+            let base_path = key_path(&key_path(path, "extends"), &idx.to_string());
+            let mut seen: Vec<(PathBuf, String)> = vec![];
+            if is_root {
+                seen.push((module_path(&self.spec.path), self.identifier.to_string()));
+            }
+
+            let properties = match base_properties(uri, self.spec, self.root, self.files, &mut seen)
+            {
+                Ok(p) => p,
+                Err(name) => {
+                    diagnostics.push(
+                        Diagnostic::error(Code::Syntax, format!("{} extends itself.", name))
+                            .at(&base_path),
+                    );
+                    continue;
+                }
+            };
+
+            let name = uri.split('/').next_back().unwrap();
+            for p in properties {
+                match inherited.get(&p) {
+                    Some(other) => diagnostics.push(
+                        Diagnostic::error(
+                            Code::Syntax,
+                            format!("Property {} is in both {} and {}.", p, other, name),
+                        )
+                        .at(&base_path),
+                    ),
+                    None => {
+                        inherited.insert(p, name.to_string());
+                    }
+                }
+            }
+        }
+
+        let own = m.get("properties").and_then(|p| p.as_mapping());
+        for key in own
+            .into_iter()
+            .flat_map(|p| p.keys())
+            .filter_map(|k| k.as_str())
+        {
+            if let Some(base) = inherited.get(key) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        Code::Syntax,
+                        format!("Property {} is also in {}.", key, base),
+                    )
+                    .at(&key_path(&key_path(path, "properties"), key)),
+                );
+            }
+        }
+    }
+}
+
+/// Property names of the object `uri` in `spec` refers to, including its bases.
+///
+/// `seen` are the objects being extended, `Err` is the name of a object
+/// extending itself.
+fn base_properties(
+    uri: &str,
+    spec: &Spec,
+    root: &Path,
+    files: &HashMap<PathBuf, &Spec>,
+    seen: &mut Vec<(PathBuf, String)>,
+) -> Result<Vec<String>, String> {
+    let Some((file, name)) = uri
+        .split_once('#')
+        .and_then(|(file, fragment)| Some((file, fragment.strip_prefix("/definition/")?)))
+    else {
+        return Ok(vec![]);
+    };
+    let target_path = target_path(file, spec, root);
+    let Some(target) = files.get(&target_path) else {
+        return Ok(vec![]);
+    };
+    let Some(body) = target
+        .def
+        .iter()
+        .find(|d| d.identifier() == name)
+        .and_then(object_body)
+    else {
+        return Ok(vec![]);
+    };
+
+    let key = (target_path, name.to_string());
+    if seen.contains(&key) {
+        return Err(name.to_string());
+    }
+    seen.push(key);
+
+    let mut result: Vec<String> = body
+        .get("properties")
+        .and_then(|p| p.as_mapping())
+        .into_iter()
+        .flat_map(|p| p.keys())
+        .filter_map(|k| k.as_str())
+        .map(|k| k.to_string())
+        .collect();
+    let bases = body.get("extends").and_then(|e| e.as_sequence());
+    for uri in bases.into_iter().flatten().filter_map(|u| u.as_str()) {
+        result.extend(base_properties(uri, target, root, files, seen)?);
+    }
+
+    seen.pop();
+    Ok(result)
+}
+
+/// `module_path` and name of a definition => the ones it holds by value,
+/// with the key path of the ref if it can be boxed.
+type ValueGraph = HashMap<(PathBuf, String), Vec<((PathBuf, String), Option<Vec<String>>)>>;
+
+/// Key paths of the `$ref`s of `modules` which are on a cycle of definitions
+/// holding each other by value in `language`, by `module_path` of their file.
+///
+/// Only arrays and maps hold a value out of line, as in rust where `Option` and enum
+/// variants are inline. A key path is as `definition_path` with `language`.
+pub fn value_cycle_refs(
+    config: &Config,
+    modules: &[Module],
+    language: &str,
+) -> HashMap<PathBuf, HashSet<Vec<String>>> {
+    let root = normalize_path(Path::new(&config.spec.root));
+
+    let mut graph: ValueGraph = HashMap::new();
+    for module in modules {
+        let file = module_path(&module.spec.path);
+        for ast in module.ast_list.iter() {
+            let Some(resolved) = ast.resolve_split(language) else {
+                continue;
+            };
+            let AST::TypeAlias(t) = resolved.as_ref() else {
+                continue;
+            };
+            let path = definition_path(module.spec, ast, Some(language), &config.output);
+            let mut refs: Vec<(&RefNode, Option<Vec<String>>)> = vec![];
+            inline_refs(&t.body, &path, &mut refs);
+            let edges = graph
+                .entry((file.clone(), t.identifier.clone()))
+                .or_default();
+            for (r, path) in refs {
+                let target = (target_path(&r.path, module.spec, &root), r.name.clone());
+                edges.push((target, path));
+            }
+        }
+    }
+
+    let mut result: HashMap<PathBuf, HashSet<Vec<String>>> = HashMap::new();
+    for (from, edges) in graph.iter() {
+        for (to, path) in edges {
+            let Some(path) = path else {
+                continue;
+            };
+            let mut seen: HashSet<&(PathBuf, String)> = HashSet::new();
+            if reaches(&graph, to, from, &mut seen) {
+                result
+                    .entry(from.0.clone())
+                    .or_default()
+                    .insert(path.clone());
+            }
+        }
+    }
+    result
+}
+
+/// Refs `node` at `path` holds by value, with the key path of the ones which can be
+/// boxed. A base of `extends` is flattened into the object and can not.
+fn inline_refs<'a>(
+    node: &'a Node,
+    path: &[String],
+    refs: &mut Vec<(&'a RefNode, Option<Vec<String>>)>,
+) {
+    let at = |keys: &[&str]| keys.iter().fold(path.to_vec(), |p, key| key_path(&p, key));
+    match node {
+        Node::Ref(r) => refs.push((r, Some(path.to_vec()))),
+        Node::Object(o) => {
+            refs.extend(o.extends.iter().map(|base| (base, None)));
+            for p in o.values.iter() {
+                inline_refs(&p.body, &at(&["properties", &p.identifier]), refs);
+            }
+        }
+        Node::Tuple(t) => {
+            for (idx, item) in t.items.iter().enumerate() {
+                inline_refs(item, &at(&["items", &idx.to_string()]), refs);
+            }
+        }
+        Node::Union(u) => {
+            for (idx, t) in u.types.iter().enumerate() {
+                inline_refs(t, &at(&["types", &idx.to_string()]), refs);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn reaches<'a>(
+    graph: &'a ValueGraph,
+    from: &'a (PathBuf, String),
+    to: &(PathBuf, String),
+    seen: &mut HashSet<&'a (PathBuf, String)>,
+) -> bool {
+    if from == to {
+        return true;
+    }
+    if !seen.insert(from) {
+        return false;
+    }
+    graph
+        .get(from)
+        .into_iter()
+        .flatten()
+        .any(|(next, _)| reaches(graph, next, to, seen))
+}
+
+/// Cycles of definitions containing each other by value in `spec`.
+///
+/// A property holding the definition it is in is a pointer in go, other cycles
+/// have no finite value in go and are errors. Rust boxes every ref on a cycle,
+/// see `value_cycle_refs`. Bases of `extends` are left to `check_extends`,
+/// refs which can not be resolved are reported by `checker`.
+pub fn check_cycles(config: &Config, spec_list: &SpecList, spec: &Spec) -> Diagnostics {
+    let root = normalize_path(Path::new(&config.spec.root));
+    let files: HashMap<PathBuf, &Spec> = spec_list
+        .iter()
+        .map(|s| (module_path(&s.path), s))
+        .collect();
+
+    let mut diagnostics: Diagnostics = vec![];
+    for def in spec.def.iter() {
+        let Def::TypeAlias(def) = def else {
+            continue;
+        };
+
+        let start = (module_path(&spec.path), def.identifier.clone());
+        let path = vec![
+            String::from("definition"),
+            def.identifier.clone(),
+            String::from("body"),
+        ];
+        let mut refs: Vec<ValueRef> = vec![];
+        value_refs(&def.body, &path, Via::Body, &mut refs);
+        let mut reported: Vec<Vec<(PathBuf, String)>> = vec![];
+        for r in refs {
+            let Some(target) = value_target(&r.uri, spec, &root, &files) else {
+                continue;
+            };
+            if target == start && r.direct {
+                continue;
+            }
+            let mut seen: Vec<(PathBuf, String)> = vec![];
+            if let Some(mut cycle) = find_cycle(&target, &start, &root, &files, &mut seen) {
+                cycle.insert(0, start.clone());
+                // a cycle is found from each definition in it,
+                // it is reported by the least one only and once.
+                if cycle.iter().min() != Some(&start) || reported.contains(&cycle) {
+                    continue;
+                }
+                let names: Vec<&str> = cycle.iter().map(|(_, name)| name.as_str()).collect();
+                reported.push(cycle.clone());
+                diagnostics.push(
+                    Diagnostic::error(
+                        Code::Syntax,
+                        format!(
+                            "Reference cycle {} can not be represented, make a property in it optional, nullable or a array.",
+                            names.join(" -> ")
+                        ),
+                    )
+                    .at(&r.path),
+                );
+            }
+        }
+    }
+
+    diagnostics
+        .into_iter()
+        .map(|d| d.in_file(&spec.path).locate(&spec.source))
+        .collect()
+}
+
+/// A `$ref` held by value, not through a array, a map, a union or a optional or nullable property.
+struct ValueRef {
+    uri: String,
+    /// Key path of the uri.
+    path: Vec<String>,
+    /// Only through properties of objects, a pointer in go if it is the definition itself.
+    direct: bool,
+}
+
+/// What a value is in, from the body of a definition.
+#[derive(Clone, Copy, PartialEq)]
+enum Via {
+    Body,
+    Properties,
+    /// A item of a tuple, which is a field of a hoisted struct in go.
+    Tuple,
+}
+
+fn value_refs(m: &serde_yaml::Mapping, path: &[String], via: Via, refs: &mut Vec<ValueRef>) {
+    let node_type = m.get("type").and_then(|t| t.as_str());
+    match node_type {
+        Some("$ref") => {
+            if let Some(uri) = m.get("uri").and_then(|u| u.as_str()) {
+                refs.push(ValueRef {
+                    uri: uri.to_string(),
+                    path: key_path(path, "uri"),
+                    direct: via == Via::Properties,
+                });
+            }
+        }
+        Some("object") => {
+            let path = key_path(path, "properties");
+            let properties = m.get("properties").and_then(|p| p.as_mapping());
+            for (key, value) in properties.into_iter().flatten() {
+                let (Some(key), Some(p)) = (key.as_str(), value.as_mapping()) else {
+                    continue;
+                };
+                let flag = |k: &str| p.get(k).and_then(|v| v.as_bool()).unwrap_or_default();
+                let via = match via {
+                    Via::Body => Via::Properties,
+                    _ => via,
+                };
+                if !flag("optional") && !flag("nullable") {
+                    value_refs(p, &key_path(&path, key), via, refs);
+                }
+            }
+        }
+        Some("tuple") => {
+            let path = key_path(path, "items");
+            let items = m.get("items").and_then(|i| i.as_sequence());
+            for (idx, item) in items.into_iter().flatten().enumerate() {
+                if let Some(item) = item.as_mapping() {
+                    value_refs(item, &key_path(&path, &idx.to_string()), Via::Tuple, refs);
+                }
+            }
+        }
+        Some("split") => {
+            for (key, value) in m {
+                if let (Some(key), Some(body)) = (key.as_str(), value.as_mapping()) {
+                    value_refs(body, &key_path(path, key), via, refs);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `module_path` and name of the type alias `uri` in `spec` refers to.
+fn value_target(
+    uri: &str,
+    spec: &Spec,
+    root: &Path,
+    files: &HashMap<PathBuf, &Spec>,
+) -> Option<(PathBuf, String)> {
+    let (file, name) = uri
+        .split_once('#')
+        .and_then(|(file, fragment)| Some((file, fragment.strip_prefix("/definition/")?)))?;
+    let target_path = target_path(file, spec, root);
+    let target = files.get(&target_path)?;
+    target
+        .def
+        .iter()
+        .any(|d| matches!(d, Def::TypeAlias(d) if d.identifier == name))
+        .then(|| (target_path, name.to_string()))
+}
+
+/// Definitions from `from` to `to` by value refs, `to` included.
+///
+/// `seen` are the definitions on the way.
+fn find_cycle(
+    from: &(PathBuf, String),
+    to: &(PathBuf, String),
+    root: &Path,
+    files: &HashMap<PathBuf, &Spec>,
+    seen: &mut Vec<(PathBuf, String)>,
+) -> Option<Vec<(PathBuf, String)>> {
+    if from == to {
+        return Some(vec![to.clone()]);
+    }
+    if seen.contains(from) {
+        return None;
+    }
+    let spec = files.get(&from.0)?;
+    let def = spec.def.iter().find_map(|d| match d {
+        Def::TypeAlias(d) if d.identifier == from.1 => Some(d),
+        _ => None,
+    })?;
+    seen.push(from.clone());
+
+    let mut refs: Vec<ValueRef> = vec![];
+    value_refs(&def.body, &[], Via::Body, &mut refs);
+    for r in refs {
+        let Some(target) = value_target(&r.uri, spec, root, files) else {
+            continue;
+        };
+        // a pointer in go.
+        if &target == from && r.direct {
+            continue;
+        }
+        if let Some(mut cycle) = find_cycle(&target, to, root, files, seen) {
+            cycle.insert(0, from.clone());
+            return Some(cycle);
+        }
+    }
+    None
+}
+
+/// `name` must be an object with `property` of `type: literal` and `value: <value>`.
+fn check_variant(ast: &AST, name: &str, property: &str, value: &str) -> Result<(), String> {
+    let Some(object) = object_of(ast) else {
+        return Err(format!(
+            "{} must be a object to be a type of a discriminated union.",
+            name
+        ));
+    };
+
+    let literal = object
+        .values
+        .iter()
+        .find(|p| p.identifier == property)
+        .and_then(|p| match &p.body {
+            Node::StringLiteral(l) => Some(&l.value),
+            _ => None,
+        });
+    match literal {
+        Some(v) if v == value => Ok(()),
+        Some(v) => Err(format!(
+            "{}.{} is {}, expected {} as the discriminator value.",
+            name, property, v, value
+        )),
+        None => Err(format!(
+            "{} must have a string literal property {} as the discriminator.",
+            name, property
+        )),
+    }
+}
+
+/// Names of enum members in the `default` of a property.
+fn default_members(d: &DefaultValue) -> Vec<String> {
+    match d {
+        DefaultValue::Member(m) => vec![m.clone()],
+        DefaultValue::Array(items) => items.iter().flat_map(default_members).collect(),
+        _ => vec![],
+    }
+}
+
+/// Body of a object definition.
+fn object_of(ast: &AST) -> Option<&ObjectNode> {
+    match ast {
+        AST::TypeAlias(t) => match &t.body {
+            Node::Object(o) => Some(o),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Body of a `type: object` definition.
+fn object_body(def: &Def) -> Option<&serde_yaml::Mapping> {
+    match def {
+        Def::TypeAlias(def) if def.body.get("type").and_then(|t| t.as_str()) == Some("object") => {
+            Some(&def.body)
+        }
+        _ => None,
+    }
+}
+
+/// `module_path` of the file part of a `$ref` uri in `spec`.
+pub(crate) fn target_path(file: &str, spec: &Spec, root: &Path) -> PathBuf {
+    // selien $ref abs path must be start with `/`, even windows
+    if file.is_empty() {
+        module_path(&spec.path)
+    } else if let Some(abs) = file.strip_prefix('/') {
+        normalize_path(&root.join(abs))
+    } else {
+        let current = PathBuf::from(&spec.path);
+        normalize_path(&current.parent().unwrap().join(file))
+    }
+}
+
+/// Normalized path without extension, as `$ref` paths do not have one.
+pub fn module_path(p: &str) -> PathBuf {
+    normalize_path(Path::new(p)).with_extension("")
+}
+
+/// Identifiers declared twice in the generated code of `backends`, also run by `gen`
+/// as a clash breaks the output.
+pub fn check_identifiers(
+    config: &Config,
+    backends: &[&dyn LanguageBackend],
+    modules: &[&Module],
+) -> Diagnostics {
+    let mut modules = modules.to_vec();
+    modules.sort_by(|a, b| a.spec.path.cmp(&b.spec.path));
+
+    let mut diagnostics: Diagnostics = vec![];
+    // names only different in the first letter are the same in languages capitalizing them.
+    for module in modules.iter() {
+        let mut seen: HashMap<String, &str> = HashMap::new();
+        for ast in module.ast_list.iter() {
+            let id = ast.identifier();
+            // a split is a definition for each language.
+            if let Some(other) = seen.insert(capitalize(id), id).filter(|other| *other != id) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        Code::DuplicateIdentifier,
+                        format!(
+                            "{} and {} are the same identifier in generated code.",
+                            id, other
+                        ),
+                    )
+                    .in_file(&module.spec.path)
+                    .at(&[String::from("definition"), id.to_string()])
+                    .locate(&module.spec.source),
+                );
+            }
+        }
+    }
+
+    // go puts every file of a directory into one package,
+    // and enum members are constants of the package.
+    let Some(backend) = backends.iter().find(|b| b.id() == go::ID) else {
+        return diagnostics;
+    };
+    let instantiates = backend.instantiates_generics();
+
+    // definitions as go renders them.
+    let instantiated = if instantiates {
+        instantiate_modules(&modules)
+    } else {
+        vec![]
+    };
+
+    let mut packages: HashMap<PathBuf, HashMap<String, (&Spec, Vec<String>)>> = HashMap::new();
+    for (idx, module) in modules.iter().enumerate() {
+        let spec = module.spec;
+        let package = PathBuf::from(&spec.path).parent().unwrap().to_path_buf();
+        let names = packages.entry(package.clone()).or_default();
+
+        let ast_list = match instantiated.get(idx) {
+            Some(m) => &m.ast_list,
+            None => &module.ast_list,
+        };
+        for ast in ast_list.iter() {
+            let Some(resolved) = ast.resolve_split(go::ID) else {
+                continue;
+            };
+            let id = ast.identifier();
+            let def_path = vec![String::from("definition"), id.to_string()];
+            let is_definition = spec.def.iter().any(|d| d.identifier() == id);
+            let is_instance = instantiates && module.instances.iter().any(|i| i.identifier() == id);
+
+            let mut declared: Vec<(String, Vec<String>)> = vec![(
+                capitalize(id),
+                if is_definition {
+                    def_path.clone()
+                } else {
+                    vec![]
+                },
+            )];
+            let path = definition_path(spec, ast, Some(go::ID), &config.output);
+            // a instance is not in the spec file, the alias it replaces is if any.
+            declared.extend(package_names(&resolved, &path).into_iter().map(
+                |(name, path)| match (is_instance, is_definition) {
+                    (false, _) => (name, path),
+                    (true, true) => (name, def_path.clone()),
+                    (true, false) => (name, vec![]),
+                },
+            ));
+
+            for (name, path) in declared {
+                match names.get(&name) {
+                    Some((other, other_path)) => {
+                        // definitions in the same file are reported above.
+                        if other.path == spec.path && other_path.len() == 2 && path.len() == 2 {
+                            continue;
+                        }
+                        let at = if other_path.is_empty() {
+                            other.path.clone()
+                        } else {
+                            format!("{} ({})", other.path, other_path.join("."))
+                        };
+                        diagnostics.push(
+                            Diagnostic::error(
+                                Code::DuplicateIdentifier,
+                                format!(
+                                    "{} is declared twice in go package {}, also at {}.",
+                                    name,
+                                    package.display(),
+                                    at
+                                ),
+                            )
+                            .in_file(&spec.path)
+                            .at(&path)
+                            .locate(&spec.source),
+                        );
+                    }
+                    None => {
+                        names.insert(name, (spec, path));
+                    }
+                }
+            }
+        }
+    }
+    diagnostics
+}
+
+// unit test here
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::prepare::parse_spec;
+    use crate::transformer::{generics::instances, main::transformer};
+    use crate::types::config;
+
+    fn config() -> Config {
+        Config {
+            spec: config::Spec {
+                root: String::from("spec"),
+            },
+            output: Backends::default(),
+        }
+    }
+
+    /// Config with go in `output`, `generics` as given.
+    fn go_config(generics: bool) -> Config {
+        let mut output = Backends::builtin();
+        let value: serde_yaml::Value = serde_yaml::from_str(&format!(
+            "mod_name: example.com/app\nroot: dist/go\noutput: dist/go\ngenerics: {}\n",
+            generics
+        ))
+        .unwrap();
+        output
+            .find_mut(go::ID)
+            .unwrap()
+            .parse_config(&value)
+            .unwrap();
+        Config {
+            spec: config::Spec {
+                root: String::from("spec"),
+            },
+            output,
+        }
+    }
+
+    fn spec(path: &str, source: &str) -> Spec {
+        let mut diagnostics: Diagnostics = vec![];
+        let spec = parse_spec(path, source, &mut diagnostics).unwrap();
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        spec
+    }
+
+    /// Messages of `checker` for `specs`, every one of them must transform.
+    fn check(config: &Config, specs: &SpecList) -> Vec<String> {
+        let modules: Vec<Module> = specs
+            .iter()
+            .map(|spec| {
+                let ast_list = transformer(spec, &config.output).unwrap();
+                let instances = instances(config, specs, spec, &ast_list).unwrap();
+                Module {
+                    spec,
+                    ast_list,
+                    instances,
+                }
+            })
+            .collect();
+        checker(config, specs, &modules)
+            .into_iter()
+            .map(|d| format!("{} ({})", d.message, d.key_path.join(".")))
+            .collect()
+    }
+
+    /// Messages of `check_cycles` for every spec of `specs`.
+    fn cycles(specs: SpecList) -> Vec<String> {
+        let config = config();
+        specs
+            .iter()
+            .flat_map(|s| check_cycles(&config, &specs, s))
+            .map(|d| d.message)
+            .collect()
+    }
+
+    #[test]
+    fn self_ref() {
+        let node = spec(
+            "spec/node.yaml",
+            "selien-version: 0.1.0
+definition:
+  node:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        parent:
+          type: $ref
+          uri: \"#/definition/node\"
+  list:
+    declaration: type-alias
+    body:
+      type: tuple
+      items:
+        - type: $ref
+          uri: \"#/definition/list\"
+",
+        );
+        let messages = cycles(vec![node]);
+        assert_eq!(
+            messages,
+            vec!["Reference cycle list -> list can not be represented, make a property in it optional, nullable or a array."]
+        );
+    }
+
+    #[test]
+    fn mutual_ref_reported_once() {
+        let a = spec(
+            "spec/a.yaml",
+            "selien-version: 0.1.0
+definition:
+  a:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        b:
+          type: $ref
+          uri: \"./b#/definition/b\"
+        again:
+          type: $ref
+          uri: \"./b#/definition/b\"
+",
+        );
+        let b = spec(
+            "spec/b.yaml",
+            "selien-version: 0.1.0
+definition:
+  b:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        a:
+          type: $ref
+          uri: \"./a#/definition/a\"
+",
+        );
+        let messages = cycles(vec![a, b]);
+        assert_eq!(
+            messages,
+            vec!["Reference cycle a -> b -> a can not be represented, make a property in it optional, nullable or a array."]
+        );
+    }
+
+    #[test]
+    fn optional_breaks_cycle() {
+        let a = spec(
+            "spec/a.yaml",
+            "selien-version: 0.1.0
+definition:
+  a:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        b:
+          type: $ref
+          uri: \"#/definition/b\"
+  b:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        a:
+          type: $ref
+          uri: \"#/definition/a\"
+          optional: true
+",
+        );
+        assert!(cycles(vec![a]).is_empty());
+    }
+
+    const USER: &str = "selien-version: 0.1.0
+definition:
+  user:
+    declaration: type-alias
+    deprecated: use member
+    body:
+      type: object
+      properties:
+        kind:
+          type: literal
+          value: user
+  role:
+    declaration: enum
+    type: string
+    members:
+      - admin: admin
+      - guest: guest
+  page:
+    declaration: type-alias
+    typeParameters:
+      - T
+    body:
+      type: object
+      properties:
+        items:
+          type: array
+          items:
+            type: param
+            name: T
+";
+
+    #[test]
+    fn refs() {
+        let user = spec("spec/user.yaml", USER);
+        let a = spec(
+            "spec/a.yaml",
+            "selien-version: 0.1.0
+definition:
+  a:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        missingFile:
+          type: $ref
+          uri: \"./none#/definition/user\"
+        undefined:
+          type: $ref
+          uri: \"./user#/definition/none\"
+        args:
+          type: $ref
+          uri: \"./user#/definition/page\"
+        keys:
+          type: map
+          keys:
+            type: $ref
+            uri: \"./user#/definition/page\"
+            args:
+              - type: string
+          values:
+            type: string
+        role:
+          type: $ref
+          uri: \"./user#/definition/role\"
+          default: owner
+        pet:
+          type: union
+          discriminator: kind
+          types:
+            - type: $ref
+              uri: \"./user#/definition/user\"
+              value: member
+",
+        );
+        assert_eq!(
+            check(&config(), &vec![user, a]),
+            vec![
+                "$ref ./none#/definition/user points to a missing file. (definition.a.body.properties.missingFile.uri)",
+                "none is not defined in spec/user.yaml. (definition.a.body.properties.undefined.uri)",
+                "page takes 1 type arguments, 0 given. (definition.a.body.properties.args.uri)",
+                "Map keys must be string, number or a $ref to a string enum, page is not a string enum. (definition.a.body.properties.keys.keys.uri)",
+                "default owner is not a member of role. (definition.a.body.properties.role.uri)",
+                "user.kind is user, expected member as the discriminator value. (definition.a.body.properties.pet.types.0.uri)",
+            ]
+        );
+    }
+
+    #[test]
+    fn deprecated_refs() {
+        let user = spec("spec/user.yaml", USER);
+        let a = spec(
+            "spec/a.yaml",
+            "selien-version: 0.1.0
+definition:
+  a:
+    declaration: type-alias
+    body:
+      type: $ref
+      uri: \"./user#/definition/user\"
+  old:
+    declaration: type-alias
+    deprecated: \"\"
+    body:
+      type: $ref
+      uri: \"./user#/definition/user\"
+",
+        );
+        assert_eq!(
+            check(&config(), &vec![user, a]),
+            vec!["user is deprecated: use member (definition.a.body.uri)"]
+        );
+    }
+
+    #[test]
+    fn go_hoisted_names() {
+        let a = spec(
+            "spec/a.yaml",
+            "selien-version: 0.1.0
+definition:
+  owner:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        pet:
+          type: union
+          discriminator: kind
+          types:
+            - type: $ref
+              uri: \"#/definition/cat\"
+        state:
+          type: enum
+          members:
+            - active: active
+        pos:
+          type: tuple
+          items:
+            - type: number
+  cat:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        kind:
+          type: literal
+          value: cat
+  point:
+    declaration: type-alias
+    body:
+      type: tuple
+      items:
+        - type: number
+",
+        );
+        let b = spec(
+            "spec/b.yaml",
+            "selien-version: 0.1.0
+definition:
+  ownerPet:
+    declaration: type-alias
+    body:
+      type: string
+  ownerState:
+    declaration: type-alias
+    body:
+      type: string
+  ownerStateActive:
+    declaration: type-alias
+    body:
+      type: string
+  ownerPos:
+    declaration: type-alias
+    body:
+      type: string
+  point:
+    declaration: type-alias
+    body:
+      type: string
+",
+        );
+        assert_eq!(
+            check(&go_config(true), &vec![a, b]),
+            vec![
+                "OwnerPet is declared twice in go package spec, also at spec/a.yaml (definition.owner.body.properties.pet). (definition.ownerPet)",
+                "OwnerState is declared twice in go package spec, also at spec/a.yaml (definition.owner.body.properties.state). (definition.ownerState)",
+                "OwnerStateActive is declared twice in go package spec, also at spec/a.yaml (definition.owner.body.properties.state.members.0.active). (definition.ownerStateActive)",
+                "OwnerPos is declared twice in go package spec, also at spec/a.yaml (definition.owner.body.properties.pos). (definition.ownerPos)",
+                "Point is declared twice in go package spec, also at spec/a.yaml (definition.point). (definition.point)",
+            ]
+        );
+        // only go has one namespace for every file of a directory.
+        assert!(check(&config(), &vec![spec("spec/b.yaml", USER)]).is_empty());
+    }
+
+    #[test]
+    fn go_instance_names() {
+        const A: &str = "selien-version: 0.1.0
+definition:
+  users:
+    declaration: type-alias
+    body:
+      type: $ref
+      uri: \"./user#/definition/page\"
+      args:
+        - type: $ref
+          uri: \"./user#/definition/role\"
+";
+        const B: &str = "selien-version: 0.1.0
+definition:
+  pageRole:
+    declaration: type-alias
+    body:
+      type: string
+";
+        let specs = || {
+            vec![
+                spec("spec/user.yaml", USER),
+                spec("spec/a.yaml", A),
+                spec("spec/b.yaml", B),
+            ]
+        };
+        assert_eq!(
+            check(&go_config(false), &specs()),
+            vec!["PageRole is declared twice in go package spec, also at spec/a.yaml. (definition.pageRole)"]
+        );
+        // a generic type in go, no instance is declared.
+        assert!(check(&go_config(true), &specs()).is_empty());
+    }
+
+    #[test]
+    fn go_shared_instances() {
+        const A: &str = "selien-version: 0.1.0
+definition:
+  users:
+    declaration: type-alias
+    body:
+      type: $ref
+      uri: \"./user#/definition/page\"
+      args:
+        - type: $ref
+          uri: \"./user#/definition/role\"
+";
+        let specs = vec![
+            spec("spec/user.yaml", USER),
+            spec("spec/a.yaml", A),
+            spec("spec/b.yaml", &A.replace("users", "admins")),
+        ];
+        // one instance for the package.
+        assert!(check(&go_config(false), &specs).is_empty());
+    }
+
+    #[test]
+    fn split_refs() {
+        let a = spec(
+            "spec/a.yaml",
+            "selien-version: 0.1.0
+definition:
+  both:
+    declaration: type-alias
+    body:
+      type: split
+      ts:
+        type: string
+      go:
+        type: string
+  tsOnly:
+    declaration: type-alias
+    body:
+      type: split
+      ts:
+        type: string
+  user:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        both:
+          type: $ref
+          uri: \"#/definition/both\"
+        tsOnly:
+          type: $ref
+          uri: \"#/definition/tsOnly\"
+",
+        );
+        assert_eq!(
+            check(&go_config(true), &vec![a]),
+            vec!["tsOnly is a split and not defined for go. (definition.user.body.properties.tsOnly.uri)"]
+        );
+    }
+}
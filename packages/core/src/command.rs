@@ -0,0 +1,445 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use log::{error, info};
+
+use crate::backend::{Backends, LanguageBackend};
+use crate::checker::{
+    check_cycles, check_defaults, check_extends, check_identifiers, checker, definition_path,
+    dependencies, module_path,
+};
+use crate::compiler::{
+    main::{compiler, drift_checker, partial_compiler},
+    types::Module,
+};
+use crate::diagnostic::{self, Code, Diagnostic, Diagnostics};
+use crate::prepare;
+use crate::transformer;
+use crate::types::{
+    cli::{CheckArgs, GenArgs},
+    config::Config,
+    spec::{Spec, SpecList},
+};
+
+/// `selien gen`, return every problem found.
+///
+/// Nothing is written if any spec file has an error, or with `--check`.
+pub fn gen(args: &GenArgs, backends: Backends) -> Diagnostics {
+    let (config, spec_list, mut diagnostics) = match prepare::prepare(&args.config, backends) {
+        Ok(r) => r,
+        Err(d) => return d,
+    };
+
+    let modules = transform(&config, &spec_list, &spec_list, &mut diagnostics);
+
+    let targets = targets(args, &config, &mut diagnostics);
+    diagnostics.extend(check_languages(&config, &targets, &modules));
+    let all: Vec<&Module> = modules.iter().collect();
+    diagnostics.extend(check_defaults(&config, all.iter().copied(), &all));
+    diagnostics.extend(check_identifiers(&config, &targets, &all));
+
+    if diagnostic::has_error(&diagnostics) {
+        return diagnostics;
+    }
+
+    for backend in targets {
+        let result = if args.check {
+            drift_checker(backend, &config, &modules)
+        } else {
+            compiler(backend, &config, &modules)
+        };
+        if let Err(d) = result {
+            diagnostics.extend(d);
+        }
+    }
+
+    diagnostics
+}
+
+/// `selien check`, everything `gen` does for every configured language
+/// and the checks across spec files, but nothing is written.
+pub fn check(args: &CheckArgs, backends: Backends) -> Diagnostics {
+    let (config, spec_list, mut diagnostics) = match prepare::prepare(&args.config, backends) {
+        Ok(r) => r,
+        Err(d) => return d,
+    };
+
+    let modules = transform(&config, &spec_list, &spec_list, &mut diagnostics);
+    let configured: Vec<&dyn LanguageBackend> = config.output.configured().collect();
+    diagnostics.extend(check_languages(&config, &configured, &modules));
+    diagnostics.extend(checker(&config, &spec_list, &modules));
+
+    if diagnostic::has_error(&diagnostics) {
+        return diagnostics;
+    }
+
+    for backend in config.output.configured() {
+        if let Err(d) = backend.compile(&config, &modules) {
+            diagnostics.extend(d);
+        }
+    }
+
+    diagnostics
+}
+
+/// Languages to generate, the one of `-o` or every configured one.
+fn targets<'a>(
+    args: &GenArgs,
+    config: &'a Config,
+    diagnostics: &mut Diagnostics,
+) -> Vec<&'a dyn LanguageBackend> {
+    match &args.output {
+        Some(output) => match config.output.find(output) {
+            Some(b) if b.is_configured() => vec![b],
+            Some(b) => {
+                diagnostics.push(Diagnostic::error(
+                    Code::Config,
+                    format!("{} is not defined in config output.", b.id()),
+                ));
+                vec![]
+            }
+            None => {
+                diagnostics.push(Diagnostic::error(
+                    Code::Config,
+                    format!("Unsupported language or alias: {}.", output),
+                ));
+                vec![]
+            }
+        },
+        None => config.output.configured().collect(),
+    }
+}
+
+/// `LanguageBackend::check` of every definition in `modules` for each of `backends`.
+fn check_languages<'a>(
+    config: &Config,
+    backends: &[&dyn LanguageBackend],
+    modules: impl IntoIterator<Item = &'a Module<'a>>,
+) -> Diagnostics {
+    let mut diagnostics: Diagnostics = vec![];
+    for module in modules {
+        let spec = module.spec;
+        for backend in backends {
+            for ast in module.ast_list.iter() {
+                let Some(resolved) = ast.resolve_split(backend.id()) else {
+                    continue;
+                };
+                let path = definition_path(spec, ast, Some(backend.id()), &config.output);
+                diagnostics.extend(
+                    backend
+                        .check(&resolved, &path)
+                        .into_iter()
+                        .map(|d| d.in_file(&spec.path).locate(&spec.source)),
+                );
+            }
+        }
+    }
+    diagnostics
+}
+
+/// Transform `specs`, `spec_list` is every spec file for looking up bases of `extends`
+/// and generic definitions.
+fn transform<'a>(
+    config: &Config,
+    spec_list: &SpecList,
+    specs: impl IntoIterator<Item = &'a Spec>,
+    diagnostics: &mut Diagnostics,
+) -> Vec<Module<'a>> {
+    let mut modules: Vec<Module> = vec![];
+    for spec in specs {
+        diagnostics.extend(check_extends(config, spec_list, spec));
+        diagnostics.extend(check_cycles(config, spec_list, spec));
+        match transformer::main::transformer(spec, &config.output) {
+            Ok(ast_list) => {
+                let instances =
+                    transformer::generics::instances(config, spec_list, spec, &ast_list)
+                        .unwrap_or_else(|d| {
+                            diagnostics.push(d);
+                            vec![]
+                        });
+                modules.push(Module {
+                    spec,
+                    ast_list,
+                    instances,
+                })
+            }
+            Err(d) => diagnostics.extend(d),
+        }
+    }
+    modules
+}
+
+/// Interval of polling files in `selien gen --watch`.
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// `selien gen --watch`, never returns.
+///
+/// Files are polled instead of watched with file system events, which works the
+/// same on every platform. `backends` is called for every run as the config file
+/// may change.
+pub fn watch<F: Fn() -> Backends>(args: &GenArgs, backends: F) -> ! {
+    let config_path = prepare::config_path(&args.config);
+    let mut state = WatchState::default();
+    let mut last: Option<BTreeMap<PathBuf, SystemTime>> = None;
+
+    info!("Watching for changes, press Ctrl-C to stop.");
+    loop {
+        let now = modified_times(&config_path, state.root.as_deref());
+        if last.as_ref() != Some(&now) {
+            let root = state.root.clone();
+            let diagnostics = rebuild(args, backends(), &config_path, &mut state);
+
+            let errors = diagnostic::report(&diagnostics);
+            if errors > 0 {
+                error!(
+                    "{} error(s), {} warning(s) found. Waiting for changes.",
+                    errors,
+                    diagnostics.len() - errors
+                );
+            } else {
+                info!("Done. Waiting for changes.");
+            }
+
+            // spec root is known or moved, files in it are not changes.
+            last = if root == state.root {
+                Some(now)
+            } else {
+                Some(modified_times(&config_path, state.root.as_deref()))
+            };
+        }
+        thread::sleep(WATCH_INTERVAL);
+    }
+}
+
+/// What `watch` knows about the last successful run.
+#[derive(Default)]
+struct WatchState {
+    root: Option<String>,
+    config: Option<String>,
+    /// Sources of spec files, by `module_path`.
+    sources: HashMap<PathBuf, String>,
+}
+
+/// Compile spec files changed since the last successful run, and files referring to them.
+fn rebuild(
+    args: &GenArgs,
+    backends: Backends,
+    config_path: &Path,
+    state: &mut WatchState,
+) -> Diagnostics {
+    let (config, spec_list, mut diagnostics) = match prepare::prepare(&args.config, backends) {
+        Ok(r) => r,
+        Err(d) => return d,
+    };
+    state.root = Some(config.spec.root.clone());
+
+    let config_source = fs::read_to_string(config_path).ok();
+    if config_source != state.config {
+        // every file depends on config file.
+        state.sources.clear();
+    }
+
+    let sources: HashMap<PathBuf, String> = spec_list
+        .iter()
+        .map(|s| {
+            let source = fs::read_to_string(&s.path).unwrap_or_default();
+            (module_path(&s.path), source)
+        })
+        .collect();
+
+    // changed, created or deleted files.
+    let mut dirty: HashSet<PathBuf> = sources
+        .iter()
+        .filter(|(p, s)| state.sources.get(*p) != Some(*s))
+        .map(|(p, _)| p.clone())
+        .chain(
+            state
+                .sources
+                .keys()
+                .filter(|p| !sources.contains_key(*p))
+                .cloned(),
+        )
+        .collect();
+
+    // every spec file is transformed as files like `mod.rs` are made of all of them,
+    // only problems of dirty ones are reported, the others were by the last run.
+    let mut modules: Vec<Module> = vec![];
+    let mut problems: HashMap<PathBuf, Diagnostics> = HashMap::new();
+    for spec in spec_list.iter() {
+        let mut d: Diagnostics = vec![];
+        modules.extend(transform(&config, &spec_list, [spec], &mut d));
+        problems.insert(module_path(&spec.path), d);
+    }
+
+    // files referring to a dirty file are dirty, until nothing is added.
+    let deps: Vec<(PathBuf, Vec<PathBuf>)> = modules
+        .iter()
+        .map(|m| (module_path(&m.spec.path), dependencies(&config, m)))
+        .collect();
+    loop {
+        let added: Vec<PathBuf> = deps
+            .iter()
+            .filter(|(p, d)| !dirty.contains(p) && d.iter().any(|d| dirty.contains(d)))
+            .map(|(p, _)| p.clone())
+            .collect();
+        if added.is_empty() {
+            break;
+        }
+        dirty.extend(added);
+    }
+
+    // a instance of go without generics is in one file of the package using it.
+    let targets = targets(args, &config, &mut diagnostics);
+    if targets.iter().any(|b| b.instantiates_generics()) {
+        let packages: HashSet<PathBuf> = dirty
+            .iter()
+            .filter_map(|p| p.parent().map(Path::to_path_buf))
+            .collect();
+        dirty.extend(
+            sources
+                .keys()
+                .filter(|p| p.parent().is_some_and(|d| packages.contains(d)))
+                .cloned(),
+        );
+    }
+
+    let dirty_specs: Vec<&Spec> = spec_list
+        .iter()
+        .filter(|s| dirty.contains(&module_path(&s.path)))
+        .collect();
+    for spec in dirty_specs.iter() {
+        diagnostics.extend(
+            problems
+                .remove(&module_path(&spec.path))
+                .unwrap_or_default(),
+        );
+    }
+    let dirty_modules: Vec<&Module> = modules
+        .iter()
+        .filter(|m| dirty_specs.iter().any(|s| std::ptr::eq(*s, m.spec)))
+        .collect();
+    diagnostics.extend(check_languages(
+        &config,
+        &targets,
+        dirty_modules.iter().copied(),
+    ));
+    let all: Vec<&Module> = modules.iter().collect();
+    diagnostics.extend(check_defaults(&config, dirty_modules, &all));
+    // a clash is reported at one of the files, which may not be dirty.
+    diagnostics.extend(check_identifiers(&config, &targets, &all));
+
+    if diagnostic::has_error(&diagnostics) {
+        return diagnostics;
+    }
+
+    if !dirty.is_empty() {
+        info!("Compiling {} spec file(s).", dirty_specs.len());
+        for backend in targets {
+            if let Err(d) = partial_compiler(backend, &config, &modules, &dirty_specs) {
+                diagnostics.extend(d);
+            }
+        }
+    }
+
+    if !diagnostic::has_error(&diagnostics) {
+        state.config = config_source;
+        state.sources = sources;
+    }
+    diagnostics
+}
+
+/// Modified time of the config file and every file in spec root.
+fn modified_times(config_path: &Path, root: Option<&str>) -> BTreeMap<PathBuf, SystemTime> {
+    let mut result: BTreeMap<PathBuf, SystemTime> = BTreeMap::new();
+    if let Ok(t) = fs::metadata(config_path).and_then(|m| m.modified()) {
+        result.insert(config_path.to_path_buf(), t);
+    }
+
+    let mut dirs: Vec<PathBuf> = root.map(PathBuf::from).into_iter().collect();
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let p = entry.path();
+            if p.is_dir() {
+                dirs.push(p);
+            } else if let Ok(t) = entry.metadata().and_then(|m| m.modified()) {
+                result.insert(p, t);
+            }
+        }
+    }
+    result
+}
+
+/// Print diagnostics of a command, the exit code is 1 if there is any error.
+pub fn finish(diagnostics: &[Diagnostic]) -> u8 {
+    let errors = diagnostic::report(diagnostics);
+    let warnings = diagnostics.len() - errors;
+    if errors > 0 {
+        error!("{} error(s), {} warning(s) found.", errors, warnings);
+        return 1;
+    }
+
+    info!("Done.");
+    0
+}
+
+// unit test here
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SPEC: &str = "selien-version: 0.1.0
+definition:
+  user:
+    declaration: type-alias
+    body:
+      type: string
+";
+
+    #[test]
+    fn partial_rebuild() {
+        let dir = std::env::temp_dir().join(format!("selien-watch-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("spec")).unwrap();
+        fs::write(
+            dir.join("selien.config.yaml"),
+            format!(
+                "spec:\n  root: {d}/spec\noutput:\n  rust:\n    root: {d}/out\n    output: {d}/out/gen\n",
+                d = dir.to_str().unwrap()
+            ),
+        )
+        .unwrap();
+        fs::write(dir.join("spec/a.yaml"), SPEC).unwrap();
+        fs::write(dir.join("spec/b.yaml"), SPEC).unwrap();
+
+        let args = GenArgs {
+            output: None,
+            check: false,
+            watch: true,
+            config: dir.to_str().unwrap().to_string(),
+        };
+        let config_path = prepare::config_path(&args.config);
+        let mut state = WatchState::default();
+        let read = |p: &str| fs::read_to_string(dir.join("out/gen").join(p)).unwrap();
+
+        let diagnostics = rebuild(&args, Backends::builtin(), &config_path, &mut state);
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert!(read("mod.rs").contains("pub mod a;\npub mod b;\n"));
+
+        // only a is compiled again, b is kept as is and still in mod.rs.
+        fs::write(dir.join("out/gen/b.rs"), "untouched").unwrap();
+        fs::write(dir.join("spec/a.yaml"), SPEC.replace("string", "number")).unwrap();
+        let diagnostics = rebuild(&args, Backends::builtin(), &config_path, &mut state);
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert!(read("a.rs").contains("pub type User = f64;"));
+        assert_eq!(read("b.rs"), "untouched");
+        assert!(read("mod.rs").contains("pub mod a;\npub mod b;\n"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,166 +1,276 @@
-use log::error;
-
-use super::special::with_special;
-use super::types::{self, Output, OutputFile};
-use crate::compiler::importer::with_import;
-use crate::generator::types::{Imports, TabSize};
+use super::types::{self, Module, Output, OutputFile};
+use crate::backend::LanguageBackend;
+use crate::diagnostic::{Code, Diagnostic, Diagnostics};
+use crate::generator::types::Imports;
 use crate::path::process_path;
-use crate::transformer;
-use crate::types::lang::SupportedLang;
-use crate::types::{config::Config, spec::SpecList};
-use std::fs::{create_dir_all, File};
-use std::io::Write;
+use crate::types::{config::Config, spec::Spec};
+use similar::TextDiff;
+use std::fs::{self, create_dir_all, File};
+use std::io::{ErrorKind, Write};
 use std::path::PathBuf;
 
-fn with_notice(content: &String) -> String {
+fn with_notice<B: LanguageBackend + ?Sized>(backend: &B, content: &String) -> String {
     let mut result = String::new();
     result += &format!(
-        "// This file is created automatically by Selien.\n// Do NOT edit.\n\n{}",
-        content
+        "{c} This file is created automatically by Selien.\n{c} Do NOT edit.\n\n{}",
+        content,
+        c = backend.comment()
     );
     result
 }
 
-impl SupportedLang {
-    pub fn compiler(&self, config: &Config, spec_list: &SpecList) {
-        let mut output = types::Output {
-            config,
-            files: Vec::new(),
-        };
-        for spec in spec_list {
-            let ast_list = transformer::main::transformer(spec);
-            let mut content: String = String::new();
-            let mut imports: Imports = vec![];
-            let tabsize = TabSize {
-                go: config.output.go.as_ref().unwrap().tabsize,
-                typescript: config.output.typescript.as_ref().unwrap().tabsize,
-            };
-
-            for ast in ast_list {
-                content += &ast.generator(self, &mut imports, &tabsize);
-            }
+pub fn compiler(
+    backend: &dyn LanguageBackend,
+    config: &Config,
+    modules: &[Module],
+) -> Result<(), Diagnostics> {
+    let output = types::Output {
+        config,
+        files: backend.compile(config, modules)?,
+    };
 
-            if !imports.is_empty() {
-                content = with_import(self, &spec.path, &content, &imports, config);
-            }
+    file_creater(&output, backend)
+}
+
+/// Like `compiler`, but only files of `specs` and files not generated from a spec file,
+/// like `mod.rs`, are written.
+///
+/// `modules` are all spec files, so that those other files are complete.
+pub fn partial_compiler(
+    backend: &dyn LanguageBackend,
+    config: &Config,
+    modules: &[Module],
+    specs: &[&Spec],
+) -> Result<(), Diagnostics> {
+    let files = backend
+        .compile(config, modules)?
+        .into_iter()
+        .filter(|f| {
+            let is_spec = |s: &&Spec| s.path == f.path;
+            specs.iter().any(is_spec) || !modules.iter().map(|m| &m.spec).any(is_spec)
+        })
+        .collect();
+    let output = types::Output { config, files };
+
+    file_creater(&output, backend)
+}
 
-            content = with_special(self, &content, spec, config);
-            content = with_notice(&content);
+/// Like `compiler`, but compare files with the disk instead of writing them.
+///
+/// Every file that differs or does not exist is an `OutOfDate` error.
+pub fn drift_checker(
+    backend: &dyn LanguageBackend,
+    config: &Config,
+    modules: &[Module],
+) -> Result<(), Diagnostics> {
+    let output = types::Output {
+        config,
+        files: backend.compile(config, modules)?,
+    };
 
-            let file = OutputFile {
-                path: spec.path.clone(),
-                content,
-            };
-            output.files.push(file);
+    let mut diagnostics: Diagnostics = Vec::new();
+    for file in output.files.iter() {
+        if let Err(d) = compare_file(file, config, backend) {
+            diagnostics.push(d);
         }
+    }
 
-        file_creater(&output, self);
+    if diagnostics.is_empty() {
+        Ok(())
+    } else {
+        Err(diagnostics)
     }
 }
 
-fn file_creater(output: &Output, lang: &SupportedLang) {
-    for file in output.files.iter() {
-        let mut p = PathBuf::from(&file.path);
-        let config = &output.config;
+/// Default compilation of `LanguageBackend`.
+///
+/// A spec file with problems is skipped, problems of all files are returned.
+pub fn render_files<B: LanguageBackend + ?Sized>(
+    backend: &B,
+    config: &Config,
+    modules: &[Module],
+) -> Result<Vec<OutputFile>, Diagnostics> {
+    let mut files: Vec<OutputFile> = Vec::new();
+    let mut diagnostics: Diagnostics = Vec::new();
+    'modules: for module in modules {
+        let spec = module.spec;
+        let mut content: String = String::new();
+        let mut imports: Imports = vec![];
 
-        p = match p.strip_prefix(&config.spec.root) {
-            Ok(r) => r.to_path_buf(),
-            Err(err) => {
-                error!(
-                    "File path {} is not start with spec root in config: {}. Error: {}",
-                    file.path, config.spec.root, err
-                );
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
-                }
-            }
-        };
-        process_output(&mut p, config, lang);
-        p = process_path(p);
-
-        let dir = match p.parent() {
-            Some(r) => r,
-            None => {
-                error!("Can not get parent dir of {}", file.path);
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
+        for ast in module.ast_list.iter() {
+            if let Some(ast) = ast.resolve_split(backend.id()) {
+                match backend.render_type(&ast, &mut imports) {
+                    Ok(r) => content += &r,
+                    Err(d) => {
+                        diagnostics.push(d.in_file(&spec.path));
+                        continue 'modules;
+                    }
                 }
             }
-        };
+        }
 
-        match create_dir_all(dir) {
-            Ok(_) => {}
-            Err(err) => {
-                error!("Can not create file {} with err: {}", file.path, err);
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
+        if !imports.is_empty() {
+            match backend.render_imports(&spec.path, &imports, config) {
+                Ok(r) => content = r + &content,
+                Err(d) => {
+                    diagnostics.push(d.in_file(&spec.path));
+                    continue;
                 }
             }
-        };
+        }
 
-        let mut f = match File::create(&p) {
+        content = match backend.preamble(&content, spec, config) {
             Ok(r) => r,
-            Err(err) => {
-                error!("Can not create file {} with err: {}", file.path, err);
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
-                }
+            Err(d) => {
+                diagnostics.push(d.in_file(&spec.path));
+                continue;
             }
         };
+        content = with_notice(backend, &content);
 
-        match f.write(file.content.as_bytes()) {
-            Ok(_) => {}
-            Err(err) => {
-                error!("Can not write file {} with err: {}", file.path, err);
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
-                }
-            }
+        let file = OutputFile {
+            path: spec.path.clone(),
+            content,
         };
+        files.push(file);
     }
+
+    if !diagnostics.is_empty() {
+        return Err(diagnostics);
+    }
+
+    for mut file in backend.extra_files(modules, config) {
+        file.content = with_notice(backend, &file.content);
+        files.push(file);
+    }
+
+    Ok(files)
 }
 
-fn process_output(p: &mut PathBuf, config: &Config, lang: &SupportedLang) {
-    match lang {
-        SupportedLang::Go => {
-            let output_path = match &config.output.go {
-                Some(o) => o.output.clone(),
-                None => {
-                    error!("You may want to generate Go code but forget to defiend it in config.");
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
-            *p = PathBuf::from(output_path).join(&p);
-            p.set_extension("go");
+fn file_creater(output: &Output, backend: &dyn LanguageBackend) -> Result<(), Diagnostics> {
+    let mut diagnostics: Diagnostics = Vec::new();
+    for file in output.files.iter() {
+        if let Err(d) = create_file(file, output.config, backend) {
+            diagnostics.push(d);
         }
-        SupportedLang::TypeScript => {
-            let output_path = match &config.output.typescript {
-                Some(o) => o.output.clone(),
-                None => {
-                    error!("You may want to generate TypeScript code but forget to defiend it in config.");
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
-            *p = PathBuf::from(output_path).join(&p);
-            p.set_extension("ts");
+    }
+
+    if diagnostics.is_empty() {
+        Ok(())
+    } else {
+        Err(diagnostics)
+    }
+}
+
+fn create_file(
+    file: &OutputFile,
+    config: &Config,
+    backend: &dyn LanguageBackend,
+) -> Result<(), Diagnostic> {
+    let p = output_path(file, config, backend)?;
+
+    let dir = match p.parent() {
+        Some(r) => r,
+        None => {
+            return Err(Diagnostic::error(
+                Code::Io,
+                format!("Can not get parent dir of {}", file.path),
+            ));
         }
+    };
+
+    if let Err(err) = create_dir_all(dir) {
+        return Err(Diagnostic::error(
+            Code::Io,
+            format!("Can not create file {} with err: {}", file.path, err),
+        ));
+    }
+
+    let mut f = match File::create(&p) {
+        Ok(r) => r,
+        Err(err) => {
+            return Err(Diagnostic::error(
+                Code::Io,
+                format!("Can not create file {} with err: {}", file.path, err),
+            ));
+        }
+    };
+
+    if let Err(err) = f.write_all(file.content.as_bytes()) {
+        return Err(Diagnostic::error(
+            Code::Io,
+            format!("Can not write file {} with err: {}", file.path, err),
+        ));
+    }
+
+    Ok(())
+}
+
+fn compare_file(
+    file: &OutputFile,
+    config: &Config,
+    backend: &dyn LanguageBackend,
+) -> Result<(), Diagnostic> {
+    let p = output_path(file, config, backend)?;
+    let display = p.display().to_string();
+
+    let current = match fs::read_to_string(&p) {
+        Ok(r) => r,
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            return Err(Diagnostic::error(
+                Code::OutOfDate,
+                format!("{} would be created.", display),
+            ));
+        }
+        Err(err) => {
+            return Err(Diagnostic::error(
+                Code::Io,
+                format!("Can not read file {} with err: {}", display, err),
+            ));
+        }
+    };
+
+    if current == file.content {
+        return Ok(());
+    }
+
+    let diff = TextDiff::from_lines(&current, &file.content)
+        .unified_diff()
+        .header(&display, &display)
+        .to_string();
+    let mut d = Diagnostic::error(Code::OutOfDate, format!("{} is out of date.", display));
+    d.frame = Some(diff.trim_end().to_string());
+    Err(d)
+}
+
+/// Where `file` is written, under the output dir of `backend`.
+fn output_path(
+    file: &OutputFile,
+    config: &Config,
+    backend: &dyn LanguageBackend,
+) -> Result<PathBuf, Diagnostic> {
+    let mut p = PathBuf::from(&file.path);
+
+    p = match p.strip_prefix(&config.spec.root) {
+        Ok(r) => r.to_path_buf(),
+        Err(err) => {
+            return Err(Diagnostic::error(
+                Code::Io,
+                format!(
+                    "File path {} is not start with spec root in config: {}. Error: {}",
+                    file.path, config.spec.root, err
+                ),
+            ));
+        }
+    };
+    process_output(&mut p, backend);
+    Ok(process_path(p))
+}
+
+fn process_output(p: &mut PathBuf, backend: &dyn LanguageBackend) {
+    *p = PathBuf::from(backend.output_dir()).join(&p);
+    // empty extension means the backend names files itself, like plugins.
+    if !backend.file_extension().is_empty() {
+        p.set_extension(backend.file_extension());
     }
 }
@@ -1,6 +1,3 @@
 pub mod main;
 pub mod types;
-
-pub(self) mod importer;
-pub(self) mod special;
-pub(self) mod utils;
+pub mod utils;
@@ -1,6 +1,9 @@
-use crate::types::config::Config;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+use crate::generator::types::AST;
+use crate::types::{config::Config, spec::Spec};
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct OutputFile {
     pub path: String,
     pub content: String,
@@ -10,3 +13,15 @@ pub struct Output<'a> {
     pub config: &'a Config,
     pub files: Vec<OutputFile>,
 }
+
+/// A spec file with its transformed definitions.
+///
+/// Spec files are transformed once and shared by every language.
+#[derive(Debug)]
+pub struct Module<'a> {
+    pub spec: &'a Spec,
+    pub ast_list: Vec<AST>,
+    /// Copies of generic definitions for each set of args used in the file,
+    /// only filled when a language `instantiates` some of them.
+    pub instances: Vec<AST>,
+}
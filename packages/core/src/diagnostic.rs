@@ -0,0 +1,175 @@
+use std::fmt;
+
+use log::{error, warn};
+
+use crate::source::{Position, SourceMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// Kind of a problem, printed as `E<number>` so that it can be searched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Code {
+    /// Can not read or write a file.
+    Io,
+    /// Invalid yaml.
+    Yaml,
+    /// Invalid `selien.config.yaml`.
+    Config,
+    /// Invalid structure of selien file, like a missing key.
+    Syntax,
+    /// Unknown `type` value.
+    UnknownType,
+    /// Invalid enum type or member.
+    InvalidEnum,
+    /// `$ref` can not be resolved.
+    InvalidRef,
+    /// Two definitions or enum members get the same name in generated code.
+    DuplicateIdentifier,
+    /// A `$ref` to a deprecated definition.
+    Deprecated,
+    /// Plugin failed.
+    Plugin,
+    /// Generated file on disk differs from what `selien gen` would write.
+    OutOfDate,
+}
+
+impl Code {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Code::Io => "E001",
+            Code::Yaml => "E002",
+            Code::Config => "E003",
+            Code::Syntax => "E100",
+            Code::UnknownType => "E101",
+            Code::InvalidEnum => "E102",
+            Code::InvalidRef => "E103",
+            Code::DuplicateIdentifier => "E104",
+            Code::Deprecated => "E105",
+            Code::Plugin => "E200",
+            Code::OutOfDate => "E300",
+        }
+    }
+}
+
+/// A problem found while running selien.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: Code,
+    pub message: String,
+    /// File the problem is in, spec file or config file.
+    pub file: Option<String>,
+    /// Yaml key path in `file`, `e.g.` `["definition", "user", "body", "type"]`.
+    pub key_path: Vec<String>,
+    pub position: Option<Position>,
+    /// Code frame of the source lines at `position`.
+    pub frame: Option<String>,
+}
+
+pub type Diagnostics = Vec<Diagnostic>;
+
+impl From<Diagnostic> for Diagnostics {
+    fn from(diagnostic: Diagnostic) -> Self {
+        vec![diagnostic]
+    }
+}
+
+impl Diagnostic {
+    pub fn error(code: Code, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            code,
+            message: message.into(),
+            file: None,
+            key_path: vec![],
+            position: None,
+            frame: None,
+        }
+    }
+
+    pub fn warning(code: Code, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::error(code, message)
+        }
+    }
+
+    /// Set file if not set yet.
+    pub fn in_file(mut self, file: &str) -> Self {
+        if self.file.is_none() {
+            self.file = Some(file.to_string());
+        }
+        self
+    }
+
+    pub fn at(mut self, key_path: &[String]) -> Self {
+        self.key_path = key_path.to_vec();
+        self
+    }
+
+    /// Find position of key path in `source` if not set yet.
+    pub fn locate(mut self, source: &SourceMap) -> Self {
+        if self.position.is_none() && !self.key_path.is_empty() {
+            self.position = source.find(&self.key_path);
+        }
+        if self.frame.is_none() {
+            self.frame = self.position.and_then(|p| source.frame(p));
+        }
+        self
+    }
+
+    /// Prepend `parent` to key path, for diagnostics created with a relative one.
+    pub fn under(mut self, parent: &[String]) -> Self {
+        self.key_path = [parent, &self.key_path].concat();
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.code.as_str(), self.message)?;
+        if let Some(file) = &self.file {
+            write!(f, "\n  --> {}", file)?;
+            if let Some(p) = &self.position {
+                write!(f, ":{}:{}", p.line, p.col)?;
+            }
+            if !self.key_path.is_empty() {
+                write!(f, " ({})", self.key_path.join("."))?;
+            }
+        }
+        if let Some(frame) = &self.frame {
+            write!(f, "\n{}", frame)?;
+        }
+        Ok(())
+    }
+}
+
+/// `e.g.` `key_path(&["definition", "user"], "body")`
+pub fn key_path(parent: &[String], key: &str) -> Vec<String> {
+    let mut result = parent.to_vec();
+    result.push(key.to_string());
+    result
+}
+
+pub fn has_error(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics.iter().any(|d| d.severity == Severity::Error)
+}
+
+/// Log every diagnostic, return the count of errors.
+pub fn report(diagnostics: &[Diagnostic]) -> usize {
+    let mut errors = 0;
+    for d in diagnostics {
+        match d.severity {
+            Severity::Error => {
+                errors += 1;
+                error!("{}", d);
+            }
+            Severity::Warning => warn!("{}", d),
+        }
+    }
+    errors
+}
@@ -1,18 +1,24 @@
 use std::path::PathBuf;
 
-use log::{error, warn};
-
 use super::super::types::{
     ast_enum,
-    ast_type_alias::{Node, Property, TypeAliasAst},
+    ast_type_alias::{
+        Constraints, DefaultValue, Discriminator, KeywordNode, Keywords, MapNode, Node,
+        NumberFormat, ObjectNode, Property, RefNode, StringFormat, TupleNode, TypeAliasAst,
+        UnionNode,
+    },
     AST,
 };
 use crate::{
+    backend::go,
+    diagnostic::{key_path, Diagnostic},
     generator::{
+        main::{params_of, unexpected},
         types::{DynImport, Import, Imports, RefImport},
-        utils::capitalize,
+        utils::{capitalize, rename, to_snake_case},
+        validate::{checks, literal_message, one_of_message, Check, ValuePath},
     },
-    types::lang::SupportedLang,
+    types::config::{GoOptions, Naming},
 };
 
 /// Make sure to due with golang imports. (when $ref used)
@@ -32,98 +38,114 @@ use crate::{
 /// So we add a **token** when $ref used. Syntax is `[selien-ref]path/to/ref/yourpackage/file.yaml[selien-ref]`
 ///
 /// This token must be replace to actual package name when compile phase.
-pub fn generate_go(ast: &AST, imports: &mut Imports, tabsize: usize) -> String {
+pub fn generate_go(
+    ast: &AST,
+    imports: &mut Imports,
+    tabsize: usize,
+    options: &GoOptions,
+) -> Result<String, Diagnostic> {
     match ast {
         AST::Enum(enum_ast) => {
             let mut result = String::from("\n");
+            result += &doc_comment(
+                enum_ast.description.as_ref(),
+                enum_ast.deprecated.as_ref(),
+                &capitalize(&enum_ast.identifier),
+                "",
+            );
 
-            match &enum_ast.r#type {
-                ast_enum::MembersType::String => {
-                    result += &format!("type {} string\n", capitalize(&enum_ast.identifier));
-                }
-                ast_enum::MembersType::Number => {
-                    result += &format!("type {} int\n", capitalize(&enum_ast.identifier));
-                }
-            }
-
-            let s = iterate_members(
+            result += &render_enum(
                 &enum_ast.members,
                 &enum_ast.r#type,
-                &enum_ast.identifier,
+                enum_ast.format,
+                &capitalize(&enum_ast.identifier),
+                "",
                 tabsize,
             );
-            result += &format!("const (\n{}\n)\n\n", s);
-
-            result
+            Ok(result)
         }
         AST::TypeAlias(type_alias_ast) => {
+            let id = capitalize(&type_alias_ast.identifier);
+            let params = &type_alias_ast.type_parameters;
+            // `Name[T any]`
+            let decl = format!("{}{}", id, declare_params(params));
+            let mut hoisted: Vec<String> = vec![];
             let mut result = String::new();
-            match &type_alias_ast.body {
+            let body = self_refs_as_pointers(&type_alias_ast.body, &type_alias_ast.identifier);
+            match &body {
                 Node::StringLiteral(_) => {
-                    let s = format!("type {} string\n", capitalize(&type_alias_ast.identifier));
+                    let s = format!("type {} string\n", decl);
                     result += &s;
                 }
-                Node::NumberLiteral(_) => {
-                    let s = format!("type {} int\n", capitalize(&type_alias_ast.identifier));
+                Node::NumberLiteral(node) => {
+                    let s = format!("type {} {}\n", decl, number(node.format));
                     result += &s;
                 }
                 Node::Keyword(node) => {
-                    let s = format!(
-                        "type {} {}\n",
-                        capitalize(&type_alias_ast.identifier),
-                        node.value.to_string(SupportedLang::Go)
-                    );
+                    let s = format!("type {} {}\n", decl, keyword(imports, node));
                     result += &s;
                 }
                 Node::Object(node) => {
-                    let r: String = node
+                    let r = node
                         .values
                         .iter()
                         .enumerate()
                         .map(|(idx, v)| {
                             let is_last = idx == node.values.len() - 1;
-                            iterate_properties(imports, v, 1, tabsize, is_last)
+                            iterate_properties(
+                                imports,
+                                &mut hoisted,
+                                v,
+                                &id,
+                                1,
+                                tabsize,
+                                is_last,
+                                options,
+                            )
                         })
-                        .collect();
+                        .collect::<Result<String, _>>()?;
                     result += &format!(
                         "type {} struct {{\n{}\n}}\n",
-                        capitalize(&type_alias_ast.identifier),
-                        r
+                        decl,
+                        struct_fields(imports, &mut hoisted, node, r, 1, tabsize, options)?
                     );
+                    if node.has_defaults() {
+                        result +=
+                            &render_constructor(imports, node, &id, params, tabsize, options)?;
+                    }
                 }
                 Node::Array(node) => {
-                    let r = iterate_array(imports, &node.items, tabsize);
-                    result += &format!("type {} []{}\n", capitalize(&type_alias_ast.identifier), r);
+                    let hint = format!("{}Item", id);
+                    let r = iterate_array(
+                        imports,
+                        &mut hoisted,
+                        &node.items,
+                        &hint,
+                        0,
+                        tabsize,
+                        options,
+                    )?;
+                    result += &format!("type {} []{}\n", decl, r);
+                }
+                Node::Map(node) => {
+                    let r = render_map(imports, &mut hoisted, node, &id, 0, tabsize, options)?;
+                    result += &format!("type {} {}\n", decl, r);
+                }
+                Node::Tuple(node) => {
+                    result +=
+                        &render_tuple(imports, &mut hoisted, node, &id, params, tabsize, options)?;
                 }
                 Node::Ref(node) => {
-                    let mut s = format!(
-                        "type {} {}\n",
-                        capitalize(&type_alias_ast.identifier),
-                        capitalize(&node.name)
-                    );
-
-                    if !node.path.is_empty() {
-                        let ref_token = format!("[selien-ref]{}[selien-ref]", node.path);
-                        s = format!(
-                            "type {} {}{}\n",
-                            capitalize(&type_alias_ast.identifier),
-                            ref_token,
-                            capitalize(&node.name)
-                        );
-
-                        imports.push(Import::Ref(RefImport {
-                            name: capitalize(&node.name),
-                            from: PathBuf::from(&node.path),
-                        }));
-                    }
-
-                    result += &s;
+                    let r = ref_type(imports, &mut hoisted, node, &id, 0, tabsize, options)?;
+                    result += &format!("type {} {}\n", decl, r);
                 }
+                Node::Enum(_) => return Err(unexpected("enum node as a definition")),
+                Node::Param(_) => return Err(unexpected("type parameter as a definition")),
                 Node::Dyn(node) => {
                     let fp = PathBuf::from(&node.from);
                     let s = format!(
                         "type {} {}.{}\n",
-                        capitalize(&type_alias_ast.identifier),
+                        decl,
                         fp.file_name().unwrap().to_str().unwrap(),
                         capitalize(&node.name)
                     );
@@ -134,71 +156,95 @@ pub fn generate_go(ast: &AST, imports: &mut Imports, tabsize: usize) -> String {
 
                     result += &s;
                 }
+                Node::Union(
+                    node @ UnionNode {
+                        discriminator: Some(d),
+                        ..
+                    },
+                ) => {
+                    result += &render_discriminated(
+                        imports,
+                        &mut hoisted,
+                        node,
+                        d,
+                        &id,
+                        params,
+                        tabsize,
+                        options,
+                    )?;
+                }
+                // warned by `Go::check`.
                 Node::Union(_) => {
-                    let id = capitalize(&type_alias_ast.identifier);
-                    warn!(
-                        "Union type '{}' using in golang. interface{{}} type will be generated.",
-                        &id
-                    );
-                    let s = format!("type {} interface{{}}\n", id);
+                    let s = format!("type {} interface{{}}\n", decl);
                     result += &s;
                 }
                 Node::Split(split) => {
-                    if let Some(node) = &split.go {
+                    if let Some(node) = split.nodes.get(go::ID) {
                         let s = generate_go(
                             &AST::TypeAlias(TypeAliasAst {
                                 identifier: type_alias_ast.identifier.clone(),
+                                type_parameters: type_alias_ast.type_parameters.clone(),
+                                description: type_alias_ast.description.clone(),
+                                deprecated: type_alias_ast.deprecated.clone(),
                                 body: node.as_ref().clone(),
                             }),
                             imports,
                             tabsize,
-                        );
+                            options,
+                        )?;
                         result += &s;
                     }
                 }
-                Node::Empty => {
-                    error!("Empty node.");
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
+                Node::Empty => return Err(unexpected("empty node")),
             }
-            result
+            // a split is documented by the definition of go.
+            let doc = match &type_alias_ast.body {
+                Node::Split(_) => String::new(),
+                _ => doc_comment(
+                    type_alias_ast.description.as_ref(),
+                    type_alias_ast.deprecated.as_ref(),
+                    &id,
+                    "",
+                ),
+            };
+            Ok(hoisted.concat() + &doc + &result)
         }
     }
 }
 
+/// `parent` is the name of the struct, nodes needing a name are hoisted as `<Parent><Field>`.
+#[allow(clippy::too_many_arguments)]
 fn iterate_properties(
     imports: &mut Imports,
+    hoisted: &mut Vec<String>,
     p: &Property,
+    parent: &str,
     deepth: usize,
     tabsize: usize,
     is_last: bool,
-) -> String {
+    options: &GoOptions,
+) -> Result<String, Diagnostic> {
     let indent = " ".repeat(deepth * tabsize);
-    let mut result = String::new();
+    let hint = property_hint(parent, p);
+    let ptr = pointer(p);
+    let tag = struct_tag(p, options);
+    let name = field_name(p, options.naming);
+    let mut result = doc_comment(
+        p.description.as_ref(),
+        p.deprecated.as_ref(),
+        &name,
+        &indent,
+    );
     match &p.body {
         Node::StringLiteral(_) => {
-            let mut s = format!(
-                "{}{} string `json:\"{}\"`",
-                indent,
-                capitalize(&p.identifier),
-                &p.identifier
-            );
+            let mut s = format!("{}{} {}string {}", indent, name, ptr, tag);
             if !is_last {
                 s += "\n"
             }
             result += &s;
         }
-        Node::NumberLiteral(_) => {
-            let mut s = format!(
-                "{}{} int `json:\"{}\"`",
-                indent,
-                capitalize(&p.identifier),
-                &p.identifier
-            );
+        Node::NumberLiteral(node) => {
+            let mut s = format!("{}{} {}{} {}", indent, name, ptr, number(node.format), tag);
             if !is_last {
                 s += "\n"
             }
@@ -206,11 +252,12 @@ fn iterate_properties(
         }
         Node::Keyword(node) => {
             let mut s = format!(
-                "{}{} {} `json:\"{}\"`",
+                "{}{} {}{} {}",
                 indent,
-                capitalize(&p.identifier),
-                node.value.to_string(SupportedLang::Go),
-                &p.identifier
+                name,
+                ptr,
+                keyword(imports, node),
+                tag
             );
             if !is_last {
                 s += "\n"
@@ -218,22 +265,32 @@ fn iterate_properties(
             result += &s;
         }
         Node::Object(node) => {
-            let r: String = node
+            let r = node
                 .values
                 .iter()
                 .enumerate()
                 .map(|(idx, v)| {
                     let is_last = idx == node.values.len() - 1;
-                    iterate_properties(imports, v, deepth + 1, tabsize, is_last)
+                    iterate_properties(
+                        imports,
+                        hoisted,
+                        v,
+                        &hint,
+                        deepth + 1,
+                        tabsize,
+                        is_last,
+                        options,
+                    )
                 })
-                .collect();
+                .collect::<Result<String, _>>()?;
             let mut s = format!(
-                "{i}{id} struct {{\n{re}\n{ii}}} `json:\"{j}\"`",
+                "{i}{id} {p}struct {{\n{re}\n{ii}}} {t}",
                 i = indent,
-                id = capitalize(&p.identifier),
+                id = name,
+                p = ptr,
                 ii = indent,
-                re = r,
-                j = &p.identifier
+                re = struct_fields(imports, hoisted, node, r, deepth + 1, tabsize, options)?,
+                t = tag
             );
             if !is_last {
                 s += "\n"
@@ -241,44 +298,73 @@ fn iterate_properties(
             result += &s
         }
         Node::Array(node) => {
-            let r = iterate_array(imports, &node.items, tabsize + 1);
+            let item_hint = format!("{}Item", hint);
+            let r = iterate_array(
+                imports,
+                hoisted,
+                &node.items,
+                &item_hint,
+                deepth,
+                tabsize,
+                options,
+            )?;
+            let mut s = format!("{}{} []{} {}", indent, name, r, tag);
+            if !is_last {
+                s += "\n"
+            }
+            result += &s
+        }
+        Node::Map(node) => {
+            let r = render_map(imports, hoisted, node, &hint, deepth, tabsize, options)?;
+            let mut s = format!("{}{} {} {}", indent, name, r, tag);
+            if !is_last {
+                s += "\n"
+            }
+            result += &s
+        }
+        Node::Tuple(node) => {
+            let params = params_of(&node.items);
+            let t = render_tuple(imports, hoisted, node, &hint, &params, tabsize, options)?;
+            hoisted.push(t + "\n");
             let mut s = format!(
-                "{}{} []{} `json:\"{}\"`",
+                "{}{} {}{}{} {}",
                 indent,
-                capitalize(&p.identifier),
-                r,
-                &p.identifier
+                name,
+                ptr,
+                hint,
+                use_params(&params),
+                tag
             );
             if !is_last {
                 s += "\n"
             }
             result += &s
         }
+        Node::Enum(node) => {
+            hoisted.push(render_enum(
+                &node.members,
+                &node.r#type,
+                node.format,
+                &hint,
+                &hint,
+                tabsize,
+            ));
+            let mut s = format!("{}{} {}{} {}", indent, name, ptr, hint, tag);
+            if !is_last {
+                s += "\n"
+            }
+            result += &s
+        }
         Node::Ref(node) => {
-            let mut s = format!(
-                "{}{} {} `json:\"{}\"`",
-                indent,
-                capitalize(&p.identifier),
-                capitalize(&node.name),
-                &p.identifier
-            );
-            if !node.path.is_empty() {
-                let ref_token = format!("[selien-ref]{}[selien-ref]", node.path);
-                s = format!(
-                    "{}{} {}{} `json:\"{}\"`",
-                    indent,
-                    capitalize(&p.identifier),
-                    ref_token,
-                    capitalize(&node.name),
-                    &p.identifier
-                );
-
-                imports.push(Import::Ref(RefImport {
-                    name: capitalize(&node.name),
-                    from: PathBuf::from(&node.path),
-                }));
+            let r = ref_type(imports, hoisted, node, &hint, deepth, tabsize, options)?;
+            let mut s = format!("{}{} {}{} {}", indent, name, ptr, r, tag);
+            if !is_last {
+                s += "\n"
             }
-
+            result += &s
+        }
+        Node::Param(node) => {
+            let mut s = format!("{}{} {}{} {}", indent, name, ptr, node.name, tag);
             if !is_last {
                 s += "\n"
             }
@@ -287,12 +373,13 @@ fn iterate_properties(
         Node::Dyn(node) => {
             let fp = PathBuf::from(&node.from);
             let mut s = format!(
-                "{}{} {}.{} `json:\"{}\"`",
+                "{}{} {}{}.{} {}",
                 indent,
-                capitalize(&p.identifier),
+                name,
+                ptr,
                 fp.file_name().unwrap().to_str().unwrap(),
                 capitalize(&node.name),
-                &p.identifier,
+                tag,
             );
             if !is_last {
                 s += "\n"
@@ -304,77 +391,385 @@ fn iterate_properties(
 
             result += &s;
         }
-        Node::Union(_) => {
-            let id = capitalize(&p.identifier);
-            warn!(
-                "Union type '{}' using in golang object type. interface{{}} type will be generated.",
-                &id
-            );
+        Node::Union(
+            node @ UnionNode {
+                discriminator: Some(d),
+                ..
+            },
+        ) => {
+            let params = params_of(&node.types);
+            let t =
+                render_discriminated(imports, hoisted, node, d, &hint, &params, tabsize, options)?;
+            hoisted.push(t + "\n");
             let mut s = format!(
-                "{}{} interface{{}} `json:\"{}\"`",
-                indent, id, &p.identifier
+                "{}{} {}{}{} {}",
+                indent,
+                name,
+                ptr,
+                hint,
+                use_params(&params),
+                tag
             );
             if !is_last {
                 s += "\n"
             }
+            result += &s
+        }
+        Node::Union(_) => {
+            let mut s = format!("{}{} interface{{}} {}", indent, name, tag);
+            if !is_last {
+                s += "\n"
+            }
             result += &s;
         }
-        Node::Split(_) => {
-            error!("Split-type can only use on top-level.");
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1);
+        Node::Split(_) => return Err(unexpected("split node")),
+        Node::Empty => return Err(unexpected("empty node")),
+    }
+    Ok(result)
+}
+
+/// `body` of the definition `identifier` with properties holding the definition
+/// itself made nullable, which are pointers as a struct can not contain itself.
+///
+/// Other cycles are errors of `checker::check_cycles`.
+fn self_refs_as_pointers(body: &Node, identifier: &str) -> Node {
+    let Node::Object(node) = body else {
+        return body.clone();
+    };
+    let values = node
+        .values
+        .iter()
+        .map(|p| match &p.body {
+            Node::Ref(r) if r.path.is_empty() && r.name == identifier => Property {
+                nullable: true,
+                ..p.clone()
+            },
+            Node::Object(_) => Property {
+                body: self_refs_as_pointers(&p.body, identifier),
+                ..p.clone()
+            },
+            _ => p.clone(),
+        })
+        .collect();
+    Node::Object(ObjectNode {
+        values,
+        ..node.clone()
+    })
+}
+
+/// Name of the property `p` of `parent` if it is hoisted, `<Parent><Field>`.
+fn property_hint(parent: &str, p: &Property) -> String {
+    format!("{}{}", parent, capitalize(&p.identifier))
+}
+
+/// Field of the variant `idx` of a discriminated union, named by the definition of a `$ref`.
+fn variant_field(t: &Node, idx: usize) -> String {
+    match t {
+        Node::Ref(r) => capitalize(&r.name),
+        _ => format!("Variant{}", idx),
+    }
+}
+
+/// Constant of a enum member, see `render_enum`.
+fn member_name(prefix: &str, m: &ast_enum::Member) -> String {
+    format!("{}{}", prefix, capitalize(&m.identifier))
+}
+
+/// Names `generate_go` declares in the package for `ast` besides the type of the definition,
+/// like hoisted types, constants of members and constructors.
+///
+/// Each is paired with the key path of the node declaring it, `path` is the key path of `ast`
+/// as for `LanguageBackend::check`.
+pub fn package_names(ast: &AST, path: &[String]) -> Vec<(String, Vec<String>)> {
+    let mut names: Vec<(String, Vec<String>)> = vec![];
+    match ast {
+        AST::Enum(e) => member_names(&e.members, "", &key_path(path, "members"), &mut names),
+        AST::TypeAlias(t) => {
+            let id = capitalize(&t.identifier);
+            if matches!(&t.body, Node::Object(o) if o.has_defaults()) {
+                names.push((format!("New{}", id), path.to_vec()));
             }
+            // the definition is the type of the body.
+            inner_names(&t.body, &id, path, &mut names);
         }
-        Node::Empty => {
-            error!("Empty node.");
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1);
+    }
+    names
+}
+
+/// Names declared for `node` rendered by `iterate_array` with `hint`.
+fn node_names(node: &Node, hint: &str, path: &[String], names: &mut Vec<(String, Vec<String>)>) {
+    match node {
+        Node::Tuple(_)
+        | Node::Enum(_)
+        | Node::Union(UnionNode {
+            discriminator: Some(_),
+            ..
+        }) => names.push((hint.to_string(), path.to_vec())),
+        _ => {}
+    }
+    inner_names(node, hint, path, names);
+}
+
+/// Names declared for the nodes in `node`, which is named `hint`.
+fn inner_names(node: &Node, hint: &str, path: &[String], names: &mut Vec<(String, Vec<String>)>) {
+    let at = |keys: &[&str]| keys.iter().fold(path.to_vec(), |p, key| key_path(&p, key));
+    match node {
+        Node::Object(o) => {
+            for p in o.values.iter() {
+                let path = at(&["properties", &p.identifier]);
+                node_names(&p.body, &property_hint(hint, p), &path, names);
+            }
+        }
+        Node::Array(a) => node_names(&a.items, &format!("{}Item", hint), &at(&["items"]), names),
+        Node::Map(m) => {
+            node_names(&m.keys, &format!("{}Key", hint), &at(&["keys"]), names);
+            node_names(
+                &m.values,
+                &format!("{}Value", hint),
+                &at(&["values"]),
+                names,
+            );
+        }
+        Node::Tuple(t) => {
+            for (idx, item) in t.items.iter().enumerate() {
+                let path = at(&["items", &idx.to_string()]);
+                node_names(item, &format!("{}Item{}", hint, idx), &path, names);
+            }
+        }
+        Node::Ref(r) => {
+            for (idx, a) in r.args.iter().enumerate() {
+                let path = at(&["args", &idx.to_string()]);
+                node_names(a, &format!("{}Arg{}", hint, idx), &path, names);
             }
         }
+        Node::Enum(e) => member_names(&e.members, hint, &at(&["members"]), names),
+        Node::Union(UnionNode {
+            types,
+            discriminator: Some(_),
+            ..
+        }) => {
+            for (idx, t) in types.iter().enumerate() {
+                let hint = format!("{}{}", hint, variant_field(t, idx));
+                node_names(t, &hint, &at(&["types", &idx.to_string()]), names);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Constants of `members`, `path` is the key path of the members.
+fn member_names(
+    members: &ast_enum::Members,
+    prefix: &str,
+    path: &[String],
+    names: &mut Vec<(String, Vec<String>)>,
+) {
+    for (idx, m) in members.iter().enumerate() {
+        let member_path = key_path(&key_path(path, &idx.to_string()), &m.identifier);
+        names.push((member_name(prefix, m), member_path));
+    }
+}
+
+/// Embedded structs of the bases of an object followed by `fields`, its own properties.
+///
+/// `encoding/json` treats fields of embedded structs as fields of the outer one.
+fn struct_fields(
+    imports: &mut Imports,
+    hoisted: &mut Vec<String>,
+    node: &ObjectNode,
+    fields: String,
+    deepth: usize,
+    tabsize: usize,
+    options: &GoOptions,
+) -> Result<String, Diagnostic> {
+    let indent = " ".repeat(deepth * tabsize);
+    let mut lines = node
+        .extends
+        .iter()
+        .map(|base| {
+            let ty = iterate_array(
+                imports,
+                hoisted,
+                &Node::Ref(base.clone()),
+                "",
+                deepth,
+                tabsize,
+                options,
+            )?;
+            Ok(format!("{}{}", indent, ty))
+        })
+        .collect::<Result<Vec<String>, Diagnostic>>()?;
+    if lines.is_empty() || !fields.is_empty() {
+        lines.push(fields);
+    }
+    Ok(lines.join("\n"))
+}
+
+/// `*` if the property may be absent or null.
+///
+/// Slices, maps and `interface{}` can be nil already, they are kept as is.
+fn pointer(p: &Property) -> &'static str {
+    let is_nillable = match &p.body {
+        Node::Array(_) | Node::Map(_) => true,
+        Node::Union(node) => node.discriminator.is_none(),
+        Node::Keyword(node) => {
+            matches!(node.value, Keywords::Any) || node.string_format() == Some(StringFormat::Byte)
+        }
+        _ => false,
+    };
+    if (p.optional || p.nullable) && !is_nillable {
+        "*"
+    } else {
+        ""
     }
-    result
 }
 
-fn iterate_array(imports: &mut Imports, node: &Node, tabsize: usize) -> String {
+/// Tags of encoders holding the name of a field, other tags like `validate`
+/// hold rules of their own.
+pub const NAME_TAGS: [&str; 7] = [
+    "bson",
+    "db",
+    "mapstructure",
+    "msgpack",
+    "toml",
+    "xml",
+    "yaml",
+];
+
+/// `json:"name"` followed by `tags` of options in order, names are followed by `omitempty`
+/// if the property is optional.
+///
+/// A tag not in `NAME_TAGS` is only `omitempty` if the property is optional, and left out
+/// if it is not. `go.tags` of the property replace the generated ones, other keys of it
+/// come last sorted.
+fn struct_tag(p: &Property, options: &GoOptions) -> String {
+    let name = |naming: Option<Naming>| {
+        let name = rename(p.wire_name(), naming);
+        if p.optional {
+            format!("{},omitempty", name)
+        } else {
+            name
+        }
+    };
+    let mut tags: Vec<(&str, String)> = vec![("json", name(None))];
+    for tag in options.tags.iter() {
+        let value = match p.go_tags.get(&tag.key) {
+            Some(v) => v.clone(),
+            None if NAME_TAGS.contains(&tag.key.as_str()) => name(tag.naming),
+            None if p.optional => String::from("omitempty"),
+            None => continue,
+        };
+        tags.push((&tag.key, value));
+    }
+    for (key, value) in p.go_tags.iter() {
+        if !options.tags.iter().any(|t| &t.key == key) {
+            tags.push((key, value.clone()));
+        }
+    }
+    let tags: Vec<String> = tags
+        .iter()
+        .map(|(key, value)| format!("{}:{}", key, go_string(value)))
+        .collect();
+    format!("`{}`", tags.join(" "))
+}
+
+/// Name of the field of a property, `naming` of `PascalCase` follows golint,
+/// `userId` is `UserID`.
+pub fn field_name(p: &Property, naming: Option<Naming>) -> String {
+    match naming {
+        Some(Naming::Pascal) => to_snake_case(&p.identifier)
+            .split('_')
+            .map(|word| match word.to_uppercase() {
+                upper if INITIALISMS.contains(&upper.as_str()) => upper,
+                _ => capitalize(word),
+            })
+            .collect(),
+        _ => capitalize(&p.identifier),
+    }
+}
+
+/// Initialisms golint wants to be in one case.
+const INITIALISMS: [&str; 38] = [
+    "ACL", "API", "ASCII", "CPU", "CSS", "DNS", "EOF", "GUID", "HTML", "HTTP", "HTTPS", "ID", "IP",
+    "JSON", "LHS", "QPS", "RAM", "RHS", "RPC", "SLA", "SMTP", "SQL", "SSH", "TCP", "TLS", "TTL",
+    "UDP", "UI", "UID", "UUID", "URI", "URL", "UTF8", "VM", "XML", "XMPP", "XSRF", "XSS",
+];
+
+/// Render the go type of a node, `hint` is the name used if it must be hoisted.
+fn iterate_array(
+    imports: &mut Imports,
+    hoisted: &mut Vec<String>,
+    node: &Node,
+    hint: &str,
+    deepth: usize,
+    tabsize: usize,
+    options: &GoOptions,
+) -> Result<String, Diagnostic> {
     let mut result = String::new();
     match node {
         Node::StringLiteral(_) => result += "string",
-        Node::NumberLiteral(_) => result += "int",
-        Node::Keyword(node) => result += &node.value.to_string(SupportedLang::Go),
+        Node::NumberLiteral(node) => result += &number(node.format),
+        Node::Keyword(node) => result += &keyword(imports, node),
         Node::Object(node) => {
-            let r: String = node
+            let r = node
                 .values
                 .iter()
                 .enumerate()
                 .map(|(idx, v)| {
                     let is_last = idx == node.values.len() - 1;
-                    iterate_properties(imports, v, 1, tabsize, is_last)
+                    iterate_properties(
+                        imports,
+                        hoisted,
+                        v,
+                        hint,
+                        deepth + 1,
+                        tabsize,
+                        is_last,
+                        options,
+                    )
                 })
-                .collect();
-            result += &format!("struct {{\n{}\n}}", r);
+                .collect::<Result<String, _>>()?;
+            let fields = struct_fields(imports, hoisted, node, r, deepth + 1, tabsize, options)?;
+            let indent = " ".repeat(deepth * tabsize);
+            result += &format!("struct {{\n{}\n{}}}", fields, indent);
         }
         Node::Array(node) => {
-            let r = iterate_array(imports, &node.items, tabsize);
+            let item_hint = format!("{}Item", hint);
+            let r = iterate_array(
+                imports,
+                hoisted,
+                &node.items,
+                &item_hint,
+                deepth,
+                tabsize,
+                options,
+            )?;
             result += &format!("[]{}", r);
         }
+        Node::Map(node) => {
+            result += &render_map(imports, hoisted, node, hint, deepth, tabsize, options)?
+        }
+        Node::Tuple(node) => {
+            let params = params_of(&node.items);
+            let t = render_tuple(imports, hoisted, node, hint, &params, tabsize, options)?;
+            hoisted.push(t + "\n");
+            result += &format!("{}{}", hint, use_params(&params));
+        }
+        Node::Enum(node) => {
+            hoisted.push(render_enum(
+                &node.members,
+                &node.r#type,
+                node.format,
+                hint,
+                hint,
+                tabsize,
+            ));
+            result += hint;
+        }
         Node::Ref(node) => {
-            let mut s = capitalize(&node.name);
-            if !node.path.is_empty() {
-                let ref_token = format!("[selien-ref]{}[selien-ref]", node.path);
-                s = format!("{}{}", ref_token, capitalize(&node.name));
-
-                imports.push(Import::Ref(RefImport {
-                    name: capitalize(&node.name),
-                    from: PathBuf::from(&node.path),
-                }));
-            }
-
-            result += &s
+            result += &ref_type(imports, hoisted, node, hint, deepth, tabsize, options)?
         }
+        Node::Param(node) => result += &node.name,
         Node::Dyn(node) => {
             let fp = PathBuf::from(&node.from);
             let s = format!(
@@ -389,34 +784,308 @@ fn iterate_array(imports: &mut Imports, node: &Node, tabsize: usize) -> String {
 
             result += &s;
         }
-        Node::Union(_) => {
-            warn!("Union type using in golang array type. interface{{}} type will be generated.");
-            result += "interface{}"
-        }
-        Node::Split(_) => {
-            error!("Split-type can only use on top-level.");
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1);
-            }
-        }
-        Node::Empty => {
-            error!("Empty node.");
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1);
-            }
+        Node::Union(
+            node @ UnionNode {
+                discriminator: Some(d),
+                ..
+            },
+        ) => {
+            let params = params_of(&node.types);
+            let t =
+                render_discriminated(imports, hoisted, node, d, hint, &params, tabsize, options)?;
+            hoisted.push(t + "\n");
+            result += &format!("{}{}", hint, use_params(&params));
         }
+        Node::Union(_) => result += "interface{}",
+        Node::Split(_) => return Err(unexpected("split node")),
+        Node::Empty => return Err(unexpected("empty node")),
     }
-    result
+    Ok(result)
+}
+
+/// `Name`, or `Name[A, B]` with type arguments, `hint` is used for args must be hoisted.
+fn ref_type(
+    imports: &mut Imports,
+    hoisted: &mut Vec<String>,
+    node: &RefNode,
+    hint: &str,
+    deepth: usize,
+    tabsize: usize,
+    options: &GoOptions,
+) -> Result<String, Diagnostic> {
+    let mut s = capitalize(&node.name);
+    if !node.path.is_empty() {
+        let ref_token = format!("[selien-ref]{}[selien-ref]", node.path);
+        s = format!("{}{}", ref_token, capitalize(&node.name));
+
+        imports.push(Import::Ref(RefImport {
+            name: capitalize(&node.name),
+            from: PathBuf::from(&node.path),
+        }));
+    }
+    if node.args.is_empty() {
+        return Ok(s);
+    }
+
+    let args = node
+        .args
+        .iter()
+        .enumerate()
+        .map(|(idx, a)| {
+            let hint = format!("{}Arg{}", hint, idx);
+            iterate_array(imports, hoisted, a, &hint, deepth, tabsize, options)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(format!("{}[{}]", s, args.join(", ")))
+}
+
+/// `[T any, E any]` to declare a generic type, empty if not generic.
+fn declare_params(params: &[String]) -> String {
+    if params.is_empty() {
+        return String::new();
+    }
+    let params: Vec<String> = params.iter().map(|p| format!("{} any", p)).collect();
+    format!("[{}]", params.join(", "))
+}
+
+/// `[T, E]` to use a generic type declared by `declare_params`.
+fn use_params(params: &[String]) -> String {
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("[{}]", params.join(", "))
+    }
+}
+
+/// `map[K]V`
+fn render_map(
+    imports: &mut Imports,
+    hoisted: &mut Vec<String>,
+    node: &MapNode,
+    hint: &str,
+    deepth: usize,
+    tabsize: usize,
+    options: &GoOptions,
+) -> Result<String, Diagnostic> {
+    let k = iterate_array(
+        imports,
+        hoisted,
+        &node.keys,
+        &format!("{}Key", hint),
+        deepth,
+        tabsize,
+        options,
+    )?;
+    let v = iterate_array(
+        imports,
+        hoisted,
+        &node.values,
+        &format!("{}Value", hint),
+        deepth,
+        tabsize,
+        options,
+    )?;
+    Ok(format!("map[{}]{}", k, v))
+}
+
+/// A struct with one field per item, encoded as a JSON array by `MarshalJSON` and `UnmarshalJSON`.
+fn render_tuple(
+    imports: &mut Imports,
+    hoisted: &mut Vec<String>,
+    node: &TupleNode,
+    id: &str,
+    params: &[String],
+    tabsize: usize,
+    options: &GoOptions,
+) -> Result<String, Diagnostic> {
+    let i = " ".repeat(tabsize);
+    let n = node.items.len();
+    let decl = format!("{}{}", id, declare_params(params));
+    let name = format!("{}{}", id, use_params(params));
+    imports.push(Import::Dyn(DynImport {
+        name: String::from("json"),
+        from: String::from("encoding/json"),
+    }));
+    imports.push(Import::Dyn(DynImport {
+        name: String::from("fmt"),
+        from: String::from("fmt"),
+    }));
+
+    let fields = node
+        .items
+        .iter()
+        .enumerate()
+        .map(|(idx, item)| {
+            let hint = format!("{}Item{}", id, idx);
+            let t = iterate_array(imports, hoisted, item, &hint, 1, tabsize, options)?;
+            Ok(format!("{}Item{} {}", i, idx, t))
+        })
+        .collect::<Result<Vec<String>, Diagnostic>>()?;
+    let values: Vec<String> = (0..n).map(|idx| format!("t.Item{}", idx)).collect();
+    let unmarshal: String = (0..n)
+        .map(|idx| {
+            format!(
+                "{i}if err := json.Unmarshal(items[{idx}], &t.Item{idx}); err != nil {{\n{i}{i}return err\n{i}}}\n"
+            )
+        })
+        .collect();
+
+    Ok(format!(
+        "type {decl} struct {{\n{f}\n}}\n\n\
+        func (t {name}) MarshalJSON() ([]byte, error) {{\n\
+        {i}return json.Marshal([]interface{{}}{{{v}}})\n\
+        }}\n\n\
+        func (t *{name}) UnmarshalJSON(data []byte) error {{\n\
+        {i}var items []json.RawMessage\n\
+        {i}if err := json.Unmarshal(data, &items); err != nil {{\n\
+        {i}{i}return err\n\
+        {i}}}\n\
+        {i}if len(items) != {n} {{\n\
+        {i}{i}return fmt.Errorf(\"expected {n} items for {id}, got %d\", len(items))\n\
+        {i}}}\n\
+        {unmarshal}{i}return nil\n\
+        }}\n",
+        f = fields.join("\n"),
+        v = values.join(", "),
+    ))
+}
+
+/// A struct with a pointer field per variant, only one of them is set.
+///
+/// JSON is decoded into the variant named by the discriminator property.
+#[allow(clippy::too_many_arguments)]
+fn render_discriminated(
+    imports: &mut Imports,
+    hoisted: &mut Vec<String>,
+    node: &UnionNode,
+    d: &Discriminator,
+    id: &str,
+    params: &[String],
+    tabsize: usize,
+    options: &GoOptions,
+) -> Result<String, Diagnostic> {
+    let i = " ".repeat(tabsize);
+    let decl = format!("{}{}", id, declare_params(params));
+    let name = format!("{}{}", id, use_params(params));
+    imports.push(Import::Dyn(DynImport {
+        name: String::from("json"),
+        from: String::from("encoding/json"),
+    }));
+    imports.push(Import::Dyn(DynImport {
+        name: String::from("fmt"),
+        from: String::from("fmt"),
+    }));
+
+    // (field, type, discriminator value)
+    let variants = node
+        .types
+        .iter()
+        .zip(d.values.iter())
+        .enumerate()
+        .map(|(idx, (t, value))| {
+            let field = variant_field(t, idx);
+            let ty = iterate_array(
+                imports,
+                hoisted,
+                t,
+                &format!("{}{}", id, field),
+                1,
+                tabsize,
+                options,
+            )?;
+            Ok((field, ty, value))
+        })
+        .collect::<Result<Vec<(String, String, &String)>, Diagnostic>>()?;
+
+    let fields: Vec<String> = variants
+        .iter()
+        .enumerate()
+        .map(|(idx, (field, ty, _))| {
+            let doc = doc_comment(
+                node.descriptions.get(idx).and_then(|d| d.as_ref()),
+                None,
+                field,
+                &i,
+            );
+            format!("{}{}{} *{}", doc, i, field, ty)
+        })
+        .collect();
+    let marshal: String = variants
+        .iter()
+        .map(|(field, _, _)| {
+            format!("{i}case u.{field} != nil:\n{i}{i}return json.Marshal(u.{field})\n")
+        })
+        .collect();
+    let unmarshal: String = variants
+        .iter()
+        .map(|(field, ty, value)| {
+            format!(
+                "{i}case {value:?}:\n{i}{i}u.{field} = new({ty})\n{i}{i}return json.Unmarshal(data, u.{field})\n"
+            )
+        })
+        .collect();
+    let accessors: Vec<String> = variants
+        .iter()
+        .map(|(field, ty, _)| {
+            format!(
+                "func (u {name}) As{field}() (*{ty}, bool) {{\n{i}return u.{field}, u.{field} != nil\n}}\n"
+            )
+        })
+        .collect();
+
+    Ok(format!(
+        "type {decl} struct {{\n{f}\n}}\n\n\
+        func (u {name}) MarshalJSON() ([]byte, error) {{\n\
+        {i}switch {{\n\
+        {marshal}\
+        {i}}}\n\
+        {i}return nil, fmt.Errorf(\"no variant of {id} is set\")\n\
+        }}\n\n\
+        func (u *{name}) UnmarshalJSON(data []byte) error {{\n\
+        {i}var d struct {{\n\
+        {i}{i}Value string `json:\"{p}\"`\n\
+        {i}}}\n\
+        {i}if err := json.Unmarshal(data, &d); err != nil {{\n\
+        {i}{i}return err\n\
+        {i}}}\n\
+        {i}*u = {name}{{}}\n\
+        {i}switch d.Value {{\n\
+        {unmarshal}\
+        {i}}}\n\
+        {i}return fmt.Errorf(\"unknown {p} %q for {id}\", d.Value)\n\
+        }}\n\n\
+        {a}",
+        f = fields.join("\n"),
+        p = d.property,
+        a = accessors.join("\n"),
+    ))
+}
+
+/// `type Name string` and a const of each member.
+///
+/// Members are named by `prefix` and the member, a enum in a definition is prefixed by its name
+/// as members of every enum are constants of the package.
+fn render_enum(
+    members: &ast_enum::Members,
+    m_type: &ast_enum::MembersType,
+    format: Option<NumberFormat>,
+    id: &str,
+    prefix: &str,
+    tabsize: usize,
+) -> String {
+    let ty = match m_type {
+        ast_enum::MembersType::String => String::from("string"),
+        ast_enum::MembersType::Number => number(format),
+    };
+    let s = iterate_members(members, m_type, id, prefix, tabsize);
+    format!("type {} {}\nconst (\n{}\n)\n\n", id, ty, s)
 }
 
 fn iterate_members(
     members: &ast_enum::Members,
     m_type: &ast_enum::MembersType,
     type_id: &str,
+    prefix: &str,
     tabsize: usize,
 ) -> String {
     let indent = " ".repeat(tabsize);
@@ -426,10 +1095,17 @@ fn iterate_members(
         ast_enum::MembersType::String => {
             for (idx, m) in members.iter().enumerate() {
                 let is_last = idx == members.len() - 1;
+                let name = member_name(prefix, m);
                 let mut s = format!(
-                    "{}{} {} = \"{}\"",
+                    "{}{}{} {} = \"{}\"",
+                    doc_comment(
+                        m.description.as_ref(),
+                        m.deprecated.as_ref(),
+                        &name,
+                        &indent
+                    ),
                     indent,
-                    capitalize(&m.identifier),
+                    name,
                     capitalize(type_id),
                     m.value
                 );
@@ -443,10 +1119,17 @@ fn iterate_members(
         ast_enum::MembersType::Number => {
             for (idx, m) in members.iter().enumerate() {
                 let is_last = idx == members.len() - 1;
+                let name = member_name(prefix, m);
                 let mut s = format!(
-                    "{}{} {} = {}",
+                    "{}{}{} {} = {}",
+                    doc_comment(
+                        m.description.as_ref(),
+                        m.deprecated.as_ref(),
+                        &name,
+                        &indent
+                    ),
                     indent,
-                    capitalize(&m.identifier),
+                    name,
                     capitalize(type_id),
                     m.value
                 );
@@ -462,39 +1145,719 @@ fn iterate_members(
     result
 }
 
+/// `// Name description`, golint wants a doc comment to start with the name.
+fn doc_comment(
+    description: Option<&String>,
+    deprecated: Option<&String>,
+    name: &str,
+    indent: &str,
+) -> String {
+    let mut text = match description.map(|d| d.trim()) {
+        Some(description) => {
+            // `userName is ...` is `UserName is ...`.
+            let (first, rest) = description.split_once(' ').unwrap_or((description, ""));
+            if capitalize(first) == name {
+                format!("{} {}", name, rest).trim_end().to_string()
+            } else {
+                format!("{} {}", name, description)
+            }
+        }
+        None if deprecated.is_some() => format!("{} is deprecated.", name),
+        None => return String::new(),
+    };
+    // a paragraph of its own, which tools like staticcheck look for.
+    if let Some(reason) = deprecated.map(|r| r.trim()) {
+        let reason = if reason.is_empty() {
+            "do not use."
+        } else {
+            reason
+        };
+        text += &format!("\n\nDeprecated: {}", reason);
+    }
+    text.lines()
+        .map(|line| match line.trim_end() {
+            "" => format!("{}//\n", indent),
+            line => format!("{}// {}\n", indent, line),
+        })
+        .collect()
+}
+
+fn keyword(imports: &mut Imports, k: &KeywordNode) -> String {
+    match k.value {
+        Keywords::Any => String::from("interface{}"),
+        Keywords::Boolean => String::from("bool"),
+        Keywords::Number => number(k.number_format()),
+        // other formats have no type in the standard library
+        // which is encoded the same way by `encoding/json`.
+        Keywords::String => match k.string_format() {
+            Some(StringFormat::DateTime) => {
+                imports.push(Import::Dyn(DynImport {
+                    name: String::from("time"),
+                    from: String::from("time"),
+                }));
+                String::from("time.Time")
+            }
+            Some(StringFormat::Byte) => String::from("[]byte"),
+            _ => String::from("string"),
+        },
+    }
+}
+
+/// `int` unless the number has a format, which are all go types.
+fn number(format: Option<NumberFormat>) -> String {
+    format.map_or(String::from("int"), |f| f.name().to_string())
+}
+
+/// `NewName() Name`, a `Name` with the defaults of its properties.
+fn render_constructor(
+    imports: &mut Imports,
+    node: &ObjectNode,
+    id: &str,
+    params: &[String],
+    tabsize: usize,
+    options: &GoOptions,
+) -> Result<String, Diagnostic> {
+    let i = " ".repeat(tabsize);
+    let ty = format!("{}{}", id, use_params(params));
+    Ok(format!(
+        "\n// New{id} returns a {id} with the defaults of its properties.\n\
+        func New{id}{p}() {ty} {{\n\
+        {i}var v {ty}\n\
+        {d}\
+        {i}return v\n\
+        }}\n",
+        p = declare_params(params),
+        d = defaults(imports, node, "v", id, &i, tabsize, options)?,
+    ))
+}
+
+/// Assignments of the defaults of `node` to the fields of `expr`, each line starts with `indent`.
+///
+/// `parent` is the name `node` is hoisted by, see `iterate_properties`.
+fn defaults(
+    imports: &mut Imports,
+    node: &ObjectNode,
+    expr: &str,
+    parent: &str,
+    indent: &str,
+    tabsize: usize,
+    options: &GoOptions,
+) -> Result<String, Diagnostic> {
+    let mut result = String::new();
+    for p in node.values.iter() {
+        let field = format!("{}.{}", expr, field_name(p, options.naming));
+        let hint = property_hint(parent, p);
+        match (&p.default, &p.body) {
+            // nil is the zero value.
+            (Some(DefaultValue::Null), _) => {}
+            (Some(d), body) if pointer(p) == "*" => {
+                let ty = iterate_array(imports, &mut vec![], body, &hint, 1, tabsize, options)?;
+                result += &format!("{}{} = new({})\n", indent, field, ty);
+                result += &format!(
+                    "{}*{} = {}\n",
+                    indent,
+                    field,
+                    default_value(imports, body, d, &hint, tabsize, options)?
+                );
+            }
+            (Some(d), body) => {
+                result += &format!(
+                    "{}{} = {}\n",
+                    indent,
+                    field,
+                    default_value(imports, body, d, &hint, tabsize, options)?
+                );
+            }
+            (None, Node::Object(n)) if !p.optional && !p.nullable => {
+                result += &defaults(imports, n, &field, &hint, indent, tabsize, options)?;
+            }
+            (None, _) => {}
+        }
+    }
+    Ok(result)
+}
+
+/// Go expression of a default of `node`, which is checked by `transformer`.
+///
+/// `hint` is the name of `node` if it is hoisted.
+fn default_value(
+    imports: &mut Imports,
+    node: &Node,
+    d: &DefaultValue,
+    hint: &str,
+    tabsize: usize,
+    options: &GoOptions,
+) -> Result<String, Diagnostic> {
+    let value = match (d, node) {
+        (DefaultValue::String(s), _) => go_string(s),
+        (DefaultValue::Number(n), _) => n.clone(),
+        (DefaultValue::Boolean(b), _) => b.to_string(),
+        (DefaultValue::Null, _) => String::from("nil"),
+        // members are named without the enum.
+        (DefaultValue::Member(m), Node::Ref(r)) => {
+            let ty = ref_type(imports, &mut vec![], r, "", 1, tabsize, options)?;
+            let prefix = ty.strip_suffix(&capitalize(&r.name)).unwrap_or_default();
+            format!("{}{}", prefix, capitalize(m))
+        }
+        (DefaultValue::Member(m), Node::Enum(_)) => format!("{}{}", hint, capitalize(m)),
+        (DefaultValue::Array(items), Node::Array(a)) => {
+            let ty = iterate_array(imports, &mut vec![], node, hint, 1, tabsize, options)?;
+            let item_hint = format!("{}Item", hint);
+            let items = items
+                .iter()
+                .map(|item| default_value(imports, &a.items, item, &item_hint, tabsize, options))
+                .collect::<Result<Vec<_>, _>>()?;
+            format!("{}{{{}}}", ty, items.join(", "))
+        }
+        _ => return Err(unexpected("default of another type")),
+    };
+    Ok(value)
+}
+
+/// `Validate() error` of the type of `ast`, returning the first value breaking a constraint.
+///
+/// Values of other types are checked by their `Validate`, if they have one.
+/// Empty if the type is not a struct or a enum and has nothing to check.
+pub fn generate_go_validate(
+    ast: &AST,
+    imports: &mut Imports,
+    tabsize: usize,
+    options: &GoOptions,
+) -> Result<String, Diagnostic> {
+    let i = " ".repeat(tabsize);
+    match ast {
+        AST::Enum(enum_ast) => {
+            let names: Vec<String> = enum_ast
+                .members
+                .iter()
+                .map(|m| capitalize(&m.identifier))
+                .collect();
+            let values: Vec<String> = enum_ast
+                .members
+                .iter()
+                .map(|m| match enum_ast.r#type {
+                    ast_enum::MembersType::String => go_string(&m.value),
+                    ast_enum::MembersType::Number => m.value.clone(),
+                })
+                .collect();
+            let mut v = Validator::new(imports, &enum_ast.identifier, tabsize, options);
+            let err = v.error(&ValuePath::default(), &one_of_message(&values));
+            Ok(format!(
+                "\nfunc (v {id}) Validate() error {{\n\
+                {i}switch v {{\n\
+                {i}case {names}:\n\
+                {i}{i}return nil\n\
+                {i}}}\n\
+                {i}return {err}\n\
+                }}\n\n",
+                id = capitalize(&enum_ast.identifier),
+                names = names.join(", "),
+            ))
+        }
+        AST::TypeAlias(type_alias_ast) => {
+            let body = match &type_alias_ast.body {
+                Node::Split(split) => match split.nodes.get(go::ID) {
+                    Some(node) => node.as_ref(),
+                    None => return Ok(String::new()),
+                },
+                body => body,
+            };
+            let body = &self_refs_as_pointers(body, &type_alias_ast.identifier);
+            let id = capitalize(&type_alias_ast.identifier);
+            let mut v = Validator::new(imports, &type_alias_ast.identifier, tabsize, options);
+            let path = ValuePath::default();
+            let (checks, is_struct) = match body {
+                Node::Object(_)
+                | Node::Tuple(_)
+                | Node::Union(UnionNode {
+                    discriminator: Some(_),
+                    ..
+                }) => (v.check(body, "v", &path, 1), true),
+                Node::Keyword(KeywordNode {
+                    value: Keywords::String,
+                    ..
+                }) => (v.check(body, "string(v)", &path, 1), false),
+                Node::Keyword(_)
+                | Node::StringLiteral(_)
+                | Node::NumberLiteral(_)
+                | Node::Array(_)
+                | Node::Map(_) => (v.check(body, "v", &path, 1), false),
+                // methods are not inherited by `type Name Other`.
+                Node::Ref(node) => {
+                    let ty = ref_type(v.imports, &mut vec![], node, &id, 0, tabsize, options)?;
+                    (v.call(&format!("{}(v)", ty), &path, 1), false)
+                }
+                _ => return Ok(String::new()),
+            };
+            if checks.is_empty() && !is_struct {
+                return Ok(String::new());
+            }
+            Ok(format!(
+                "{p}\nfunc (v {id}{params}) Validate() error {{\n{checks}{i}return nil\n}}\n\n",
+                p = v.patterns.concat(),
+                params = use_params(&type_alias_ast.type_parameters),
+            ))
+        }
+    }
+}
+
+/// Renders the checks of `generate_go_validate`.
+struct Validator<'a> {
+    imports: &'a mut Imports,
+    /// `var pattern... = regexp.MustCompile(...)`, declared before the method.
+    patterns: Vec<String>,
+    id: String,
+    tabsize: usize,
+    options: &'a GoOptions,
+}
+
+impl<'a> Validator<'a> {
+    fn new(imports: &'a mut Imports, id: &str, tabsize: usize, options: &'a GoOptions) -> Self {
+        Validator {
+            imports,
+            patterns: vec![],
+            id: id.to_string(),
+            tabsize,
+            options,
+        }
+    }
+
+    /// Statements returning a error if `expr`, a value of `node` at `path`, is not valid.
+    fn check(&mut self, node: &Node, expr: &str, path: &ValuePath, deepth: usize) -> String {
+        let i = " ".repeat(deepth * self.tabsize);
+        match node {
+            Node::Keyword(node) => self.constraints(&node.constraints, expr, path, deepth),
+            Node::StringLiteral(node) => {
+                let value = go_string(&node.value);
+                let cond = format!("{} != {}", expr, value);
+                self.fail(&cond, path, &literal_message(&value), deepth)
+            }
+            Node::NumberLiteral(node) => {
+                let cond = format!("{} != {}", expr, node.value);
+                self.fail(&cond, path, &literal_message(&node.value), deepth)
+            }
+            Node::Enum(node) => {
+                let values: Vec<String> = node
+                    .members
+                    .iter()
+                    .map(|m| match node.r#type {
+                        ast_enum::MembersType::String => go_string(&m.value),
+                        ast_enum::MembersType::Number => m.value.clone(),
+                    })
+                    .collect();
+                let cond: Vec<String> = values
+                    .iter()
+                    .map(|v| format!("{} != {}", expr, v))
+                    .collect();
+                self.fail(&cond.join(" && "), path, &one_of_message(&values), deepth)
+            }
+            Node::Object(node) => {
+                let mut result = String::new();
+                for base in node.extends.iter() {
+                    let field = format!("{}.{}", expr, capitalize(&base.name));
+                    result += &self.call(&field, path, deepth);
+                }
+                for p in node.values.iter() {
+                    result += &self.property(p, expr, path, deepth);
+                }
+                result
+            }
+            Node::Array(node) => {
+                let mut result = self.constraints(&node.constraints, expr, path, deepth);
+                let n = path.loops();
+                let (idx, item) = (format!("i{}", n), format!("x{}", n));
+                let items = self.check(&node.items, &item, &path.index(&idx), deepth + 1);
+                if !items.is_empty() {
+                    result += &format!("{i}for {idx}, {item} := range {expr} {{\n{items}{i}}}\n");
+                }
+                result
+            }
+            Node::Map(node) => {
+                let n = path.loops();
+                let (key, value) = (format!("k{}", n), format!("x{}", n));
+                let values = self.check(&node.values, &value, &path.index(&key), deepth + 1);
+                if values.is_empty() {
+                    return values;
+                }
+                format!("{i}for {key}, {value} := range {expr} {{\n{values}{i}}}\n")
+            }
+            Node::Tuple(node) => node
+                .items
+                .iter()
+                .enumerate()
+                .map(|(idx, item)| {
+                    let field = format!("{}.Item{}", expr, idx);
+                    self.check(item, &field, &path.position(idx), deepth)
+                })
+                .collect(),
+            // only the variant which is set, see `render_discriminated`.
+            Node::Union(UnionNode {
+                types,
+                discriminator: Some(_),
+                ..
+            }) => types
+                .iter()
+                .enumerate()
+                .map(|(idx, t)| {
+                    let field = format!("{}.{}", expr, variant_field(t, idx));
+                    let call = self.call(&field, path, deepth + 1);
+                    format!("{i}if {field} != nil {{\n{call}{i}}}\n")
+                })
+                .collect(),
+            Node::Ref(_) | Node::Param(_) | Node::Dyn(_) => self.call(expr, path, deepth),
+            Node::Union(_) | Node::Split(_) | Node::Empty => String::new(),
+        }
+    }
+
+    /// Checks of a field of the struct `expr`, skipped if it is nil.
+    fn property(&mut self, p: &Property, expr: &str, path: &ValuePath, deepth: usize) -> String {
+        let i = " ".repeat(deepth * self.tabsize);
+        let field = format!("{}.{}", expr, field_name(p, self.options.naming));
+        let path = path.key(p.wire_name());
+        let is_pointer = !pointer(p).is_empty();
+        if !is_pointer
+            && !((p.optional || p.nullable) && matches!(p.body, Node::Array(_) | Node::Map(_)))
+        {
+            return self.check(&p.body, &field, &path, deepth);
+        }
+        // fields of a pointer to a struct are used without `*`.
+        let value = match &p.body {
+            Node::Keyword(_) | Node::StringLiteral(_) | Node::NumberLiteral(_) | Node::Enum(_)
+                if is_pointer =>
+            {
+                format!("*{}", field)
+            }
+            _ => field.clone(),
+        };
+        let checks = self.check(&p.body, &value, &path, deepth + 1);
+        if checks.is_empty() {
+            return checks;
+        }
+        format!("{i}if {field} != nil {{\n{checks}{i}}}\n")
+    }
+
+    fn constraints(
+        &mut self,
+        constraints: &Constraints,
+        expr: &str,
+        path: &ValuePath,
+        deepth: usize,
+    ) -> String {
+        let mut result = String::new();
+        for check in checks(constraints) {
+            let cond = match &check {
+                Check::MinLength(n) => format!("{} < {}", self.rune_count(expr), n),
+                Check::MaxLength(n) => format!("{} > {}", self.rune_count(expr), n),
+                Check::Pattern(p) => format!("!{}.MatchString({})", self.pattern(p, path), expr),
+                Check::Minimum(n) => format!("float64({}) < {}", expr, n),
+                Check::Maximum(n) => format!("float64({}) > {}", expr, n),
+                Check::MinItems(n) => format!("len({}) < {}", expr, n),
+                Check::MaxItems(n) => format!("len({}) > {}", expr, n),
+            };
+            result += &self.fail(&cond, path, &check.message(), deepth);
+        }
+        result
+    }
+
+    /// `expr.Validate()` if the type of `expr` has it, its error is prefixed by `path`.
+    fn call(&mut self, expr: &str, path: &ValuePath, deepth: usize) -> String {
+        let i = " ".repeat(deepth * self.tabsize);
+        let t = " ".repeat(self.tabsize);
+        let err = match path.is_empty() {
+            true => String::from("err"),
+            false => self.errorf(path, "%w", &["err"]),
+        };
+        format!(
+            "{i}if c, ok := interface{{}}({expr}).(interface{{ Validate() error }}); ok {{\n\
+            {i}{t}if err := c.Validate(); err != nil {{\n\
+            {i}{t}{t}return {err}\n\
+            {i}{t}}}\n\
+            {i}}}\n"
+        )
+    }
+
+    /// `if cond { return <error> }`
+    fn fail(&mut self, cond: &str, path: &ValuePath, message: &str, deepth: usize) -> String {
+        let i = " ".repeat(deepth * self.tabsize);
+        let t = " ".repeat(self.tabsize);
+        let err = self.error(path, message);
+        format!("{i}if {cond} {{\n{i}{t}return {err}\n{i}}}\n")
+    }
+
+    /// `fmt.Errorf("path: message", vars...)`
+    fn error(&mut self, path: &ValuePath, message: &str) -> String {
+        let message = message.replace('%', "%%");
+        self.errorf(path, &message, &[])
+    }
+
+    /// `fmt.Errorf` of `format` prefixed by `path`, `args` follow the variables of `path`.
+    fn errorf(&mut self, path: &ValuePath, format: &str, args: &[&str]) -> String {
+        self.import("fmt");
+        let prefix = path.render("", |s| s.replace('%', "%%"), |_| String::from("%v"));
+        let mut values: Vec<&str> = path.vars();
+        values.extend(args);
+        let values: String = values.iter().map(|v| format!(", {}", v)).collect();
+        format!(
+            "fmt.Errorf({}{})",
+            go_string(&format!("{}{}", prefix, format)),
+            values
+        )
+    }
+
+    fn rune_count(&mut self, expr: &str) -> String {
+        self.import("unicode/utf8");
+        format!("utf8.RuneCountInString({})", expr)
+    }
+
+    /// Name of a package level `regexp.Regexp` of `pattern`, compiled once.
+    fn pattern(&mut self, pattern: &str, path: &ValuePath) -> String {
+        self.import("regexp");
+        let hint = format!("pattern{}", path.hint(&self.id));
+        let mut name = hint.clone();
+        let mut n = 1;
+        while self
+            .patterns
+            .iter()
+            .any(|p| p.starts_with(&format!("\nvar {} ", name)))
+        {
+            n += 1;
+            name = format!("{}{}", hint, n);
+        }
+        self.patterns.push(format!(
+            "\nvar {} = regexp.MustCompile({})\n",
+            name,
+            go_string(pattern)
+        ));
+        name
+    }
+
+    fn import(&mut self, from: &str) {
+        self.imports.push(Import::Dyn(DynImport {
+            name: from.rsplit('/').next().unwrap().to_string(),
+            from: from.to_string(),
+        }));
+    }
+}
+
+/// A interpreted go string literal, JSON escapes are valid in go.
+fn go_string(s: &str) -> String {
+    serde_json::to_string(s).unwrap()
+}
+
 // unit test here
 #[cfg(test)]
 mod test {
+    use super::{generate_go, generate_go_validate};
     use crate::{
         generator::{lang::for_test, types::DEFAULT_TABSIZE},
-        types::lang::SupportedLang,
+        types::config::{GoOptions, GoTag},
     };
 
     #[test]
     fn nested_object() {
         let ast = for_test::nested_object_ast();
-        let result = ast.generator(&SupportedLang::Go, &mut vec![], &DEFAULT_TABSIZE);
+        let result =
+            generate_go(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default()).unwrap();
         insta::assert_yaml_snapshot!(result);
     }
 
     #[test]
     fn nested_array() {
         let ast = for_test::nested_array_ast();
-        let result = ast.generator(&SupportedLang::Go, &mut vec![], &DEFAULT_TABSIZE);
+        let result =
+            generate_go(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default()).unwrap();
         insta::assert_yaml_snapshot!(result);
     }
 
     #[test]
     fn object_array() {
         let ast = for_test::object_array_ast();
-        let result = ast.generator(&SupportedLang::Go, &mut vec![], &DEFAULT_TABSIZE);
+        let result =
+            generate_go(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default()).unwrap();
         insta::assert_yaml_snapshot!(result);
     }
 
     #[test]
     fn string_enum() {
         let ast = for_test::string_enum_ast();
-        let result = ast.generator(&SupportedLang::Go, &mut vec![], &DEFAULT_TABSIZE);
+        let result =
+            generate_go(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default()).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn optional_object() {
+        let ast = for_test::optional_object_ast();
+        let result =
+            generate_go(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default()).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn map() {
+        let ast = for_test::map_ast();
+        let result =
+            generate_go(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default()).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn tuple() {
+        let ast = for_test::tuple_ast();
+        let result =
+            generate_go(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default()).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn number_format() {
+        let ast = for_test::number_format_ast();
+        let result =
+            generate_go(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default()).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn string_format() {
+        let ast = for_test::string_format_ast();
+        let result =
+            generate_go(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default()).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn discriminated_union() {
+        let ast = for_test::discriminated_union_ast();
+        let result =
+            generate_go(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default()).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn extends() {
+        let ast = for_test::extends_ast();
+        let result =
+            generate_go(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default()).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn described() {
+        let ast = for_test::described_ast();
+        let result =
+            generate_go(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default()).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn described_enum() {
+        let ast = for_test::described_enum_ast();
+        let result =
+            generate_go(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default()).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn deprecated() {
+        let ast = for_test::deprecated_ast();
+        let result =
+            generate_go(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default()).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn generic() {
+        let ast = for_test::generic_ast();
+        let result =
+            generate_go(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default()).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn generic_ref() {
+        let ast = for_test::generic_ref_ast();
+        let result =
+            generate_go(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default()).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn defaults() {
+        let ast = for_test::defaults_ast();
+        let result =
+            generate_go(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default()).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn self_ref() {
+        let ast = for_test::tree_ast();
+        let result =
+            generate_go(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default()).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn inline_enum() {
+        let ast = for_test::inline_enum_ast();
+        let result =
+            generate_go(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default()).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn validate() {
+        let ast = for_test::constrained_ast();
+        let result =
+            generate_go_validate(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default())
+                .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn validate_enum() {
+        let ast = for_test::string_enum_ast();
+        let result =
+            generate_go_validate(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default())
+                .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn validate_inline_enum() {
+        let ast = for_test::inline_enum_ast();
+        let result =
+            generate_go_validate(&ast, &mut vec![], DEFAULT_TABSIZE.go, &GoOptions::default())
+                .unwrap();
         insta::assert_yaml_snapshot!(result);
     }
+
+    #[test]
+    fn validate_tag() {
+        let ast = for_test::tagged_ast();
+        let options = GoOptions {
+            tags: vec![GoTag {
+                key: String::from("validate"),
+                naming: None,
+            }],
+            ..Default::default()
+        };
+        let result = generate_go(&ast, &mut vec![], DEFAULT_TABSIZE.go, &options).unwrap();
+        // a rule, not a name as in `validate:"userId"`.
+        assert!(
+            result.contains("UserId string `json:\"userId\"`\n"),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains("`json:\"token\" validate:\"required\" db:\"-\"`"),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains("`json:\"expiresAt,omitempty\" validate:\"omitempty\"`"),
+            "{}",
+            result
+        );
+    }
 }
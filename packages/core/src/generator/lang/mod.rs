@@ -1,11 +1,15 @@
 pub mod go;
+pub mod python;
+pub mod rust;
 pub mod typescript;
 
 #[cfg(test)]
-pub(self) mod for_test {
+pub(crate) mod for_test {
+    use std::collections::BTreeMap;
+
     use crate::generator::types::{
         ast_enum,
-        ast_type_alias::{self, Keywords},
+        ast_type_alias::{self, DefaultValue, Format, Keywords, NumberFormat, StringFormat},
         AST,
     };
 
@@ -31,29 +35,37 @@ pub(self) mod for_test {
         let head = ast_type_alias::Property {
             identifier: String::from("head"),
             body: ast_type_alias::Node::Object(ast_type_alias::ObjectNode {
+                extends: vec![],
                 values: vec![ast_type_alias::Property {
                     identifier: String::from("cmd"),
                     body: ast_type_alias::Node::StringLiteral(ast_type_alias::StringLiteralNode {
                         value: String::from("hello"),
                     }),
+                    ..Default::default()
                 }],
             }),
+            ..Default::default()
         };
         let body = ast_type_alias::Property {
             identifier: String::from("body"),
             body: ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
                 value: Keywords::Boolean,
+                format: None,
+                constraints: Default::default(),
             }),
+            ..Default::default()
         };
         let obj = ast_type_alias::ObjectNode {
+            extends: vec![],
             values: vec![head, body],
         };
-        let ast = AST::TypeAlias(ast_type_alias::TypeAliasAst {
+        AST::TypeAlias(ast_type_alias::TypeAliasAst {
             identifier: String::from("testAst"),
+            type_parameters: vec![],
+            description: None,
+            deprecated: None,
             body: ast_type_alias::Node::Object(obj),
-        });
-
-        ast
+        })
     }
 
     pub fn array_ast() -> AST {
@@ -65,16 +77,20 @@ pub(self) mod for_test {
         type TestArray []int
         */
 
-        let ast = AST::TypeAlias(ast_type_alias::TypeAliasAst {
+        AST::TypeAlias(ast_type_alias::TypeAliasAst {
             identifier: String::from("testArray"),
+            type_parameters: vec![],
+            description: None,
+            deprecated: None,
             body: ast_type_alias::Node::Array(ast_type_alias::ArrayNode {
                 items: Box::new(ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
                     value: Keywords::Number,
+                    format: None,
+                    constraints: Default::default(),
                 })),
+                constraints: Default::default(),
             }),
-        });
-
-        ast
+        })
     }
 
     pub fn nested_array_ast() -> AST {
@@ -86,18 +102,23 @@ pub(self) mod for_test {
         type NestedArray [][]int
         */
 
-        let ast = AST::TypeAlias(ast_type_alias::TypeAliasAst {
+        AST::TypeAlias(ast_type_alias::TypeAliasAst {
             identifier: String::from("nestedArray"),
+            type_parameters: vec![],
+            description: None,
+            deprecated: None,
             body: ast_type_alias::Node::Array(ast_type_alias::ArrayNode {
                 items: Box::new(ast_type_alias::Node::Array(ast_type_alias::ArrayNode {
                     items: Box::new(ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
                         value: Keywords::Number,
+                        format: None,
+                        constraints: Default::default(),
                     })),
+                    constraints: Default::default(),
                 })),
+                constraints: Default::default(),
             }),
-        });
-
-        ast
+        })
     }
 
     pub fn object_array_ast() -> AST {
@@ -126,36 +147,733 @@ pub(self) mod for_test {
         let head = ast_type_alias::Property {
             identifier: String::from("head"),
             body: ast_type_alias::Node::Object(ast_type_alias::ObjectNode {
+                extends: vec![],
                 values: vec![ast_type_alias::Property {
                     identifier: String::from("cmd"),
                     body: ast_type_alias::Node::StringLiteral(ast_type_alias::StringLiteralNode {
                         value: String::from("hello"),
                     }),
+                    ..Default::default()
                 }],
             }),
+            ..Default::default()
         };
         let body = ast_type_alias::Property {
             identifier: String::from("body"),
             body: ast_type_alias::Node::Object(ast_type_alias::ObjectNode {
+                extends: vec![],
                 values: vec![ast_type_alias::Property {
                     identifier: String::from("count"),
                     body: ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
                         value: Keywords::Number,
+                        format: None,
+                        constraints: Default::default(),
                     }),
+                    ..Default::default()
                 }],
             }),
+            ..Default::default()
         };
         let obj = ast_type_alias::ObjectNode {
+            extends: vec![],
             values: vec![head, body],
         };
-        let ast = AST::TypeAlias(ast_type_alias::TypeAliasAst {
+        AST::TypeAlias(ast_type_alias::TypeAliasAst {
             identifier: String::from("objectArray"),
+            type_parameters: vec![],
+            description: None,
+            deprecated: None,
             body: ast_type_alias::Node::Array(ast_type_alias::ArrayNode {
                 items: Box::new(ast_type_alias::Node::Object(obj)),
+                constraints: Default::default(),
+            }),
+        })
+    }
+
+    pub fn optional_object_ast() -> AST {
+        /*
+        ts:
+        export type OptionalObject = {
+            name?: string;
+            nickname: string | null;
+            tags?: string[] | null;
+        };
+
+        go:
+        type OptionalObject struct {
+            Name *string `json:"name,omitempty"`
+            Nickname *string `json:"nickname"`
+            Tags []string `json:"tags,omitempty"`
+        }
+        */
+
+        let string = || {
+            ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+                value: Keywords::String,
+                format: None,
+                constraints: Default::default(),
+            })
+        };
+        let name = ast_type_alias::Property {
+            identifier: String::from("name"),
+            body: string(),
+            optional: true,
+            nullable: false,
+            ..Default::default()
+        };
+        let nickname = ast_type_alias::Property {
+            identifier: String::from("nickname"),
+            body: string(),
+            optional: false,
+            nullable: true,
+            ..Default::default()
+        };
+        let tags = ast_type_alias::Property {
+            identifier: String::from("tags"),
+            body: ast_type_alias::Node::Array(ast_type_alias::ArrayNode {
+                items: Box::new(string()),
+                constraints: Default::default(),
+            }),
+            optional: true,
+            nullable: true,
+            ..Default::default()
+        };
+        AST::TypeAlias(ast_type_alias::TypeAliasAst {
+            identifier: String::from("optionalObject"),
+            type_parameters: vec![],
+            description: None,
+            deprecated: None,
+            body: ast_type_alias::Node::Object(ast_type_alias::ObjectNode {
+                extends: vec![],
+                values: vec![name, nickname, tags],
+            }),
+        })
+    }
+
+    pub fn map_ast() -> AST {
+        /*
+        ts:
+        export type Users = {
+            byName: Record<string, User>;
+            byRole: Record<Role, number[]>;
+        };
+
+        go:
+        type Users struct {
+            ByName map[string]User `json:"byName"`
+            ByRole map[Role][]int `json:"byRole"`
+        }
+        */
+
+        let by_name = ast_type_alias::Property {
+            identifier: String::from("byName"),
+            body: ast_type_alias::Node::Map(ast_type_alias::MapNode {
+                keys: Box::new(ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+                    value: Keywords::String,
+                    format: None,
+                    constraints: Default::default(),
+                })),
+                values: Box::new(ast_type_alias::Node::Ref(ast_type_alias::RefNode {
+                    name: String::from("user"),
+                    path: String::new(),
+                    args: vec![],
+                })),
+            }),
+            ..Default::default()
+        };
+        let by_role = ast_type_alias::Property {
+            identifier: String::from("byRole"),
+            body: ast_type_alias::Node::Map(ast_type_alias::MapNode {
+                keys: Box::new(ast_type_alias::Node::Ref(ast_type_alias::RefNode {
+                    name: String::from("role"),
+                    path: String::new(),
+                    args: vec![],
+                })),
+                values: Box::new(ast_type_alias::Node::Array(ast_type_alias::ArrayNode {
+                    items: Box::new(ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+                        value: Keywords::Number,
+                        format: None,
+                        constraints: Default::default(),
+                    })),
+                    constraints: Default::default(),
+                })),
+            }),
+            ..Default::default()
+        };
+        AST::TypeAlias(ast_type_alias::TypeAliasAst {
+            identifier: String::from("users"),
+            type_parameters: vec![],
+            description: None,
+            deprecated: None,
+            body: ast_type_alias::Node::Object(ast_type_alias::ObjectNode {
+                extends: vec![],
+                values: vec![by_name, by_role],
+            }),
+        })
+    }
+
+    pub fn tuple_ast() -> AST {
+        /*
+        ts:
+        export type Frame = {
+            header: [string, Payload, number];
+        };
+
+        go:
+        type Frame struct {
+            Header FrameHeader `json:"header"`
+        }
+        */
+
+        let header = ast_type_alias::Property {
+            identifier: String::from("header"),
+            body: ast_type_alias::Node::Tuple(ast_type_alias::TupleNode {
+                items: vec![
+                    ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+                        value: Keywords::String,
+                        format: None,
+                        constraints: Default::default(),
+                    }),
+                    ast_type_alias::Node::Ref(ast_type_alias::RefNode {
+                        name: String::from("payload"),
+                        path: String::new(),
+                        args: vec![],
+                    }),
+                    ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+                        value: Keywords::Number,
+                        format: None,
+                        constraints: Default::default(),
+                    }),
+                ],
+            }),
+            ..Default::default()
+        };
+        AST::TypeAlias(ast_type_alias::TypeAliasAst {
+            identifier: String::from("frame"),
+            type_parameters: vec![],
+            description: None,
+            deprecated: None,
+            body: ast_type_alias::Node::Object(ast_type_alias::ObjectNode {
+                extends: vec![],
+                values: vec![header],
+            }),
+        })
+    }
+
+    pub fn number_format_ast() -> AST {
+        /*
+        ts:
+        export type Order = {
+            id: number;
+            price: number;
+            count: number;
+            version: 2;
+        };
+
+        go:
+        type Order struct {
+            Id int64 `json:"id"`
+            Price float64 `json:"price"`
+            Count uint8 `json:"count"`
+            Version int64 `json:"version"`
+        }
+        */
+
+        let number = |identifier: &str, format: NumberFormat| ast_type_alias::Property {
+            identifier: String::from(identifier),
+            body: ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+                value: Keywords::Number,
+                format: Some(Format::Number(format)),
+                constraints: Default::default(),
+            }),
+            ..Default::default()
+        };
+        let version = ast_type_alias::Property {
+            identifier: String::from("version"),
+            body: ast_type_alias::Node::NumberLiteral(ast_type_alias::NumberLiteralNode {
+                value: String::from("2"),
+                format: Some(NumberFormat::Int64),
+            }),
+            ..Default::default()
+        };
+        AST::TypeAlias(ast_type_alias::TypeAliasAst {
+            identifier: String::from("order"),
+            type_parameters: vec![],
+            description: None,
+            deprecated: None,
+            body: ast_type_alias::Node::Object(ast_type_alias::ObjectNode {
+                extends: vec![],
+                values: vec![
+                    number("id", NumberFormat::Int64),
+                    number("price", NumberFormat::Float64),
+                    number("count", NumberFormat::Uint8),
+                    version,
+                ],
             }),
+        })
+    }
+
+    pub fn string_format_ast() -> AST {
+        /*
+        ts:
+        export type Event = {
+            id: UuidString;
+            at: DateTimeString;
+            day: DateString;
+            ttl: DurationString;
+            payload?: Base64String;
+        };
+
+        go:
+        type Event struct {
+            Id string `json:"id"`
+            At time.Time `json:"at"`
+            Day string `json:"day"`
+            Ttl string `json:"ttl"`
+            Payload []byte `json:"payload,omitempty"`
+        }
+        */
+
+        let string = |identifier: &str, format: StringFormat| ast_type_alias::Property {
+            identifier: String::from(identifier),
+            body: ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+                value: Keywords::String,
+                format: Some(Format::String(format)),
+                constraints: Default::default(),
+            }),
+            ..Default::default()
+        };
+        AST::TypeAlias(ast_type_alias::TypeAliasAst {
+            identifier: String::from("event"),
+            type_parameters: vec![],
+            description: None,
+            deprecated: None,
+            body: ast_type_alias::Node::Object(ast_type_alias::ObjectNode {
+                extends: vec![],
+                values: vec![
+                    string("id", StringFormat::Uuid),
+                    string("at", StringFormat::DateTime),
+                    string("day", StringFormat::Date),
+                    string("ttl", StringFormat::Duration),
+                    ast_type_alias::Property {
+                        optional: true,
+                        ..string("payload", StringFormat::Byte)
+                    },
+                ],
+            }),
+        })
+    }
+
+    pub fn discriminated_union_ast() -> AST {
+        /*
+        ts:
+        export type Pet = Cat | Dog;
+
+        go:
+        type Pet struct {
+            Cat *Cat
+            Dog *Dog
+        }
+        */
+
+        let variant = |name: &str| {
+            ast_type_alias::Node::Ref(ast_type_alias::RefNode {
+                name: String::from(name),
+                path: String::new(),
+                args: vec![],
+            })
+        };
+        AST::TypeAlias(ast_type_alias::TypeAliasAst {
+            identifier: String::from("pet"),
+            type_parameters: vec![],
+            description: None,
+            deprecated: None,
+            body: ast_type_alias::Node::Union(ast_type_alias::UnionNode {
+                types: vec![variant("cat"), variant("dog")],
+                discriminator: Some(ast_type_alias::Discriminator {
+                    property: String::from("kind"),
+                    values: vec![String::from("cat"), String::from("dog")],
+                }),
+                descriptions: vec![],
+            }),
+        })
+    }
+
+    pub fn discriminated_definitions_ast() -> Vec<AST> {
+        /*
+        definitions of a file, the union comes before its variants:
+        pet: cat | dog
+        owner: { pet: pet }
+        cat: { name: string }
+        dog: cat & { good: boolean }
+        */
+
+        let reference = |name: &str| ast_type_alias::RefNode {
+            name: String::from(name),
+            path: String::new(),
+            args: vec![],
+        };
+        let property = |name: &str, body: ast_type_alias::Node| ast_type_alias::Property {
+            identifier: String::from(name),
+            body,
+            ..Default::default()
+        };
+        let object = |name: &str,
+                      extends: Vec<ast_type_alias::RefNode>,
+                      values: Vec<ast_type_alias::Property>| {
+            AST::TypeAlias(ast_type_alias::TypeAliasAst {
+                identifier: String::from(name),
+                type_parameters: vec![],
+                description: None,
+                deprecated: None,
+                body: ast_type_alias::Node::Object(ast_type_alias::ObjectNode { extends, values }),
+            })
+        };
+        let keyword = |value: Keywords| {
+            ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+                value,
+                format: None,
+                constraints: Default::default(),
+            })
+        };
+        vec![
+            discriminated_union_ast(),
+            object(
+                "owner",
+                vec![],
+                vec![property("pet", ast_type_alias::Node::Ref(reference("pet")))],
+            ),
+            object(
+                "cat",
+                vec![],
+                vec![property("name", keyword(Keywords::String))],
+            ),
+            object(
+                "dog",
+                vec![reference("cat")],
+                vec![property("good", keyword(Keywords::Boolean))],
+            ),
+        ]
+    }
+
+    pub fn extends_ast() -> AST {
+        /*
+        ts:
+        export type Admin = User & Audited & {
+            role: string;
+            grants: (Audited & {
+                scope: string;
+            })[];
+        };
+
+        go:
+        type Admin struct {
+            User
+            Audited
+            Role string `json:"role"`
+            Grants []struct {
+                Audited
+                Scope string `json:"scope"`
+            } `json:"grants"`
+        }
+        */
+
+        let base = |name: &str| ast_type_alias::RefNode {
+            name: String::from(name),
+            path: String::new(),
+            args: vec![],
+        };
+        let string = || {
+            ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+                value: Keywords::String,
+                format: None,
+                constraints: Default::default(),
+            })
+        };
+        let grant = ast_type_alias::ObjectNode {
+            extends: vec![base("audited")],
+            values: vec![ast_type_alias::Property {
+                identifier: String::from("scope"),
+                body: string(),
+                ..Default::default()
+            }],
+        };
+        AST::TypeAlias(ast_type_alias::TypeAliasAst {
+            identifier: String::from("admin"),
+            type_parameters: vec![],
+            description: None,
+            deprecated: None,
+            body: ast_type_alias::Node::Object(ast_type_alias::ObjectNode {
+                extends: vec![base("user"), base("audited")],
+                values: vec![
+                    ast_type_alias::Property {
+                        identifier: String::from("role"),
+                        body: string(),
+                        ..Default::default()
+                    },
+                    ast_type_alias::Property {
+                        identifier: String::from("grants"),
+                        body: ast_type_alias::Node::Array(ast_type_alias::ArrayNode {
+                            items: Box::new(ast_type_alias::Node::Object(grant)),
+                            constraints: Default::default(),
+                        }),
+                        ..Default::default()
+                    },
+                ],
+            }),
+        })
+    }
+
+    pub fn generic_ast() -> AST {
+        /*
+        ts:
+        export type Page<T> = {
+            items: T[];
+            cursor?: {
+                last: T;
+            };
+        };
+
+        go:
+        type Page[T any] struct {
+            Items []T `json:"items"`
+            Cursor *struct {
+                Last T `json:"last"`
+            } `json:"cursor,omitempty"`
+        }
+        */
+
+        let param = || {
+            ast_type_alias::Node::Param(ast_type_alias::ParamNode {
+                name: String::from("T"),
+            })
+        };
+        let cursor = ast_type_alias::ObjectNode {
+            extends: vec![],
+            values: vec![ast_type_alias::Property {
+                identifier: String::from("last"),
+                body: param(),
+                ..Default::default()
+            }],
+        };
+        AST::TypeAlias(ast_type_alias::TypeAliasAst {
+            identifier: String::from("page"),
+            type_parameters: vec![String::from("T")],
+            description: None,
+            deprecated: None,
+            body: ast_type_alias::Node::Object(ast_type_alias::ObjectNode {
+                extends: vec![],
+                values: vec![
+                    ast_type_alias::Property {
+                        identifier: String::from("items"),
+                        body: ast_type_alias::Node::Array(ast_type_alias::ArrayNode {
+                            items: Box::new(param()),
+                            constraints: Default::default(),
+                        }),
+                        ..Default::default()
+                    },
+                    ast_type_alias::Property {
+                        identifier: String::from("cursor"),
+                        optional: true,
+                        body: ast_type_alias::Node::Object(cursor),
+                        ..Default::default()
+                    },
+                ],
+            }),
+        })
+    }
+
+    pub fn generic_ref_ast() -> AST {
+        /*
+        ts:
+        export type Search = {
+            pages: Page<Result<string[], User>>;
+        };
+
+        go:
+        type Search struct {
+            Pages Page[Result[[]string, User]] `json:"pages"`
+        }
+        */
+
+        let reference = |name: &str, args: Vec<ast_type_alias::Node>| {
+            ast_type_alias::Node::Ref(ast_type_alias::RefNode {
+                name: String::from(name),
+                path: String::new(),
+                args,
+            })
+        };
+        let strings = ast_type_alias::Node::Array(ast_type_alias::ArrayNode {
+            items: Box::new(ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+                value: Keywords::String,
+                format: None,
+                constraints: Default::default(),
+            })),
+            constraints: Default::default(),
         });
+        let result = reference("result", vec![strings, reference("user", vec![])]);
+        AST::TypeAlias(ast_type_alias::TypeAliasAst {
+            identifier: String::from("search"),
+            type_parameters: vec![],
+            description: None,
+            deprecated: None,
+            body: ast_type_alias::Node::Object(ast_type_alias::ObjectNode {
+                extends: vec![],
+                values: vec![ast_type_alias::Property {
+                    identifier: String::from("pages"),
+                    body: reference("page", vec![result]),
+                    ..Default::default()
+                }],
+            }),
+        })
+    }
 
-        ast
+    pub fn described_ast() -> AST {
+        /*
+        ts:
+        /**
+         * A person using the app.
+         *
+         * Created on sign up.
+         */
+        export type User = {
+            /** Display name, not unique. */
+            name: string;
+        };
+
+        go:
+        // User A person using the app.
+        //
+        // Created on sign up.
+        type User struct {
+            // Name Display name, not unique.
+            Name string `json:"name"`
+        }
+        */
+
+        AST::TypeAlias(ast_type_alias::TypeAliasAst {
+            identifier: String::from("user"),
+            type_parameters: vec![],
+            description: Some(String::from(
+                "A person using the app.\n\nCreated on sign up.\n",
+            )),
+            deprecated: None,
+            body: ast_type_alias::Node::Object(ast_type_alias::ObjectNode {
+                extends: vec![],
+                values: vec![ast_type_alias::Property {
+                    identifier: String::from("name"),
+                    body: ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+                        value: Keywords::String,
+                        format: None,
+                        constraints: Default::default(),
+                    }),
+                    description: Some(String::from("Display name, not unique.")),
+                    deprecated: None,
+                    ..Default::default()
+                }],
+            }),
+        })
+    }
+
+    pub fn deprecated_ast() -> AST {
+        /*
+        ts:
+        /** @deprecated */
+        export type LegacyUser = {
+            /**
+             * Display name.
+             * @deprecated Use handle instead.
+             */
+            name: string;
+            handle: string;
+        };
+
+        go:
+        // LegacyUser is deprecated.
+        //
+        // Deprecated: do not use.
+        type LegacyUser struct {
+            // Name Display name.
+            //
+            // Deprecated: Use handle instead.
+            Name string `json:"name"`
+            Handle string `json:"handle"`
+        }
+        */
+
+        let string = || {
+            ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+                value: Keywords::String,
+                format: None,
+                constraints: Default::default(),
+            })
+        };
+        AST::TypeAlias(ast_type_alias::TypeAliasAst {
+            identifier: String::from("legacyUser"),
+            type_parameters: vec![],
+            description: None,
+            deprecated: Some(String::new()),
+            body: ast_type_alias::Node::Object(ast_type_alias::ObjectNode {
+                extends: vec![],
+                values: vec![
+                    ast_type_alias::Property {
+                        identifier: String::from("name"),
+                        body: string(),
+                        description: Some(String::from("Display name.")),
+                        deprecated: Some(String::from("Use handle instead.")),
+                        ..Default::default()
+                    },
+                    ast_type_alias::Property {
+                        identifier: String::from("handle"),
+                        body: string(),
+                        ..Default::default()
+                    },
+                ],
+            }),
+        })
+    }
+
+    pub fn described_enum_ast() -> AST {
+        /*
+        ts:
+        /** role is what a user can do. */
+        export enum Role {
+            /** Can do everything. */
+            Admin = "admin",
+            Guest = "guest",
+        };
+
+        go:
+        // Role is what a user can do.
+        type Role string
+        const (
+            // Admin Can do everything.
+            Admin Role = "admin"
+            Guest Role = "guest"
+        )
+        */
+
+        AST::Enum(ast_enum::EnumAst {
+            identifier: String::from("role"),
+            r#type: ast_enum::MembersType::String,
+            format: None,
+            description: Some(String::from("role is what a user can do.")),
+            deprecated: None,
+            members: vec![
+                ast_enum::Member {
+                    identifier: String::from("admin"),
+                    value: String::from("admin"),
+                    description: Some(String::from("Can do everything.")),
+                    deprecated: None,
+                },
+                ast_enum::Member {
+                    identifier: String::from("guest"),
+                    value: String::from("guest"),
+                    description: None,
+                    deprecated: None,
+                },
+            ],
+        })
     }
 
     pub fn string_enum_ast() -> AST {
@@ -174,21 +892,472 @@ pub(self) mod for_test {
         )
         */
 
-        let ast = AST::Enum(ast_enum::EnumAst {
+        AST::Enum(ast_enum::EnumAst {
             identifier: String::from("stringEnum"),
             r#type: ast_enum::MembersType::String,
+            format: None,
+            description: None,
+            deprecated: None,
             members: vec![
                 ast_enum::Member {
                     identifier: String::from("hello"),
                     value: String::from("hello"),
+                    description: None,
+                    deprecated: None,
                 },
                 ast_enum::Member {
                     identifier: String::from("world"),
                     value: String::from("world"),
+                    description: None,
+                    deprecated: None,
                 },
             ],
-        });
+        })
+    }
+
+    pub fn constrained_ast() -> AST {
+        /*
+        ts:
+        export type SignUp = {
+            username: string;
+            age?: number;
+            tags: string[];
+        };
+
+        go:
+        type SignUp struct {
+            Username string `json:"username"`
+            Age *uint8 `json:"age,omitempty"`
+            Tags []string `json:"tags"`
+        }
+        */
 
-        ast
+        AST::TypeAlias(ast_type_alias::TypeAliasAst {
+            identifier: String::from("signUp"),
+            type_parameters: vec![],
+            description: None,
+            deprecated: None,
+            body: ast_type_alias::Node::Object(ast_type_alias::ObjectNode {
+                extends: vec![],
+                values: vec![
+                    ast_type_alias::Property {
+                        identifier: String::from("username"),
+                        body: ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+                            value: Keywords::String,
+                            format: None,
+                            constraints: ast_type_alias::Constraints {
+                                min_length: Some(3),
+                                max_length: Some(16),
+                                pattern: Some(String::from("^[a-z0-9_]+$")),
+                                ..Default::default()
+                            },
+                        }),
+                        ..Default::default()
+                    },
+                    ast_type_alias::Property {
+                        identifier: String::from("age"),
+                        body: ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+                            value: Keywords::Number,
+                            format: Some(Format::Number(NumberFormat::Uint8)),
+                            constraints: ast_type_alias::Constraints {
+                                minimum: Some(13.0),
+                                ..Default::default()
+                            },
+                        }),
+                        optional: true,
+                        ..Default::default()
+                    },
+                    ast_type_alias::Property {
+                        identifier: String::from("tags"),
+                        body: ast_type_alias::Node::Array(ast_type_alias::ArrayNode {
+                            items: Box::new(ast_type_alias::Node::Keyword(
+                                ast_type_alias::KeywordNode {
+                                    value: Keywords::String,
+                                    format: None,
+                                    constraints: ast_type_alias::Constraints {
+                                        min_length: Some(1),
+                                        ..Default::default()
+                                    },
+                                },
+                            )),
+                            constraints: ast_type_alias::Constraints {
+                                max_items: Some(5),
+                                ..Default::default()
+                            },
+                        }),
+                        ..Default::default()
+                    },
+                ],
+            }),
+        })
+    }
+
+    pub fn defaults_ast() -> AST {
+        /*
+        ts:
+        export type Settings = {
+            host: string;
+            port?: number;
+            tags: string[];
+            level: StringEnum;
+            tls: {
+                enabled: boolean;
+                cert: string;
+            };
+        };
+
+        go:
+        type Settings struct {
+            Host string `json:"host"`
+            Port *uint16 `json:"port,omitempty"`
+            Tags []string `json:"tags"`
+            Level StringEnum `json:"level"`
+            Tls struct {
+                Enabled bool `json:"enabled"`
+                Cert string `json:"cert"`
+            } `json:"tls"`
+        }
+        */
+
+        let string = || {
+            ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+                value: Keywords::String,
+                format: None,
+                constraints: Default::default(),
+            })
+        };
+        AST::TypeAlias(ast_type_alias::TypeAliasAst {
+            identifier: String::from("settings"),
+            type_parameters: vec![],
+            description: None,
+            deprecated: None,
+            body: ast_type_alias::Node::Object(ast_type_alias::ObjectNode {
+                extends: vec![],
+                values: vec![
+                    ast_type_alias::Property {
+                        identifier: String::from("host"),
+                        body: string(),
+                        default: Some(DefaultValue::String(String::from("localhost"))),
+                        ..Default::default()
+                    },
+                    ast_type_alias::Property {
+                        identifier: String::from("port"),
+                        body: ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+                            value: Keywords::Number,
+                            format: Some(Format::Number(NumberFormat::Uint16)),
+                            constraints: Default::default(),
+                        }),
+                        optional: true,
+                        default: Some(DefaultValue::Number(String::from("8080"))),
+                        ..Default::default()
+                    },
+                    ast_type_alias::Property {
+                        identifier: String::from("tags"),
+                        body: ast_type_alias::Node::Array(ast_type_alias::ArrayNode {
+                            items: Box::new(string()),
+                            constraints: Default::default(),
+                        }),
+                        default: Some(DefaultValue::Array(vec![DefaultValue::String(
+                            String::from("web"),
+                        )])),
+                        ..Default::default()
+                    },
+                    ast_type_alias::Property {
+                        identifier: String::from("level"),
+                        body: ast_type_alias::Node::Ref(ast_type_alias::RefNode {
+                            name: String::from("stringEnum"),
+                            path: String::new(),
+                            args: vec![],
+                        }),
+                        default: Some(DefaultValue::Member(String::from("hello"))),
+                        ..Default::default()
+                    },
+                    ast_type_alias::Property {
+                        identifier: String::from("tls"),
+                        body: ast_type_alias::Node::Object(ast_type_alias::ObjectNode {
+                            extends: vec![],
+                            values: vec![
+                                ast_type_alias::Property {
+                                    identifier: String::from("enabled"),
+                                    body: ast_type_alias::Node::Keyword(
+                                        ast_type_alias::KeywordNode {
+                                            value: Keywords::Boolean,
+                                            format: None,
+                                            constraints: Default::default(),
+                                        },
+                                    ),
+                                    default: Some(DefaultValue::Boolean(true)),
+                                    ..Default::default()
+                                },
+                                ast_type_alias::Property {
+                                    identifier: String::from("cert"),
+                                    body: string(),
+                                    ..Default::default()
+                                },
+                            ],
+                        }),
+                        ..Default::default()
+                    },
+                ],
+            }),
+        })
+    }
+
+    pub fn tree_ast() -> AST {
+        /*
+        ts:
+        export type TreeNode = {
+            value: string;
+            parent: TreeNode;
+            children: TreeNode[];
+        };
+
+        go:
+        type TreeNode struct {
+            Value string `json:"value"`
+            Parent *TreeNode `json:"parent"`
+            Children []TreeNode `json:"children"`
+        }
+        */
+
+        let tree_node = || {
+            ast_type_alias::Node::Ref(ast_type_alias::RefNode {
+                name: String::from("treeNode"),
+                path: String::new(),
+                args: vec![],
+            })
+        };
+        AST::TypeAlias(ast_type_alias::TypeAliasAst {
+            identifier: String::from("treeNode"),
+            type_parameters: vec![],
+            description: None,
+            deprecated: None,
+            body: ast_type_alias::Node::Object(ast_type_alias::ObjectNode {
+                extends: vec![],
+                values: vec![
+                    ast_type_alias::Property {
+                        identifier: String::from("value"),
+                        body: ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+                            value: Keywords::String,
+                            format: None,
+                            constraints: Default::default(),
+                        }),
+                        ..Default::default()
+                    },
+                    ast_type_alias::Property {
+                        identifier: String::from("parent"),
+                        body: tree_node(),
+                        ..Default::default()
+                    },
+                    ast_type_alias::Property {
+                        identifier: String::from("children"),
+                        body: ast_type_alias::Node::Array(ast_type_alias::ArrayNode {
+                            items: Box::new(tree_node()),
+                            constraints: Default::default(),
+                        }),
+                        ..Default::default()
+                    },
+                ],
+            }),
+        })
+    }
+
+    pub fn inline_enum_ast() -> AST {
+        /*
+        ts:
+        export type Account = {
+            status: "active" | /** Can not sign in. */ "banned";
+            roles: ("admin" | "member")[];
+            level?: 1 | 2;
+        };
+
+        go:
+        type AccountStatus string
+        const (
+            AccountStatusActive AccountStatus = "active"
+            // AccountStatusBanned Can not sign in.
+            AccountStatusBanned AccountStatus = "banned"
+        )
+
+        type AccountRolesItem string
+        const (
+            AccountRolesItemAdmin AccountRolesItem = "admin"
+            AccountRolesItemMember AccountRolesItem = "member"
+        )
+
+        type AccountLevel uint8
+        const (
+            AccountLevelBasic AccountLevel = 1
+            AccountLevelPremium AccountLevel = 2
+        )
+
+        type Account struct {
+            Status AccountStatus `json:"status"`
+            Roles []AccountRolesItem `json:"roles"`
+            Level *AccountLevel `json:"level,omitempty"`
+        }
+        */
+
+        let member = |identifier: &str, value: &str| ast_enum::Member {
+            identifier: identifier.to_string(),
+            value: value.to_string(),
+            description: None,
+            deprecated: None,
+        };
+        AST::TypeAlias(ast_type_alias::TypeAliasAst {
+            identifier: String::from("account"),
+            type_parameters: vec![],
+            description: None,
+            deprecated: None,
+            body: ast_type_alias::Node::Object(ast_type_alias::ObjectNode {
+                extends: vec![],
+                values: vec![
+                    ast_type_alias::Property {
+                        identifier: String::from("status"),
+                        body: ast_type_alias::Node::Enum(ast_type_alias::EnumNode {
+                            r#type: ast_enum::MembersType::String,
+                            format: None,
+                            members: vec![
+                                member("active", "active"),
+                                ast_enum::Member {
+                                    description: Some(String::from("Can not sign in.")),
+                                    ..member("banned", "banned")
+                                },
+                            ],
+                        }),
+                        default: Some(DefaultValue::Member(String::from("active"))),
+                        ..Default::default()
+                    },
+                    ast_type_alias::Property {
+                        identifier: String::from("roles"),
+                        body: ast_type_alias::Node::Array(ast_type_alias::ArrayNode {
+                            items: Box::new(ast_type_alias::Node::Enum(ast_type_alias::EnumNode {
+                                r#type: ast_enum::MembersType::String,
+                                format: None,
+                                members: vec![member("admin", "admin"), member("member", "member")],
+                            })),
+                            constraints: Default::default(),
+                        }),
+                        ..Default::default()
+                    },
+                    ast_type_alias::Property {
+                        identifier: String::from("level"),
+                        body: ast_type_alias::Node::Enum(ast_type_alias::EnumNode {
+                            r#type: ast_enum::MembersType::Number,
+                            format: Some(NumberFormat::Uint8),
+                            members: vec![member("basic", "1"), member("premium", "2")],
+                        }),
+                        optional: true,
+                        default: Some(DefaultValue::Member(String::from("basic"))),
+                        ..Default::default()
+                    },
+                ],
+            }),
+        })
+    }
+
+    pub fn wire_name_ast() -> AST {
+        /*
+        ts:
+        export type Profile = {
+            user_id: string;
+            avatar_url?: string;
+            displayName: string;
+        };
+
+        go, naming PascalCase:
+        type Profile struct {
+            UserID string `json:"user_id"`
+            AvatarURL *string `json:"avatar_url,omitempty"`
+            DisplayName string `json:"displayName"`
+        }
+        */
+
+        let string = || {
+            ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+                value: Keywords::String,
+                format: None,
+                constraints: Default::default(),
+            })
+        };
+        AST::TypeAlias(ast_type_alias::TypeAliasAst {
+            identifier: String::from("profile"),
+            type_parameters: vec![],
+            description: None,
+            deprecated: None,
+            body: ast_type_alias::Node::Object(ast_type_alias::ObjectNode {
+                extends: vec![],
+                values: vec![
+                    ast_type_alias::Property {
+                        identifier: String::from("userId"),
+                        wire_name: Some(String::from("user_id")),
+                        body: string(),
+                        ..Default::default()
+                    },
+                    ast_type_alias::Property {
+                        identifier: String::from("avatarUrl"),
+                        wire_name: Some(String::from("avatar_url")),
+                        body: string(),
+                        optional: true,
+                        ..Default::default()
+                    },
+                    ast_type_alias::Property {
+                        identifier: String::from("displayName"),
+                        body: string(),
+                        ..Default::default()
+                    },
+                ],
+            }),
+        })
+    }
+
+    pub fn tagged_ast() -> AST {
+        /*
+        go, tags yaml, db (snake_case) and msgpack:
+        type Session struct {
+            UserId string `json:"userId" yaml:"userId" db:"user_id" msgpack:"userId"`
+            Token string `json:"token" yaml:"token" db:"-" msgpack:"token" validate:"required"`
+            ExpiresAt *string `json:"expiresAt,omitempty" yaml:"expiresAt,omitempty" db:"expires_at,omitempty" msgpack:"expiresAt,omitempty"`
+        }
+        */
+
+        let string = || {
+            ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+                value: Keywords::String,
+                format: None,
+                constraints: Default::default(),
+            })
+        };
+        AST::TypeAlias(ast_type_alias::TypeAliasAst {
+            identifier: String::from("session"),
+            type_parameters: vec![],
+            description: None,
+            deprecated: None,
+            body: ast_type_alias::Node::Object(ast_type_alias::ObjectNode {
+                extends: vec![],
+                values: vec![
+                    ast_type_alias::Property {
+                        identifier: String::from("userId"),
+                        body: string(),
+                        ..Default::default()
+                    },
+                    ast_type_alias::Property {
+                        identifier: String::from("token"),
+                        body: string(),
+                        go_tags: BTreeMap::from([
+                            (String::from("db"), String::from("-")),
+                            (String::from("validate"), String::from("required")),
+                        ]),
+                        ..Default::default()
+                    },
+                    ast_type_alias::Property {
+                        identifier: String::from("expiresAt"),
+                        body: string(),
+                        optional: true,
+                        ..Default::default()
+                    },
+                ],
+            }),
+        })
     }
 }
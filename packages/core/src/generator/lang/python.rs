@@ -0,0 +1,813 @@
+use std::path::PathBuf;
+
+use super::super::types::{
+    ast_enum,
+    ast_type_alias::{
+        KeywordNode, Keywords, Node, ObjectNode, Property, StringFormat, TypeAliasAst,
+    },
+    AST,
+};
+use crate::{
+    backend::python,
+    diagnostic::Diagnostic,
+    generator::{
+        main::{params_of, unexpected},
+        types::{DynImport, Import, Imports, RefImport},
+        utils::{capitalize, rename},
+    },
+    types::config::{Naming, PythonModel},
+};
+
+/// Pseudo module of type parameters, `render_imports` declares each
+/// name imported from it with `TypeVar`.
+pub const TYPE_VARS: &str = "<TypeVar>";
+
+const KEYWORDS: [&str; 35] = [
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// Python can not declare a class inside an annotation,
+/// so nested objects are **hoisted** to their own classes named `<Parent><Field>`,
+/// and emitted right before the class using them.
+///
+/// ```python
+/// @dataclass
+/// class TestAstHead:
+///     cmd: str
+///
+///
+/// @dataclass
+/// class TestAst:
+///     head: TestAstHead
+/// ```
+///
+/// Everything needed from `typing`, `dataclasses`, `pydantic` and `enum` is pushed to imports as `$dyn`.
+pub fn generate_python(
+    ast: &AST,
+    imports: &mut Imports,
+    tabsize: usize,
+    model: &PythonModel,
+    naming: Option<Naming>,
+) -> Result<String, Diagnostic> {
+    let mut hoisted: Vec<String> = vec![];
+
+    let item = match ast {
+        AST::Enum(enum_ast) => {
+            let id = capitalize(&enum_ast.identifier);
+            let s = iterate_members(&enum_ast.members, &enum_ast.r#type, tabsize);
+
+            let is_int = match enum_ast.format {
+                Some(f) => f.is_integer(),
+                None => enum_ast
+                    .members
+                    .iter()
+                    .all(|m| m.value.parse::<i64>().is_ok()),
+            };
+            let base = match &enum_ast.r#type {
+                ast_enum::MembersType::String => {
+                    push_import(imports, "Enum", "enum");
+                    "str, Enum"
+                }
+                ast_enum::MembersType::Number if is_int => {
+                    push_import(imports, "IntEnum", "enum");
+                    "IntEnum"
+                }
+                ast_enum::MembersType::Number => {
+                    push_import(imports, "Enum", "enum");
+                    "float, Enum"
+                }
+            };
+
+            format!("class {}({}):\n{}\n", id, base, s)
+        }
+        AST::TypeAlias(type_alias_ast) => {
+            let id = capitalize(&type_alias_ast.identifier);
+            let params = &type_alias_ast.type_parameters;
+            // definitions may share parameter names, they are declared once after imports.
+            for p in params {
+                push_import(imports, p, TYPE_VARS);
+            }
+
+            match &type_alias_ast.body {
+                Node::Object(node) => render_class(
+                    imports,
+                    &mut hoisted,
+                    node,
+                    &id,
+                    params,
+                    tabsize,
+                    model,
+                    naming,
+                )?,
+                Node::Split(split) => {
+                    if let Some(node) = split.nodes.get(python::ID) {
+                        return generate_python(
+                            &AST::TypeAlias(TypeAliasAst {
+                                identifier: type_alias_ast.identifier.clone(),
+                                type_parameters: type_alias_ast.type_parameters.clone(),
+                                description: type_alias_ast.description.clone(),
+                                deprecated: type_alias_ast.deprecated.clone(),
+                                body: node.as_ref().clone(),
+                            }),
+                            imports,
+                            tabsize,
+                            model,
+                            naming,
+                        );
+                    }
+                    return Ok(String::new());
+                }
+                node => {
+                    let r = iterate_type(imports, &mut hoisted, node, &id, tabsize, model, naming)?;
+                    format!("{} = {}\n", id, r)
+                }
+            }
+        }
+    };
+
+    let mut result = String::new();
+    for h in hoisted {
+        result += &format!("\n\n{}", h);
+    }
+    result += &format!("\n\n{}", item);
+    Ok(result)
+}
+
+/// Definitions of a file in the order they are declared.
+///
+/// Module level aliases and bases of classes are evaluated when the module is loaded,
+/// so definitions they use in the same file are moved before them, otherwise the order
+/// is kept. Annotations of fields are lazy by `from __future__ import annotations`.
+pub fn order_definitions(ast_list: &[AST]) -> Vec<&AST> {
+    let deps: Vec<Vec<String>> = ast_list
+        .iter()
+        .map(|ast| {
+            let mut refs: Vec<String> = vec![];
+            if let AST::TypeAlias(type_alias_ast) = ast {
+                eager_refs(&type_alias_ast.body, false, &mut refs);
+            }
+            refs
+        })
+        .collect();
+    let is_local = |name: &String| ast_list.iter().any(|ast| ast.identifier() == name);
+
+    let mut declared: Vec<String> = vec![];
+    let mut remaining: Vec<usize> = (0..ast_list.len()).collect();
+    let mut result: Vec<&AST> = vec![];
+    while !remaining.is_empty() {
+        // the first one ready, or the first one left if the rest refer to each other.
+        let pos = remaining
+            .iter()
+            .position(|&idx| {
+                deps[idx]
+                    .iter()
+                    .all(|d| declared.contains(d) || !is_local(d))
+            })
+            .unwrap_or(0);
+        let idx = remaining.remove(pos);
+        let ast = &ast_list[idx];
+        declared.push(ast.identifier().to_string());
+        result.push(ast);
+    }
+    result
+}
+
+/// Names of `$ref` in the same file evaluated when `node` is declared, `lazy` in annotations.
+fn eager_refs(node: &Node, lazy: bool, refs: &mut Vec<String>) {
+    match node {
+        Node::Ref(node) => {
+            if !lazy && node.path.is_empty() {
+                refs.push(node.name.clone());
+            }
+            node.args.iter().for_each(|a| eager_refs(a, lazy, refs));
+        }
+        // bases of hoisted classes are evaluated as well.
+        Node::Object(node) => {
+            for base in node.extends.iter().filter(|b| b.path.is_empty()) {
+                refs.push(base.name.clone());
+            }
+            node.values
+                .iter()
+                .for_each(|p| eager_refs(&p.body, true, refs));
+        }
+        Node::Array(node) => eager_refs(&node.items, lazy, refs),
+        Node::Map(node) => {
+            eager_refs(&node.keys, lazy, refs);
+            eager_refs(&node.values, lazy, refs);
+        }
+        Node::Tuple(node) => node.items.iter().for_each(|t| eager_refs(t, lazy, refs)),
+        Node::Union(node) => node.types.iter().for_each(|t| eager_refs(t, lazy, refs)),
+        Node::Split(split) => {
+            if let Some(node) = split.nodes.get(python::ID) {
+                eager_refs(node, lazy, refs);
+            }
+        }
+        Node::StringLiteral(_)
+        | Node::NumberLiteral(_)
+        | Node::Keyword(_)
+        | Node::Param(_)
+        | Node::Dyn(_)
+        | Node::Enum(_)
+        | Node::Empty => {}
+    }
+}
+
+/// Render the python annotation of a node.
+///
+/// `hint` is the class name used when the node must be hoisted.
+fn iterate_type(
+    imports: &mut Imports,
+    hoisted: &mut Vec<String>,
+    node: &Node,
+    hint: &str,
+    tabsize: usize,
+    model: &PythonModel,
+    naming: Option<Naming>,
+) -> Result<String, Diagnostic> {
+    let result = match node {
+        Node::StringLiteral(node) => {
+            push_import(imports, "Literal", "typing");
+            format!("Literal[{}]", py_string(&node.value))
+        }
+        Node::NumberLiteral(node) => {
+            push_import(imports, "Literal", "typing");
+            format!("Literal[{}]", node.value)
+        }
+        Node::Enum(node) => {
+            push_import(imports, "Literal", "typing");
+            let values: Vec<String> = node
+                .members
+                .iter()
+                .map(|m| match node.r#type {
+                    ast_enum::MembersType::String => py_string(&m.value),
+                    ast_enum::MembersType::Number => m.value.clone(),
+                })
+                .collect();
+            format!("Literal[{}]", values.join(", "))
+        }
+        Node::Keyword(node) => {
+            if let Keywords::Any = node.value {
+                push_import(imports, "Any", "typing");
+            }
+            let t = keyword(node);
+            match node.string_format() {
+                Some(StringFormat::Uuid) => push_import(imports, &t, "uuid"),
+                Some(StringFormat::Byte) | None => {}
+                Some(_) => push_import(imports, &t, "datetime"),
+            }
+            t
+        }
+        Node::Object(node) => {
+            let params = params_of(node.values.iter().map(|p| &p.body));
+            let s = render_class(
+                imports, hoisted, node, hint, &params, tabsize, model, naming,
+            )?;
+            hoisted.push(s);
+            if params.is_empty() {
+                hint.to_string()
+            } else {
+                format!("{}[{}]", hint, params.join(", "))
+            }
+        }
+        Node::Array(node) => {
+            push_import(imports, "List", "typing");
+            let r = iterate_type(
+                imports,
+                hoisted,
+                &node.items,
+                &format!("{}Item", hint),
+                tabsize,
+                model,
+                naming,
+            )?;
+            format!("List[{}]", r)
+        }
+        Node::Map(node) => {
+            push_import(imports, "Dict", "typing");
+            let k = iterate_type(
+                imports,
+                hoisted,
+                &node.keys,
+                &format!("{}Key", hint),
+                tabsize,
+                model,
+                naming,
+            )?;
+            let v = iterate_type(
+                imports,
+                hoisted,
+                &node.values,
+                &format!("{}Value", hint),
+                tabsize,
+                model,
+                naming,
+            )?;
+            format!("Dict[{}, {}]", k, v)
+        }
+        Node::Tuple(node) => {
+            push_import(imports, "Tuple", "typing");
+            let r = node
+                .items
+                .iter()
+                .enumerate()
+                .map(|(idx, t)| {
+                    iterate_type(
+                        imports,
+                        hoisted,
+                        t,
+                        &format!("{}Item{}", hint, idx),
+                        tabsize,
+                        model,
+                        naming,
+                    )
+                })
+                .collect::<Result<Vec<String>, _>>()?
+                .join(", ");
+            format!("Tuple[{}]", r)
+        }
+        Node::Ref(node) => {
+            if !node.path.is_empty() {
+                imports.push(Import::Ref(RefImport {
+                    name: capitalize(&node.name),
+                    from: PathBuf::from(&node.path),
+                }));
+            }
+            if node.args.is_empty() {
+                return Ok(capitalize(&node.name));
+            }
+            let args: Vec<String> = node
+                .args
+                .iter()
+                .enumerate()
+                .map(|(idx, a)| {
+                    let hint = format!("{}Arg{}", hint, idx);
+                    iterate_type(imports, hoisted, a, &hint, tabsize, model, naming)
+                })
+                .collect::<Result<_, _>>()?;
+            format!("{}[{}]", capitalize(&node.name), args.join(", "))
+        }
+        Node::Param(node) => node.name.clone(),
+        Node::Dyn(node) => {
+            push_import(imports, &node.name, &node.from);
+            node.name.clone()
+        }
+        Node::Union(node) => {
+            push_import(imports, "Union", "typing");
+            let r = node
+                .types
+                .iter()
+                .enumerate()
+                .map(|(idx, t)| {
+                    if let Node::Union(_) = t {
+                        return Err(unexpected("union nested in a union"));
+                    }
+                    iterate_type(
+                        imports,
+                        hoisted,
+                        t,
+                        &format!("{}Variant{}", hint, idx),
+                        tabsize,
+                        model,
+                        naming,
+                    )
+                })
+                .collect::<Result<Vec<String>, _>>()?
+                .join(", ");
+            format!("Union[{}]", r)
+        }
+        Node::Split(_) => return Err(unexpected("split node")),
+        Node::Empty => return Err(unexpected("empty node")),
+    };
+    Ok(result)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn render_class(
+    imports: &mut Imports,
+    hoisted: &mut Vec<String>,
+    node: &ObjectNode,
+    id: &str,
+    params: &[String],
+    tabsize: usize,
+    model: &PythonModel,
+    naming: Option<Naming>,
+) -> Result<String, Diagnostic> {
+    // dataclass fields with a default must come after the others.
+    let (optional, required): (Vec<&Property>, Vec<&Property>) =
+        node.values.iter().partition(|v| v.optional);
+    let mut r: String = required
+        .into_iter()
+        .chain(optional)
+        .map(|v| iterate_properties(imports, hoisted, v, id, tabsize, model, naming))
+        .collect::<Result<_, _>>()?;
+    if r.is_empty() {
+        r = format!("{}pass\n", " ".repeat(tabsize));
+    }
+
+    let bases: Vec<String> = node
+        .extends
+        .iter()
+        .map(|base| {
+            iterate_type(
+                imports,
+                hoisted,
+                &Node::Ref(base.clone()),
+                id,
+                tabsize,
+                model,
+                naming,
+            )
+        })
+        .collect::<Result<_, _>>()?;
+
+    let is_extended = !bases.is_empty();
+    let mut bases = bases;
+    if matches!(model, PythonModel::Pydantic) && !is_extended {
+        push_import(imports, "BaseModel", "pydantic");
+        bases.insert(0, String::from("BaseModel"));
+    }
+    if !params.is_empty() {
+        push_import(imports, "Generic", "typing");
+        bases.push(format!("Generic[{}]", params.join(", ")));
+    }
+    let class = if bases.is_empty() {
+        format!("class {}:", id)
+    } else {
+        format!("class {}({}):", id, bases.join(", "))
+    };
+
+    match model {
+        PythonModel::Dataclass => {
+            push_import(imports, "dataclass", "dataclasses");
+            // fields of a subclass come after the ones of bases, keyword only fields
+            // are moved after fields with a default.
+            let decorator = if is_extended {
+                "@dataclass(kw_only=True)"
+            } else {
+                "@dataclass"
+            };
+            Ok(format!("{}\n{}\n{}", decorator, class, r))
+        }
+        PythonModel::Pydantic => Ok(format!("{}\n{}", class, r)),
+    }
+}
+
+fn iterate_properties(
+    imports: &mut Imports,
+    hoisted: &mut Vec<String>,
+    p: &Property,
+    parent: &str,
+    tabsize: usize,
+    model: &PythonModel,
+    naming: Option<Naming>,
+) -> Result<String, Diagnostic> {
+    let indent = " ".repeat(tabsize);
+    let hint = format!("{}{}", parent, capitalize(&p.identifier));
+    let mut r = iterate_type(imports, hoisted, &p.body, &hint, tabsize, model, naming)?;
+    if p.optional || p.nullable {
+        push_import(imports, "Optional", "typing");
+        r = format!("Optional[{}]", r);
+    }
+
+    let field = field_name(p, naming);
+    if field != p.wire_name() {
+        let default = if p.optional { "default=None, " } else { "" };
+        // the key on the wire is kept as an alias, in the metadata of dataclass fields.
+        let value = match model {
+            PythonModel::Pydantic => {
+                push_import(imports, "Field", "pydantic");
+                format!("Field({}alias={})", default, py_string(p.wire_name()))
+            }
+            PythonModel::Dataclass => {
+                push_import(imports, "field", "dataclasses");
+                format!(
+                    "field({}metadata={{\"alias\": {}}})",
+                    default,
+                    py_string(p.wire_name())
+                )
+            }
+        };
+        return Ok(format!("{}{}: {} = {}\n", indent, field, r, value));
+    }
+    let default = if p.optional { " = None" } else { "" };
+    Ok(format!("{}{}: {}{}\n", indent, field, r, default))
+}
+
+fn iterate_members(
+    members: &ast_enum::Members,
+    m_type: &ast_enum::MembersType,
+    tabsize: usize,
+) -> String {
+    let indent = " ".repeat(tabsize);
+    let mut result = String::new();
+
+    match m_type {
+        ast_enum::MembersType::String => {
+            for (idx, m) in members.iter().enumerate() {
+                let is_last = idx == members.len() - 1;
+                let mut s = format!(
+                    "{}{} = {}",
+                    indent,
+                    capitalize(&m.identifier),
+                    py_string(&m.value)
+                );
+                if !is_last {
+                    s += "\n"
+                }
+
+                result += &s
+            }
+        }
+        ast_enum::MembersType::Number => {
+            for (idx, m) in members.iter().enumerate() {
+                let is_last = idx == members.len() - 1;
+                let mut s = format!("{}{} = {}", indent, capitalize(&m.identifier), m.value);
+                if !is_last {
+                    s += "\n"
+                }
+
+                result += &s
+            }
+        }
+    }
+
+    result
+}
+
+fn push_import(imports: &mut Imports, name: &str, from: &str) {
+    imports.push(Import::Dyn(DynImport {
+        name: name.to_string(),
+        from: from.to_string(),
+    }));
+}
+
+/// Name of the attribute of a property in a class.
+pub fn field_name(p: &Property, naming: Option<Naming>) -> String {
+    to_field_name(&rename(&p.identifier, naming))
+}
+
+/// `e.g.` user-name -> user_name, from -> from_
+fn to_field_name(identifier: &str) -> String {
+    let s = identifier.replace(['-', ' '], "_");
+    if KEYWORDS.contains(&s.as_str()) {
+        return format!("{}_", s);
+    }
+    s
+}
+
+fn keyword(k: &KeywordNode) -> String {
+    match k.value {
+        Keywords::Any => String::from("Any"),
+        Keywords::Boolean => String::from("bool"),
+        Keywords::Number if k.number_format().is_some_and(|f| f.is_integer()) => {
+            String::from("int")
+        }
+        Keywords::Number => String::from("float"),
+        // base64 is left as is, `bytes` would be the encoded text.
+        Keywords::String => match k.string_format() {
+            Some(StringFormat::DateTime) => String::from("datetime"),
+            Some(StringFormat::Date) => String::from("date"),
+            Some(StringFormat::Duration) => String::from("timedelta"),
+            Some(StringFormat::Uuid) => String::from("UUID"),
+            Some(StringFormat::Byte) | None => String::from("str"),
+        },
+    }
+}
+
+/// A python string literal, JSON escapes are valid in python.
+fn py_string(s: &str) -> String {
+    serde_json::to_string(s).unwrap()
+}
+
+// unit test here
+#[cfg(test)]
+mod test {
+    use super::{generate_python, order_definitions};
+    use crate::{
+        generator::{lang::for_test, types::DEFAULT_TABSIZE},
+        types::config::{Naming, PythonModel},
+    };
+
+    #[test]
+    fn nested_object() {
+        let ast = for_test::nested_object_ast();
+        let result = generate_python(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.python,
+            &PythonModel::Dataclass,
+            None,
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn nested_object_pydantic() {
+        let ast = for_test::nested_object_ast();
+        let result = generate_python(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.python,
+            &PythonModel::Pydantic,
+            None,
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn nested_array() {
+        let ast = for_test::nested_array_ast();
+        let result = generate_python(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.python,
+            &PythonModel::Dataclass,
+            None,
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn object_array() {
+        let ast = for_test::object_array_ast();
+        let result = generate_python(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.python,
+            &PythonModel::Dataclass,
+            None,
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn string_enum() {
+        let ast = for_test::string_enum_ast();
+        let result = generate_python(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.python,
+            &PythonModel::Dataclass,
+            None,
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn optional_object() {
+        let ast = for_test::optional_object_ast();
+        let result = generate_python(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.python,
+            &PythonModel::Dataclass,
+            None,
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn map() {
+        let ast = for_test::map_ast();
+        let result = generate_python(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.python,
+            &PythonModel::Dataclass,
+            None,
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn tuple() {
+        let ast = for_test::tuple_ast();
+        let result = generate_python(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.python,
+            &PythonModel::Dataclass,
+            None,
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn number_format() {
+        let ast = for_test::number_format_ast();
+        let result = generate_python(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.python,
+            &PythonModel::Dataclass,
+            None,
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn string_format() {
+        let ast = for_test::string_format_ast();
+        let result = generate_python(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.python,
+            &PythonModel::Dataclass,
+            None,
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn extends() {
+        let ast = for_test::extends_ast();
+        let result = generate_python(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.python,
+            &PythonModel::Dataclass,
+            None,
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn generic() {
+        let ast = for_test::generic_ast();
+        let result = generate_python(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.python,
+            &PythonModel::Dataclass,
+            None,
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn generic_ref() {
+        let ast = for_test::generic_ref_ast();
+        let result = generate_python(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.python,
+            &PythonModel::Dataclass,
+            None,
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn inline_enum() {
+        let ast = for_test::inline_enum_ast();
+        let result = generate_python(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.python,
+            &PythonModel::Dataclass,
+            None,
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn alias_after_classes() {
+        let ast_list = for_test::discriminated_definitions_ast();
+        let order: Vec<String> = order_definitions(&ast_list)
+            .into_iter()
+            .map(|ast| ast.identifier().to_string())
+            .collect();
+        assert_eq!(order, vec!["owner", "cat", "dog", "pet"]);
+    }
+
+    #[test]
+    fn wire_name() {
+        let ast = for_test::wire_name_ast();
+        let result = generate_python(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.python,
+            &PythonModel::Pydantic,
+            Some(Naming::Snake),
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+}
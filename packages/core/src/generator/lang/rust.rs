@@ -0,0 +1,743 @@
+use std::path::PathBuf;
+
+use super::super::types::{
+    ast_enum,
+    ast_type_alias::{
+        KeywordNode, Keywords, Node, NumberFormat, ObjectNode, Property, TypeAliasAst, UnionNode,
+    },
+    AST,
+};
+use crate::{
+    backend::rust,
+    diagnostic::{Code, Diagnostic},
+    generator::{
+        main::{params_of, unexpected},
+        types::{DynImport, Import, Imports, RefImport},
+        utils::{capitalize, rename, to_pascal_case, to_snake_case},
+    },
+    types::config::Naming,
+};
+
+const STRUCT_DERIVE: &str = "#[derive(Debug, Clone, Serialize, Deserialize)]";
+const UNIT_ENUM_DERIVE: &str =
+    "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]";
+const REPR_ENUM_DERIVE: &str =
+    "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize_repr, Deserialize_repr)]";
+
+const KEYWORDS: [&str; 38] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+/// `derive`, the `use` of its serde macros is pushed to imports.
+fn derive(imports: &mut Imports, derive: &'static str) -> &'static str {
+    let (from, suffix) = match derive {
+        REPR_ENUM_DERIVE => ("serde_repr", "_repr"),
+        _ => ("serde", ""),
+    };
+    for name in ["Deserialize", "Serialize"] {
+        imports.push(Import::Dyn(DynImport {
+            name: format!("{}{}", name, suffix),
+            from: from.to_string(),
+        }));
+    }
+    derive
+}
+
+/// Rust has neither anonymous structs nor anonymous unions.
+///
+/// So nested objects and unions are **hoisted** to their own named items,
+/// the name is `<Parent><Field>`, and they are emitted right before the item using them.
+///
+/// ```text
+/// #[derive(Debug, Clone, Serialize, Deserialize)]
+/// pub struct TestAstHead {
+///     pub cmd: String,
+/// }
+///
+/// #[derive(Debug, Clone, Serialize, Deserialize)]
+/// pub struct TestAst {
+///     pub head: TestAstHead,
+/// }
+/// ```
+///
+/// `$ref` is rendered by name only, the `use crate::...` path is resolved in compile phase.
+pub fn generate_rust(
+    ast: &AST,
+    imports: &mut Imports,
+    tabsize: usize,
+    naming: Option<Naming>,
+) -> Result<String, Diagnostic> {
+    let mut hoisted: Vec<String> = vec![];
+
+    let item = match ast {
+        AST::Enum(enum_ast) => render_enum(
+            imports,
+            &enum_ast.members,
+            &enum_ast.r#type,
+            enum_ast.format,
+            &capitalize(&enum_ast.identifier),
+            tabsize,
+        )?,
+        AST::TypeAlias(type_alias_ast) => {
+            let id = capitalize(&type_alias_ast.identifier);
+            let params = &type_alias_ast.type_parameters;
+            match &type_alias_ast.body {
+                Node::Object(node) => {
+                    render_struct(imports, &mut hoisted, node, &id, params, tabsize, naming)?
+                }
+                Node::Union(node) => {
+                    render_union(imports, &mut hoisted, node, &id, params, tabsize, naming)?
+                }
+                Node::Split(split) => {
+                    if let Some(node) = split.nodes.get(rust::ID) {
+                        return generate_rust(
+                            &AST::TypeAlias(TypeAliasAst {
+                                identifier: type_alias_ast.identifier.clone(),
+                                type_parameters: type_alias_ast.type_parameters.clone(),
+                                description: type_alias_ast.description.clone(),
+                                deprecated: type_alias_ast.deprecated.clone(),
+                                body: node.as_ref().clone(),
+                            }),
+                            imports,
+                            tabsize,
+                            naming,
+                        );
+                    }
+                    return Ok(String::new());
+                }
+                node => {
+                    let r = iterate_type(imports, &mut hoisted, node, &id, tabsize, naming)?;
+                    format!("pub type {}{} = {};\n", id, generics(params), r)
+                }
+            }
+        }
+    };
+
+    let mut result = String::new();
+    for h in hoisted {
+        result += &format!("\n{}", h);
+    }
+    result += &format!("\n{}", item);
+    Ok(result)
+}
+
+/// Render the rust type of a node.
+///
+/// `hint` is the name used when the node must be hoisted to a named item.
+fn iterate_type(
+    imports: &mut Imports,
+    hoisted: &mut Vec<String>,
+    node: &Node,
+    hint: &str,
+    tabsize: usize,
+    naming: Option<Naming>,
+) -> Result<String, Diagnostic> {
+    let result = match node {
+        Node::StringLiteral(_) => String::from("String"),
+        Node::NumberLiteral(node) => number(node.format),
+        Node::Keyword(node) => keyword(node),
+        Node::Object(node) => {
+            let params = params_of(node.values.iter().map(|p| &p.body));
+            let s = render_struct(imports, hoisted, node, hint, &params, tabsize, naming)?;
+            hoisted.push(s);
+            format!("{}{}", hint, generics(&params))
+        }
+        Node::Array(node) => {
+            let r = iterate_type(
+                imports,
+                hoisted,
+                &node.items,
+                &format!("{}Item", hint),
+                tabsize,
+                naming,
+            )?;
+            format!("Vec<{}>", r)
+        }
+        Node::Map(node) => {
+            imports.push(Import::Dyn(DynImport {
+                name: String::from("HashMap"),
+                from: String::from("std::collections"),
+            }));
+            // f64 is not `Hash`, keys with a format are always integers.
+            let k = match node.keys.as_ref() {
+                Node::Keyword(k) if matches!(k.value, Keywords::Number) => {
+                    match k.number_format() {
+                        Some(f) => number(Some(f)),
+                        None => String::from("i64"),
+                    }
+                }
+                keys => iterate_type(
+                    imports,
+                    hoisted,
+                    keys,
+                    &format!("{}Key", hint),
+                    tabsize,
+                    naming,
+                )?,
+            };
+            let v = iterate_type(
+                imports,
+                hoisted,
+                &node.values,
+                &format!("{}Value", hint),
+                tabsize,
+                naming,
+            )?;
+            format!("HashMap<{}, {}>", k, v)
+        }
+        Node::Tuple(node) => {
+            let items: Vec<String> = node
+                .items
+                .iter()
+                .enumerate()
+                .map(|(idx, t)| {
+                    iterate_type(
+                        imports,
+                        hoisted,
+                        t,
+                        &format!("{}{}", hint, idx),
+                        tabsize,
+                        naming,
+                    )
+                })
+                .collect::<Result<_, _>>()?;
+            // a tuple of one item needs a trailing comma.
+            if items.len() == 1 {
+                format!("({},)", items[0])
+            } else {
+                format!("({})", items.join(", "))
+            }
+        }
+        Node::Ref(node) => {
+            if !node.path.is_empty() {
+                imports.push(Import::Ref(RefImport {
+                    name: capitalize(&node.name),
+                    from: PathBuf::from(&node.path),
+                }));
+            }
+            let args: Vec<String> = node
+                .args
+                .iter()
+                .enumerate()
+                .map(|(idx, a)| {
+                    iterate_type(
+                        imports,
+                        hoisted,
+                        a,
+                        &format!("{}Arg{}", hint, idx),
+                        tabsize,
+                        naming,
+                    )
+                })
+                .collect::<Result<_, _>>()?;
+            format!("{}{}", capitalize(&node.name), generics(&args))
+        }
+        Node::Param(node) => node.name.clone(),
+        Node::Dyn(node) => {
+            imports.push(Import::Dyn(DynImport {
+                name: node.name.clone(),
+                from: node.from.clone(),
+            }));
+            node.name.clone()
+        }
+        Node::Union(node) => {
+            let params = params_of(&node.types);
+            let s = render_union(imports, hoisted, node, hint, &params, tabsize, naming)?;
+            hoisted.push(s);
+            format!("{}{}", hint, generics(&params))
+        }
+        Node::Enum(node) => {
+            let s = render_enum(
+                imports,
+                &node.members,
+                &node.r#type,
+                node.format,
+                hint,
+                tabsize,
+            )?;
+            hoisted.push(s);
+            hint.to_string()
+        }
+        Node::Split(_) => return Err(unexpected("split node")),
+        Node::Empty => return Err(unexpected("empty node")),
+    };
+    Ok(result)
+}
+
+fn render_struct(
+    imports: &mut Imports,
+    hoisted: &mut Vec<String>,
+    node: &ObjectNode,
+    id: &str,
+    params: &[String],
+    tabsize: usize,
+    naming: Option<Naming>,
+) -> Result<String, Diagnostic> {
+    let indent = " ".repeat(tabsize);
+    // fields of bases are flattened into the struct by serde.
+    let mut r: String = node
+        .extends
+        .iter()
+        .map(|base| {
+            let ty = iterate_type(
+                imports,
+                hoisted,
+                &Node::Ref(base.clone()),
+                id,
+                tabsize,
+                naming,
+            )?;
+            Ok(format!(
+                "{i}#[serde(flatten)]\n{i}pub {}: {},\n",
+                to_field_name(&base.name),
+                ty,
+                i = indent
+            ))
+        })
+        .collect::<Result<_, Diagnostic>>()?;
+    r += &node
+        .values
+        .iter()
+        .map(|v| iterate_properties(imports, hoisted, v, id, tabsize, naming))
+        .collect::<Result<String, _>>()?;
+
+    Ok(format!(
+        "{}\npub struct {}{} {{\n{}}}\n",
+        derive(imports, STRUCT_DERIVE),
+        id,
+        generics(params),
+        r
+    ))
+}
+
+/// `<T, E>` of a generic item or its args, empty if there is none.
+fn generics(params: &[String]) -> String {
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", params.join(", "))
+    }
+}
+
+fn iterate_properties(
+    imports: &mut Imports,
+    hoisted: &mut Vec<String>,
+    p: &Property,
+    parent: &str,
+    tabsize: usize,
+    naming: Option<Naming>,
+) -> Result<String, Diagnostic> {
+    let indent = " ".repeat(tabsize);
+    let hint = format!("{}{}", parent, to_pascal_case(&p.identifier));
+    let mut r = iterate_type(imports, hoisted, &p.body, &hint, tabsize, naming)?;
+    if p.optional || p.nullable {
+        r = format!("Option<{}>", r);
+    }
+
+    let field = field_name(p, naming);
+    let mut result = String::new();
+    if field.trim_start_matches("r#") != p.wire_name() {
+        result += &format!("{}#[serde(rename = \"{}\")]\n", indent, p.wire_name());
+    }
+    if p.optional {
+        result += &format!(
+            "{}#[serde(default, skip_serializing_if = \"Option::is_none\")]\n",
+            indent
+        );
+    }
+    result += &format!("{}pub {}: {},\n", indent, field, r);
+    Ok(result)
+}
+
+/// Unions of string literals only become a plain enum,
+/// otherwise an `untagged` enum with one newtype variant per member.
+fn render_union(
+    imports: &mut Imports,
+    hoisted: &mut Vec<String>,
+    node: &UnionNode,
+    id: &str,
+    params: &[String],
+    tabsize: usize,
+    naming: Option<Naming>,
+) -> Result<String, Diagnostic> {
+    let indent = " ".repeat(tabsize);
+
+    let is_string_literals = node
+        .types
+        .iter()
+        .all(|t| matches!(t, Node::StringLiteral(_)));
+    if is_string_literals {
+        let mut used: Vec<String> = vec![];
+        let mut r = String::new();
+        for t in node.types.iter() {
+            if let Node::StringLiteral(l) = t {
+                let variant = literal_variant(&l.value, &used);
+                r += &format!(
+                    "{i}#[serde(rename = \"{v}\")]\n{i}{n},\n",
+                    i = indent,
+                    v = l.value.escape_default(),
+                    n = variant
+                );
+                used.push(variant);
+            }
+        }
+        let derive = derive(imports, UNIT_ENUM_DERIVE);
+        return Ok(format!("{}\npub enum {} {{\n{}}}\n", derive, id, r));
+    }
+
+    let mut used: Vec<String> = vec![];
+    let mut r = String::new();
+    for (idx, t) in node.types.iter().enumerate() {
+        let mut variant = variant_name(t)?;
+        if used.contains(&variant) {
+            variant = format!("{}{}", variant, idx);
+        }
+        let ty = iterate_type(
+            imports,
+            hoisted,
+            t,
+            &format!("{}{}", id, variant),
+            tabsize,
+            naming,
+        )?;
+        r += &format!("{}{}({}),\n", indent, variant, ty);
+        used.push(variant);
+    }
+
+    Ok(format!(
+        "{}\n#[serde(untagged)]\npub enum {}{} {{\n{}}}\n",
+        derive(imports, STRUCT_DERIVE),
+        id,
+        generics(params),
+        r
+    ))
+}
+
+/// Variant of a string literal or a enum member, `in-progress` is `InProgress`.
+///
+/// Characters other than letters and digits split words, a name starting with a digit
+/// is prefixed by `V` and a name in `used` gets a number.
+fn literal_variant(value: &str, used: &[String]) -> String {
+    let mut name: String = value
+        .split(|c: char| !c.is_alphanumeric())
+        .map(capitalize)
+        .collect();
+    if name.is_empty() {
+        name = String::from("Empty");
+    }
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        name = format!("V{}", name);
+    }
+    if name == "Self" {
+        name = String::from("Self_");
+    }
+    let mut result = name.clone();
+    let mut n = 1;
+    while used.contains(&result) {
+        n += 1;
+        result = format!("{}{}", name, n);
+    }
+    result
+}
+
+fn variant_name(node: &Node) -> Result<String, Diagnostic> {
+    let name = match node {
+        Node::StringLiteral(_) => String::from("String"),
+        Node::NumberLiteral(_) => String::from("Number"),
+        Node::Keyword(node) => format!("{:?}", node.value),
+        Node::Object(_) => String::from("Object"),
+        Node::Array(_) => String::from("Array"),
+        Node::Map(_) => String::from("Map"),
+        Node::Tuple(_) => String::from("Tuple"),
+        Node::Enum(_) => String::from("Enum"),
+        // a boxed ref on a cycle, see `Rust::compile`.
+        Node::Ref(node) if node.name == "Box" && node.path.is_empty() && node.args.len() == 1 => {
+            variant_name(&node.args[0])?
+        }
+        Node::Ref(node) => capitalize(&node.name),
+        Node::Param(node) => capitalize(&node.name),
+        Node::Dyn(node) => to_pascal_case(&node.name),
+        Node::Union(_) => return Err(unexpected("union nested in a union")),
+        Node::Split(_) => return Err(unexpected("split node")),
+        Node::Empty => return Err(unexpected("empty node")),
+    };
+    Ok(name)
+}
+
+/// A unit enum of string members, or a `repr` enum of number members.
+fn render_enum(
+    imports: &mut Imports,
+    members: &ast_enum::Members,
+    m_type: &ast_enum::MembersType,
+    format: Option<NumberFormat>,
+    id: &str,
+    tabsize: usize,
+) -> Result<String, Diagnostic> {
+    let s = iterate_members(members, m_type, id, tabsize)?;
+
+    match m_type {
+        ast_enum::MembersType::String => {
+            let derive = derive(imports, UNIT_ENUM_DERIVE);
+            Ok(format!("{}\npub enum {} {{\n{}\n}}\n", derive, id, s))
+        }
+        ast_enum::MembersType::Number => {
+            // only integers can be the repr of an enum.
+            let repr = match format {
+                Some(f) if f.is_integer() => number(Some(f)),
+                _ => String::from("i64"),
+            };
+            Ok(format!(
+                "{}\n#[repr({})]\npub enum {} {{\n{}\n}}\n",
+                derive(imports, REPR_ENUM_DERIVE),
+                repr,
+                id,
+                s
+            ))
+        }
+    }
+}
+
+fn iterate_members(
+    members: &ast_enum::Members,
+    m_type: &ast_enum::MembersType,
+    type_id: &str,
+    tabsize: usize,
+) -> Result<String, Diagnostic> {
+    let indent = " ".repeat(tabsize);
+    let mut result = String::new();
+    let mut used: Vec<String> = vec![];
+
+    match m_type {
+        ast_enum::MembersType::String => {
+            for (idx, m) in members.iter().enumerate() {
+                let is_last = idx == members.len() - 1;
+                let variant = literal_variant(&m.identifier, &used);
+                let mut s = format!(
+                    "{i}#[serde(rename = \"{v}\")]\n{i}{n},",
+                    i = indent,
+                    v = m.value.escape_default(),
+                    n = variant
+                );
+                used.push(variant);
+                if !is_last {
+                    s += "\n"
+                }
+
+                result += &s
+            }
+        }
+        ast_enum::MembersType::Number => {
+            for (idx, m) in members.iter().enumerate() {
+                // reported with its key path by `Rust::check`.
+                if m.value.parse::<i64>().is_err() {
+                    return Err(Diagnostic::error(
+                        Code::InvalidEnum,
+                        format!(
+                            "Member {}.{} of a number enum must be a integer in rust.",
+                            type_id, m.identifier
+                        ),
+                    ));
+                }
+
+                let is_last = idx == members.len() - 1;
+                let variant = literal_variant(&m.identifier, &used);
+                let mut s = format!("{}{} = {},", indent, variant, m.value);
+                used.push(variant);
+                if !is_last {
+                    s += "\n"
+                }
+
+                result += &s
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Name of the field of a property in a struct, snake_case unless `naming` is set.
+pub fn field_name(p: &Property, naming: Option<Naming>) -> String {
+    match naming {
+        Some(_) => escape_keyword(&rename(&p.identifier, naming)),
+        None => to_field_name(&p.identifier),
+    }
+}
+
+/// `e.g.` userName -> user_name, type -> r#type
+fn to_field_name(identifier: &str) -> String {
+    escape_keyword(&to_snake_case(identifier))
+}
+
+/// `e.g.` type -> r#type, self -> self_
+pub fn escape_keyword(s: &str) -> String {
+    match s {
+        // can not be a raw identifier
+        "crate" | "self" | "Self" | "super" => format!("{}_", s),
+        _ if KEYWORDS.contains(&s) => format!("r#{}", s),
+        _ => s.to_string(),
+    }
+}
+
+fn keyword(k: &KeywordNode) -> String {
+    match k.value {
+        Keywords::Any => String::from("serde_json::Value"),
+        Keywords::Boolean => String::from("bool"),
+        Keywords::Number => number(k.number_format()),
+        Keywords::String => String::from("String"),
+    }
+}
+
+/// `f64` unless the number has a format.
+fn number(format: Option<NumberFormat>) -> String {
+    let s = match format {
+        Some(NumberFormat::Int32) => "i32",
+        Some(NumberFormat::Int64) => "i64",
+        Some(NumberFormat::Uint8) => "u8",
+        Some(NumberFormat::Uint16) => "u16",
+        Some(NumberFormat::Uint32) => "u32",
+        Some(NumberFormat::Uint64) => "u64",
+        Some(NumberFormat::Float32) => "f32",
+        Some(NumberFormat::Float64) | None => "f64",
+    };
+    String::from(s)
+}
+
+// unit test here
+#[cfg(test)]
+mod test {
+    use super::generate_rust;
+    use crate::generator::{
+        lang::for_test,
+        types::{ast_type_alias, AST, DEFAULT_TABSIZE},
+    };
+
+    #[test]
+    fn nested_object() {
+        let ast = for_test::nested_object_ast();
+        let result = generate_rust(&ast, &mut vec![], DEFAULT_TABSIZE.rust, None).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn array() {
+        let ast = for_test::array_ast();
+        let result = generate_rust(&ast, &mut vec![], DEFAULT_TABSIZE.rust, None).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn nested_array() {
+        let ast = for_test::nested_array_ast();
+        let result = generate_rust(&ast, &mut vec![], DEFAULT_TABSIZE.rust, None).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn object_array() {
+        let ast = for_test::object_array_ast();
+        let result = generate_rust(&ast, &mut vec![], DEFAULT_TABSIZE.rust, None).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn string_enum() {
+        let ast = for_test::string_enum_ast();
+        let result = generate_rust(&ast, &mut vec![], DEFAULT_TABSIZE.rust, None).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn optional_object() {
+        let ast = for_test::optional_object_ast();
+        let result = generate_rust(&ast, &mut vec![], DEFAULT_TABSIZE.rust, None).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn map() {
+        let ast = for_test::map_ast();
+        let result = generate_rust(&ast, &mut vec![], DEFAULT_TABSIZE.rust, None).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn tuple() {
+        let ast = for_test::tuple_ast();
+        let result = generate_rust(&ast, &mut vec![], DEFAULT_TABSIZE.rust, None).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn number_format() {
+        let ast = for_test::number_format_ast();
+        let result = generate_rust(&ast, &mut vec![], DEFAULT_TABSIZE.rust, None).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn string_format() {
+        let ast = for_test::string_format_ast();
+        let result = generate_rust(&ast, &mut vec![], DEFAULT_TABSIZE.rust, None).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn extends() {
+        let ast = for_test::extends_ast();
+        let result = generate_rust(&ast, &mut vec![], DEFAULT_TABSIZE.rust, None).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn generic() {
+        let ast = for_test::generic_ast();
+        let result = generate_rust(&ast, &mut vec![], DEFAULT_TABSIZE.rust, None).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn generic_ref() {
+        let ast = for_test::generic_ref_ast();
+        let result = generate_rust(&ast, &mut vec![], DEFAULT_TABSIZE.rust, None).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn inline_enum() {
+        let ast = for_test::inline_enum_ast();
+        let result = generate_rust(&ast, &mut vec![], DEFAULT_TABSIZE.rust, None).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn literal_union() {
+        let literal = |value: &str| {
+            ast_type_alias::Node::StringLiteral(ast_type_alias::StringLiteralNode {
+                value: String::from(value),
+            })
+        };
+        let ast = AST::TypeAlias(ast_type_alias::TypeAliasAst {
+            identifier: String::from("status"),
+            type_parameters: vec![],
+            description: None,
+            deprecated: None,
+            body: ast_type_alias::Node::Union(ast_type_alias::UnionNode {
+                types: vec![
+                    literal("in-progress"),
+                    literal("1st"),
+                    literal("a.b"),
+                    literal("a_b"),
+                    literal(""),
+                ],
+                discriminator: None,
+                descriptions: vec![],
+            }),
+        });
+        let mut imports = vec![];
+        let result = generate_rust(&ast, &mut imports, DEFAULT_TABSIZE.rust, None).unwrap();
+        insta::assert_yaml_snapshot!((result, imports));
+    }
+}
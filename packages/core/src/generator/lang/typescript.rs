@@ -1,146 +1,191 @@
 use std::path::PathBuf;
 
-use log::error;
-
 use super::super::types::{
     ast_enum,
-    ast_type_alias::{Node, Property, TypeAliasAst},
+    ast_type_alias::{
+        DefaultValue, EnumNode, KeywordNode, Keywords, MapNode, Node, NumberFormat,
+        NumberLiteralNode, ObjectNode, Property, RefNode, StringFormat, TupleNode, TypeAliasAst,
+        UnionNode,
+    },
     AST,
 };
 use crate::{
+    backend::typescript,
+    diagnostic::Diagnostic,
     generator::{
+        main::unexpected,
         types::{DynImport, Import, Imports, RefImport},
-        utils::capitalize,
+        utils::{capitalize, rename},
+        validate::{checks as checks_of, literal_message, one_of_message, Check, ValuePath},
     },
-    types::lang::SupportedLang,
+    types::config::{Int64Type, StringFormatType, TypescriptOptions},
 };
 
-pub fn generate_typescript(ast: &AST, imports: &mut Imports, tabsize: usize) -> String {
+pub fn generate_typescript(
+    ast: &AST,
+    imports: &mut Imports,
+    tabsize: usize,
+    options: &TypescriptOptions,
+) -> Result<String, Diagnostic> {
     let mut result = String::new();
 
     match ast {
         AST::Enum(enum_ast) => {
-            let s = iterate_members(&enum_ast.members, &enum_ast.r#type, tabsize);
+            result += &jsdoc(
+                enum_ast.description.as_ref(),
+                enum_ast.deprecated.as_ref(),
+                "",
+            );
+            let s = iterate_members(
+                &enum_ast.members,
+                &enum_ast.r#type,
+                enum_ast.format,
+                tabsize,
+                options,
+            );
             result += &format!(
                 "export enum {} {{\n{}\n}};\n",
                 capitalize(&enum_ast.identifier),
                 s
             );
         }
-        AST::TypeAlias(type_alias_ast) => match &type_alias_ast.body {
-            Node::StringLiteral(node) => {
-                let s = format!(
-                    "export type {} = \"{}\";\n",
-                    capitalize(&type_alias_ast.identifier),
-                    node.value
-                );
-                result += &s;
-            }
-            Node::NumberLiteral(node) => {
-                let s = format!(
-                    "export type {} = {};\n",
-                    capitalize(&type_alias_ast.identifier),
-                    node.value
-                );
-                result += &s;
-            }
-            Node::Keyword(node) => {
-                let s = format!(
-                    "export type {} = {};\n",
-                    capitalize(&type_alias_ast.identifier),
-                    node.value.to_string(SupportedLang::TypeScript)
-                );
-                result += &s;
-            }
-            Node::Object(node) => {
-                let r: String = node
-                    .values
-                    .iter()
-                    .enumerate()
-                    .map(|(idx, v)| {
-                        let is_last = idx == node.values.len() - 1;
-                        iterate_properties(imports, v, 1, tabsize, is_last)
-                    })
-                    .collect();
-                result += &format!(
-                    "export type {} = {{\n{}\n}};\n",
-                    capitalize(&type_alias_ast.identifier),
-                    r
-                );
-            }
-            Node::Array(node) => {
-                let r = iterate_array(imports, &node.items, tabsize);
-                result += &format!(
-                    "export type {} = {}[];\n",
-                    capitalize(&type_alias_ast.identifier),
-                    r
+        AST::TypeAlias(type_alias_ast) => {
+            // a split is documented by the definition of typescript.
+            if !matches!(type_alias_ast.body, Node::Split(_)) {
+                result += &jsdoc(
+                    type_alias_ast.description.as_ref(),
+                    type_alias_ast.deprecated.as_ref(),
+                    "",
                 );
             }
-            Node::Ref(node) => {
-                let s = format!(
-                    "export type {} = {};\n",
-                    capitalize(&type_alias_ast.identifier),
-                    capitalize(&node.name)
-                );
-                if !node.path.is_empty() {
-                    imports.push(Import::Ref(RefImport {
-                        name: capitalize(&node.name),
-                        from: PathBuf::from(&node.path),
-                    }));
+            let name = format!(
+                "{}{}",
+                capitalize(&type_alias_ast.identifier),
+                type_parameters(&type_alias_ast.type_parameters)
+            );
+            match &type_alias_ast.body {
+                Node::StringLiteral(node) => {
+                    let s = format!("export type {} = \"{}\";\n", name, node.value);
+                    result += &s;
                 }
-
-                result += &s;
-            }
-            Node::Dyn(node) => {
-                let s = format!(
-                    "export type {} = {}\n",
-                    capitalize(&type_alias_ast.identifier),
-                    capitalize(&node.name)
-                );
-                imports.push(Import::Dyn(DynImport {
-                    name: node.name.clone(),
-                    from: node.from.clone(),
-                }));
-
-                result += &s;
-            }
-            Node::Split(split) => {
-                if let Some(node) = &split.typescript {
-                    let s = generate_typescript(
-                        &AST::TypeAlias(TypeAliasAst {
-                            identifier: type_alias_ast.identifier.clone(),
-                            body: node.as_ref().clone(),
-                        }),
-                        imports,
-                        tabsize,
+                Node::NumberLiteral(node) => {
+                    let s = format!(
+                        "export type {} = {};\n",
+                        name,
+                        number_literal(node, options)
                     );
                     result += &s;
                 }
-            }
-            Node::Union(node) => {
-                let s = format!(
-                    "export type {} = {};\n",
-                    capitalize(&type_alias_ast.identifier),
-                    node.types
+                Node::Keyword(node) => {
+                    let s = format!(
+                        "export type {} = {};\n",
+                        name,
+                        keyword(imports, node, options)
+                    );
+                    result += &s;
+                }
+                Node::Object(node) => {
+                    let r = node
+                        .values
                         .iter()
-                        .map(|v| iterate_union(imports, v, tabsize))
-                        .collect::<Vec<String>>()
-                        .join(" | ")
-                );
-                result += &s;
-            }
-            Node::Empty => {
-                error!("Empty node.");
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
+                        .enumerate()
+                        .map(|(idx, v)| {
+                            let is_last = idx == node.values.len() - 1;
+                            iterate_properties(imports, v, 1, tabsize, is_last, options)
+                        })
+                        .collect::<Result<String, _>>()?;
+                    result += &format!(
+                        "export type {} = {};\n",
+                        name,
+                        with_bases(imports, node, format!("{{\n{}\n}}", r))
+                    );
+                    if node.has_defaults() {
+                        result += &render_create(
+                            imports,
+                            node,
+                            &type_alias_ast.identifier,
+                            &type_alias_ast.type_parameters,
+                            tabsize,
+                            options,
+                        )?;
+                    }
+                }
+                Node::Array(node) => {
+                    let r = iterate_array(imports, &node.items, tabsize, options)?;
+                    result += &format!("export type {} = {}[];\n", name, r);
+                }
+                Node::Map(node) => {
+                    result += &format!(
+                        "export type {} = {};\n",
+                        name,
+                        render_map(imports, node, tabsize, options)?
+                    );
+                }
+                Node::Tuple(node) => {
+                    result += &format!(
+                        "export type {} = {};\n",
+                        name,
+                        render_tuple(imports, node, tabsize, options)?
+                    );
+                }
+                Node::Ref(node) => {
+                    let s = format!(
+                        "export type {} = {};\n",
+                        name,
+                        ref_type(imports, node, tabsize, options)?
+                    );
+                    result += &s;
+                }
+                Node::Enum(node) => {
+                    let s = format!("export type {} = {};\n", name, literal_union(node, options));
+                    result += &s;
+                }
+                Node::Param(node) => {
+                    result += &format!("export type {} = {};\n", name, node.name);
                 }
+                Node::Dyn(node) => {
+                    let s = format!("export type {} = {}\n", name, capitalize(&node.name));
+                    imports.push(Import::Dyn(DynImport {
+                        name: node.name.clone(),
+                        from: node.from.clone(),
+                    }));
+
+                    result += &s;
+                }
+                Node::Split(split) => {
+                    if let Some(node) = split.nodes.get(typescript::ID) {
+                        let s = generate_typescript(
+                            &AST::TypeAlias(TypeAliasAst {
+                                identifier: type_alias_ast.identifier.clone(),
+                                type_parameters: type_alias_ast.type_parameters.clone(),
+                                description: type_alias_ast.description.clone(),
+                                deprecated: type_alias_ast.deprecated.clone(),
+                                body: node.as_ref().clone(),
+                            }),
+                            imports,
+                            tabsize,
+                            options,
+                        )?;
+                        result += &s;
+                    }
+                }
+                Node::Union(node) => {
+                    let s = format!(
+                        "export type {} = {};\n",
+                        name,
+                        render_union(imports, node, tabsize, options)?
+                    );
+                    result += &s;
+                }
+                Node::Empty => return Err(unexpected("empty node")),
             }
-        },
+        }
+    }
+    if options.validate {
+        result += &generate_validate(ast, imports, tabsize, options);
     }
 
-    result
+    Ok(result)
 }
 
 fn iterate_properties(
@@ -149,12 +194,15 @@ fn iterate_properties(
     deepth: usize,
     tabsize: usize,
     is_last: bool,
-) -> String {
+    options: &TypescriptOptions,
+) -> Result<String, Diagnostic> {
     let indent = " ".repeat(deepth * tabsize);
-    let mut result = String::new();
+    let name = property_name(p, options);
+    let null = if p.nullable { " | null" } else { "" };
+    let mut result = jsdoc(p.description.as_ref(), p.deprecated.as_ref(), &indent);
     match &p.body {
         Node::StringLiteral(node) => {
-            let mut s = format!("{}{}: \"{}\";", indent, p.identifier, node.value);
+            let mut s = format!("{}{}: \"{}\"{null};", indent, name, node.value);
             if !is_last {
                 s += "\n"
             }
@@ -162,7 +210,12 @@ fn iterate_properties(
             result += &s;
         }
         Node::NumberLiteral(node) => {
-            let mut s = format!("{}{}: {};", indent, p.identifier, node.value);
+            let mut s = format!(
+                "{}{}: {}{null};",
+                indent,
+                name,
+                number_literal(node, options)
+            );
             if !is_last {
                 s += "\n"
             }
@@ -171,10 +224,10 @@ fn iterate_properties(
         }
         Node::Keyword(node) => {
             let mut s = format!(
-                "{}{}: {};",
+                "{}{}: {}{null};",
                 indent,
-                p.identifier,
-                node.value.to_string(SupportedLang::TypeScript)
+                name,
+                keyword(imports, node, options)
             );
             if !is_last {
                 s += "\n"
@@ -183,23 +236,18 @@ fn iterate_properties(
             result += &s;
         }
         Node::Object(node) => {
-            let r: String = node
+            let r = node
                 .values
                 .iter()
                 .enumerate()
                 .map(|(idx, v)| {
                     let is_last = idx == node.values.len() - 1;
-                    iterate_properties(imports, v, deepth + 1, tabsize, is_last)
+                    iterate_properties(imports, v, deepth + 1, tabsize, is_last, options)
                 })
-                .collect();
+                .collect::<Result<String, _>>()?;
 
-            let mut s = format!(
-                "{i}{id}: {{\n{re}\n{ii}}};",
-                i = indent,
-                id = p.identifier,
-                ii = indent,
-                re = r
-            );
+            let object = with_bases(imports, node, format!("{{\n{}\n{}}}", r, indent));
+            let mut s = format!("{}{}: {}{null};", indent, name, object);
             if !is_last {
                 s += "\n"
             }
@@ -207,8 +255,26 @@ fn iterate_properties(
             result += &s
         }
         Node::Array(node) => {
-            let r = &iterate_array(imports, &node.items, tabsize);
-            let mut s = format!("{}{}: {}[];", indent, p.identifier, r);
+            let r = &iterate_array(imports, &node.items, tabsize, options)?;
+            let mut s = format!("{}{}: {}[]{null};", indent, name, r);
+            if !is_last {
+                s += "\n"
+            }
+
+            result += &s
+        }
+        Node::Map(node) => {
+            let r = render_map(imports, node, tabsize, options)?;
+            let mut s = format!("{}{}: {}{null};", indent, name, r);
+            if !is_last {
+                s += "\n"
+            }
+
+            result += &s
+        }
+        Node::Tuple(node) => {
+            let r = render_tuple(imports, node, tabsize, options)?;
+            let mut s = format!("{}{}: {}{null};", indent, name, r);
             if !is_last {
                 s += "\n"
             }
@@ -216,20 +282,30 @@ fn iterate_properties(
             result += &s
         }
         Node::Ref(node) => {
-            let mut s = format!("{}{}: {};", indent, p.identifier, capitalize(&node.name));
+            let r = ref_type(imports, node, tabsize, options)?;
+            let mut s = format!("{}{}: {}{null};", indent, name, r);
             if !is_last {
                 s += "\n"
             }
-            if !node.path.is_empty() {
-                imports.push(Import::Ref(RefImport {
-                    name: capitalize(&node.name),
-                    from: PathBuf::from(&node.path),
-                }));
+            result += &s
+        }
+        Node::Enum(node) => {
+            let r = literal_union(node, options);
+            let mut s = format!("{}{}: {}{null};", indent, name, r);
+            if !is_last {
+                s += "\n"
+            }
+            result += &s
+        }
+        Node::Param(node) => {
+            let mut s = format!("{}{}: {}{null};", indent, name, node.name);
+            if !is_last {
+                s += "\n"
             }
             result += &s
         }
         Node::Dyn(node) => {
-            let mut s = format!("{}{}: {};", indent, &p.identifier, &node.name,);
+            let mut s = format!("{}{}: {}{null};", indent, &name, &node.name,);
             if !is_last {
                 s += "\n"
             }
@@ -240,24 +316,13 @@ fn iterate_properties(
 
             result += &s;
         }
-        Node::Split(_) => {
-            error!("Split-type can only use on top-level.");
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1);
-            }
-        }
+        Node::Split(_) => return Err(unexpected("split node")),
         Node::Union(node) => {
             let mut s = format!(
-                "{}{}: {};",
+                "{}{}: {}{null};",
                 indent,
-                p.identifier,
-                node.types
-                    .iter()
-                    .map(|v| iterate_union(imports, v, tabsize))
-                    .collect::<Vec<String>>()
-                    .join(" | ")
+                name,
+                render_union(imports, node, tabsize, options)?
             );
             if !is_last {
                 s += "\n"
@@ -265,19 +330,115 @@ fn iterate_properties(
 
             result += &s;
         }
-        Node::Empty => {
-            error!("Empty node.");
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1);
-            }
+        Node::Empty => return Err(unexpected("empty node")),
+    }
+    Ok(result)
+}
+
+/// `Record<K, V>`
+fn render_map(
+    imports: &mut Imports,
+    node: &MapNode,
+    tabsize: usize,
+    options: &TypescriptOptions,
+) -> Result<String, Diagnostic> {
+    Ok(format!(
+        "Record<{}, {}>",
+        iterate_array(imports, &node.keys, tabsize, options)?,
+        iterate_array(imports, &node.values, tabsize, options)?
+    ))
+}
+
+/// `[A, B, C]`
+fn render_tuple(
+    imports: &mut Imports,
+    node: &TupleNode,
+    tabsize: usize,
+    options: &TypescriptOptions,
+) -> Result<String, Diagnostic> {
+    let items = node
+        .items
+        .iter()
+        .map(|i| iterate_array(imports, i, tabsize, options))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(format!("[{}]", items.join(", ")))
+}
+
+/// `Name` or `Name<A, B>` with type arguments, imported if it is in another file.
+fn ref_type(
+    imports: &mut Imports,
+    node: &RefNode,
+    tabsize: usize,
+    options: &TypescriptOptions,
+) -> Result<String, Diagnostic> {
+    if !node.path.is_empty() {
+        imports.push(Import::Ref(RefImport {
+            name: capitalize(&node.name),
+            from: PathBuf::from(&node.path),
+        }));
+    }
+    if node.args.is_empty() {
+        return Ok(capitalize(&node.name));
+    }
+    let args = node
+        .args
+        .iter()
+        .map(|a| iterate_array(imports, a, tabsize, options))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(format!("{}<{}>", capitalize(&node.name), args.join(", ")))
+}
+
+/// `<T, E>` of a generic definition, empty if not generic.
+fn type_parameters(params: &[String]) -> String {
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", params.join(", "))
+    }
+}
+
+/// `Base & { ... }`, `object` is the own properties.
+fn with_bases(imports: &mut Imports, node: &ObjectNode, object: String) -> String {
+    let mut types: Vec<String> = vec![];
+    for base in node.extends.iter() {
+        if !base.path.is_empty() {
+            imports.push(Import::Ref(RefImport {
+                name: capitalize(&base.name),
+                from: PathBuf::from(&base.path),
+            }));
         }
+        types.push(capitalize(&base.name));
+    }
+    if types.is_empty() || !node.values.is_empty() {
+        types.push(object);
+    }
+    types.join(" & ")
+}
+
+/// `name` or `name?` if optional.
+fn property_name(p: &Property, options: &TypescriptOptions) -> String {
+    let key = property_key(p, options);
+    if p.optional {
+        format!("{}?", key)
+    } else {
+        key
+    }
+}
+
+/// Key of a property, the key in JSON unless `naming` is set.
+pub fn property_key(p: &Property, options: &TypescriptOptions) -> String {
+    match options.naming {
+        Some(_) => rename(&p.identifier, options.naming),
+        None => p.wire_name().to_string(),
     }
-    result
 }
 
-fn iterate_array(imports: &mut Imports, n: &Node, tabsize: usize) -> String {
+fn iterate_array(
+    imports: &mut Imports,
+    n: &Node,
+    tabsize: usize,
+    options: &TypescriptOptions,
+) -> Result<String, Diagnostic> {
     let mut result = String::new();
     match n {
         Node::StringLiteral(node) => {
@@ -285,37 +446,45 @@ fn iterate_array(imports: &mut Imports, n: &Node, tabsize: usize) -> String {
             result += &s;
         }
         Node::NumberLiteral(node) => {
-            let s = node.value.to_string();
-            result += &s;
+            result += &number_literal(node, options);
         }
         Node::Keyword(node) => {
-            let s = node.value.to_string(SupportedLang::TypeScript);
+            let s = keyword(imports, node, options);
             result += &s;
         }
         Node::Object(node) => {
-            let r: String = node
+            let r = node
                 .values
                 .iter()
                 .enumerate()
                 .map(|(idx, v)| {
                     let is_last = idx == node.values.len() - 1;
-                    iterate_properties(imports, v, 1, tabsize, is_last)
+                    iterate_properties(imports, v, 1, tabsize, is_last, options)
                 })
-                .collect();
-            result += &format!("{{\n{}\n}}", r);
+                .collect::<Result<String, _>>()?;
+            let object = with_bases(imports, node, format!("{{\n{}\n}}", r));
+            // `[]` is appended to items.
+            if node.extends.is_empty() {
+                result += &object;
+            } else {
+                result += &format!("({})", object);
+            }
         }
         Node::Array(node) => {
-            let r = &iterate_array(imports, &node.items, tabsize);
+            let r = &iterate_array(imports, &node.items, tabsize, options)?;
             result += &format!("{}[]", r);
         }
+        Node::Map(node) => {
+            result += &render_map(imports, node, tabsize, options)?;
+        }
+        Node::Tuple(node) => {
+            result += &render_tuple(imports, node, tabsize, options)?;
+        }
         Node::Ref(node) => {
-            if !node.path.is_empty() {
-                imports.push(Import::Ref(RefImport {
-                    name: capitalize(&node.name),
-                    from: PathBuf::from(&node.path),
-                }));
-            }
-            result += &capitalize(&node.name);
+            result += &ref_type(imports, node, tabsize, options)?;
+        }
+        Node::Param(node) => {
+            result += &node.name;
         }
         Node::Dyn(node) => {
             imports.push(Import::Dyn(DynImport {
@@ -325,21 +494,9 @@ fn iterate_array(imports: &mut Imports, n: &Node, tabsize: usize) -> String {
 
             result += &node.name;
         }
-        Node::Split(_) => {
-            error!("Split-type can only use on top-level.");
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1);
-            }
-        }
+        Node::Split(_) => return Err(unexpected("split node")),
         Node::Union(node) => {
-            let r: String = node
-                .types
-                .iter()
-                .map(|v| iterate_union(imports, v, tabsize))
-                .collect::<Vec<String>>()
-                .join(" | ");
+            let r: String = render_union(imports, node, tabsize, options)?;
 
             if node.types.len() > 1 {
                 result += &format!("({})", r);
@@ -347,22 +504,26 @@ fn iterate_array(imports: &mut Imports, n: &Node, tabsize: usize) -> String {
                 result += &r;
             }
         }
-        Node::Empty => {
-            error!("Empty node.");
-            if crate::is_dev() {
-                panic!();
+        Node::Enum(node) => {
+            let r = literal_union(node, options);
+
+            if node.members.len() > 1 {
+                result += &format!("({})", r);
             } else {
-                std::process::exit(1);
+                result += &r;
             }
         }
+        Node::Empty => return Err(unexpected("empty node")),
     }
-    result
+    Ok(result)
 }
 
 fn iterate_members(
     members: &ast_enum::Members,
     m_type: &ast_enum::MembersType,
+    format: Option<NumberFormat>,
     tabsize: usize,
+    options: &TypescriptOptions,
 ) -> String {
     let indent = " ".repeat(tabsize);
     let mut result = String::new();
@@ -371,7 +532,13 @@ fn iterate_members(
         ast_enum::MembersType::String => {
             for (idx, m) in members.iter().enumerate() {
                 let is_last = idx == members.len() - 1;
-                let mut s = format!("{}{} = \"{}\",", indent, capitalize(&m.identifier), m.value);
+                let mut s = format!(
+                    "{}{}{} = \"{}\",",
+                    jsdoc(m.description.as_ref(), m.deprecated.as_ref(), &indent),
+                    indent,
+                    capitalize(&m.identifier),
+                    m.value
+                );
                 if !is_last {
                     s += "\n"
                 }
@@ -382,7 +549,18 @@ fn iterate_members(
         ast_enum::MembersType::Number => {
             for (idx, m) in members.iter().enumerate() {
                 let is_last = idx == members.len() - 1;
-                let mut s = format!("{}{} = {},", indent, capitalize(&m.identifier), m.value);
+                // enum members can not be bigint.
+                let value = match (format, options.int64) {
+                    (Some(NumberFormat::Int64), Int64Type::String) => format!("\"{}\"", m.value),
+                    _ => m.value.clone(),
+                };
+                let mut s = format!(
+                    "{}{}{} = {},",
+                    jsdoc(m.description.as_ref(), m.deprecated.as_ref(), &indent),
+                    indent,
+                    capitalize(&m.identifier),
+                    value
+                );
                 if !is_last {
                     s += "\n"
                 }
@@ -395,42 +573,134 @@ fn iterate_members(
     result
 }
 
-fn iterate_union(imports: &mut Imports, n: &Node, tabsize: usize) -> String {
+/// Variants joined by `|`, each with its description as a inline JSDoc.
+fn render_union(
+    imports: &mut Imports,
+    node: &UnionNode,
+    tabsize: usize,
+    options: &TypescriptOptions,
+) -> Result<String, Diagnostic> {
+    let types = node
+        .types
+        .iter()
+        .enumerate()
+        .map(|(idx, v)| {
+            let doc = match node.descriptions.get(idx) {
+                Some(Some(d)) => format!("/** {} */ ", escape_doc(d).replace('\n', " ")),
+                _ => String::new(),
+            };
+            Ok(doc + &iterate_union(imports, v, tabsize, options)?)
+        })
+        .collect::<Result<Vec<String>, Diagnostic>>()?;
+    Ok(types.join(" | "))
+}
+
+/// Values of the members joined by `|`, each with its description as a inline JSDoc.
+fn literal_union(node: &EnumNode, options: &TypescriptOptions) -> String {
+    node.members
+        .iter()
+        .map(|m| {
+            let doc = match &m.description {
+                Some(d) => format!("/** {} */ ", escape_doc(d).replace('\n', " ")),
+                None => String::new(),
+            };
+            doc + &member_literal(node, &m.value, options)
+        })
+        .collect::<Vec<String>>()
+        .join(" | ")
+}
+
+/// `"value"` of a string member, `number_literal` of a number one.
+fn member_literal(node: &EnumNode, value: &str, options: &TypescriptOptions) -> String {
+    match node.r#type {
+        ast_enum::MembersType::String => js_string(value),
+        ast_enum::MembersType::Number => number_literal(
+            &NumberLiteralNode {
+                value: value.to_string(),
+                format: node.format,
+            },
+            options,
+        ),
+    }
+}
+
+/// `/** description */` followed by a new line, a block for multiple lines.
+///
+/// `@deprecated` is the last line, editors strike through the name with it.
+fn jsdoc(description: Option<&String>, deprecated: Option<&String>, indent: &str) -> String {
+    let mut lines: Vec<String> = description
+        .map(|d| escape_doc(d.trim()))
+        .map(|d| d.lines().map(|l| l.trim_end().to_string()).collect())
+        .unwrap_or_default();
+    if let Some(reason) = deprecated {
+        let tag = format!("@deprecated {}", escape_doc(reason.trim()));
+        lines.push(tag.trim_end().to_string());
+    }
+    match lines.as_slice() {
+        [] => String::new(),
+        [line] => format!("{}/** {} */\n", indent, line),
+        lines => {
+            let lines: String = lines
+                .iter()
+                .map(|line| match line.as_str() {
+                    "" => format!("{} *\n", indent),
+                    line => format!("{} * {}\n", indent, line),
+                })
+                .collect();
+            format!("{}/**\n{}{} */\n", indent, lines, indent)
+        }
+    }
+}
+
+/// `*/` would end the comment.
+fn escape_doc(description: &str) -> String {
+    description.replace("*/", "*\\/")
+}
+
+fn iterate_union(
+    imports: &mut Imports,
+    n: &Node,
+    tabsize: usize,
+    options: &TypescriptOptions,
+) -> Result<String, Diagnostic> {
     let mut result = String::new();
     match n {
         Node::StringLiteral(node) => {
             result += &format!("\"{}\"", node.value);
         }
         Node::NumberLiteral(node) => {
-            result += &node.value.to_string();
+            result += &number_literal(node, options);
         }
         Node::Keyword(node) => {
-            result += &node.value.to_string(SupportedLang::TypeScript);
+            result += &keyword(imports, node, options);
         }
         Node::Object(node) => {
-            let r: String = node
+            let r = node
                 .values
                 .iter()
                 .enumerate()
                 .map(|(idx, v)| {
                     let is_last = idx == node.values.len() - 1;
-                    iterate_properties(imports, v, 1, tabsize, is_last)
+                    iterate_properties(imports, v, 1, tabsize, is_last, options)
                 })
-                .collect();
-            result += &format!("{{\n{}\n}}", r);
+                .collect::<Result<String, _>>()?;
+            result += &with_bases(imports, node, format!("{{\n{}\n}}", r));
         }
         Node::Array(node) => {
-            let r = &iterate_array(imports, &node.items, tabsize);
+            let r = &iterate_array(imports, &node.items, tabsize, options)?;
             result += &format!("{}[]", r);
         }
+        Node::Map(node) => {
+            result += &render_map(imports, node, tabsize, options)?;
+        }
+        Node::Tuple(node) => {
+            result += &render_tuple(imports, node, tabsize, options)?;
+        }
         Node::Ref(node) => {
-            if !node.path.is_empty() {
-                imports.push(Import::Ref(RefImport {
-                    name: capitalize(&node.name),
-                    from: PathBuf::from(&node.path),
-                }));
-            }
-            result += &capitalize(&node.name);
+            result += &ref_type(imports, node, tabsize, options)?;
+        }
+        Node::Param(node) => {
+            result += &node.name;
         }
         Node::Dyn(node) => {
             imports.push(Import::Dyn(DynImport {
@@ -440,75 +710,946 @@ fn iterate_union(imports: &mut Imports, n: &Node, tabsize: usize) -> String {
 
             result += &node.name;
         }
-        Node::Split(_) => {
-            error!("Split-type can only use on top-level.");
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1);
+        Node::Split(_) => return Err(unexpected("split node")),
+        Node::Enum(node) => {
+            result += &literal_union(node, options);
+        }
+        Node::Union(_) => return Err(unexpected("union nested in a union")),
+        Node::Empty => return Err(unexpected("empty node")),
+    }
+
+    Ok(result)
+}
+
+fn keyword(imports: &mut Imports, k: &KeywordNode, options: &TypescriptOptions) -> String {
+    if let Some(f) = k.string_format() {
+        imports.push(Import::Ref(RefImport {
+            name: format_type(f).to_string(),
+            from: PathBuf::from(format!("/{}", typescript::FORMATS_MODULE)),
+        }));
+        return format_type(f).to_string();
+    }
+
+    match k.value {
+        Keywords::Any => String::from("any"),
+        Keywords::Boolean => String::from("boolean"),
+        Keywords::Number => match (k.number_format(), options.int64) {
+            (Some(NumberFormat::Int64), Int64Type::Bigint) => String::from("bigint"),
+            (Some(NumberFormat::Int64), Int64Type::String) => String::from("string"),
+            _ => String::from("number"),
+        },
+        Keywords::String => String::from("string"),
+    }
+}
+
+/// Name of the type of a string format, declared in `typescript::FORMATS_MODULE`.
+pub fn format_type(f: StringFormat) -> &'static str {
+    match f {
+        StringFormat::DateTime => "DateTimeString",
+        StringFormat::Date => "DateString",
+        StringFormat::Duration => "DurationString",
+        StringFormat::Uuid => "UuidString",
+        StringFormat::Byte => "Base64String",
+    }
+}
+
+/// `1`, or `1n` and `"1"` for int64 as bigint and string.
+fn number_literal(node: &NumberLiteralNode, options: &TypescriptOptions) -> String {
+    match (node.format, options.int64) {
+        (Some(NumberFormat::Int64), Int64Type::Bigint) => format!("{}n", node.value),
+        (Some(NumberFormat::Int64), Int64Type::String) => format!("\"{}\"", node.value),
+        _ => node.value.clone(),
+    }
+}
+
+/// `createX(partial?: Partial<X>): X`, a `X` with the defaults of its properties
+/// overridden by `partial`.
+///
+/// Required properties without a default are left to `partial`, the result is
+/// asserted to be a `X` if there is any.
+fn render_create(
+    imports: &mut Imports,
+    node: &ObjectNode,
+    identifier: &str,
+    params: &[String],
+    tabsize: usize,
+    options: &TypescriptOptions,
+) -> Result<String, Diagnostic> {
+    let i = " ".repeat(tabsize);
+    let id = capitalize(identifier);
+    let ty = format!("{}{}", id, type_parameters(params));
+    let assertion = if is_complete(node) {
+        String::new()
+    } else {
+        format!(" as {}", ty)
+    };
+    Ok(format!(
+        "\nexport function create{id}{p}(partial?: Partial<{ty}>): {ty} {{\n\
+        {i}return {{\n\
+        {d}\
+        {i}{i}...partial,\n\
+        {i}}}{assertion};\n\
+        }}\n",
+        p = type_parameters(params),
+        d = defaults(imports, node, 2, tabsize, options)?,
+    ))
+}
+
+/// Whether every required property of `node` has a default.
+fn is_complete(node: &ObjectNode) -> bool {
+    node.extends.is_empty()
+        && node.values.iter().all(|p| match &p.body {
+            _ if p.optional || p.default.is_some() => true,
+            Node::Object(n) => !p.nullable && is_complete(n),
+            _ => false,
+        })
+}
+
+/// `name: value,` lines of the defaults of `node`.
+fn defaults(
+    imports: &mut Imports,
+    node: &ObjectNode,
+    deepth: usize,
+    tabsize: usize,
+    options: &TypescriptOptions,
+) -> Result<String, Diagnostic> {
+    let indent = " ".repeat(deepth * tabsize);
+    let mut result = String::new();
+    for p in node.values.iter() {
+        match (&p.default, &p.body) {
+            (Some(d), body) => {
+                result += &format!(
+                    "{}{}: {},\n",
+                    indent,
+                    property_key(p, options),
+                    default_value(imports, body, d, options)?
+                );
             }
+            (None, Node::Object(n)) if !p.optional && !p.nullable && n.has_defaults() => {
+                result += &format!(
+                    "{}{}: {{\n{}{}}},\n",
+                    indent,
+                    property_key(p, options),
+                    defaults(imports, n, deepth + 1, tabsize, options)?,
+                    indent
+                );
+            }
+            (None, _) => {}
         }
-        Node::Union(_) => {
-            error!("Syntax error: Use a Union-type nested a Union-type.");
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1);
+    }
+    Ok(result)
+}
+
+/// TypeScript expression of a default of `node`, which is checked by `transformer`.
+fn default_value(
+    imports: &mut Imports,
+    node: &Node,
+    d: &DefaultValue,
+    options: &TypescriptOptions,
+) -> Result<String, Diagnostic> {
+    let value = match (d, node) {
+        (DefaultValue::String(s), Node::Keyword(k)) => match k.string_format() {
+            Some(f) if options.string_format == StringFormatType::Branded => {
+                format!("{} as {}", js_string(s), format_type(f))
+            }
+            _ => js_string(s),
+        },
+        (DefaultValue::String(s), _) => js_string(s),
+        (DefaultValue::Number(_), Node::NumberLiteral(literal)) => number_literal(literal, options),
+        (DefaultValue::Number(n), Node::Keyword(k)) => number_literal(
+            &NumberLiteralNode {
+                value: n.clone(),
+                format: k.number_format(),
+            },
+            options,
+        ),
+        (DefaultValue::Boolean(b), _) => b.to_string(),
+        (DefaultValue::Null, _) => String::from("null"),
+        (DefaultValue::Member(m), Node::Ref(r)) => {
+            format!("{}.{}", ref_type(imports, r, 0, options)?, capitalize(m))
+        }
+        (DefaultValue::Member(m), Node::Enum(e)) => {
+            match e.members.iter().find(|x| &x.identifier == m) {
+                Some(member) => member_literal(e, &member.value, options),
+                None => return Err(unexpected("default which is not a member of the enum")),
             }
         }
-        Node::Empty => {
-            error!("Empty node.");
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1);
+        (DefaultValue::Array(items), Node::Array(a)) => {
+            let items = items
+                .iter()
+                .map(|item| default_value(imports, &a.items, item, options))
+                .collect::<Result<Vec<_>, _>>()?;
+            format!("[{}]", items.join(", "))
+        }
+        _ => return Err(unexpected("default of another type")),
+    };
+    Ok(value)
+}
+
+/// `validateX(v: unknown): string[]` returning every problem of `v` as a `X`,
+/// and `isValidX(v: unknown): v is X`.
+///
+/// Type parameters and `$dyn` types are not checked.
+fn generate_validate(
+    ast: &AST,
+    imports: &mut Imports,
+    tabsize: usize,
+    options: &TypescriptOptions,
+) -> String {
+    let (identifier, params, checks, patterns) = match ast {
+        AST::Enum(enum_ast) => {
+            let id = capitalize(&enum_ast.identifier);
+            let members: Vec<String> = enum_ast
+                .members
+                .iter()
+                .map(|m| format!("{}.{}", id, capitalize(&m.identifier)))
+                .collect();
+            let values: Vec<String> = enum_ast
+                .members
+                .iter()
+                .map(|m| match enum_ast.r#type {
+                    ast_enum::MembersType::String => js_string(&m.value),
+                    ast_enum::MembersType::Number => m.value.clone(),
+                })
+                .collect();
+            let mut v = Validator::new(imports, &enum_ast.identifier, tabsize, options);
+            let cond = format!("![{}].includes(v as {})", members.join(", "), id);
+            let checks = v.fail(&cond, &ValuePath::default(), &one_of_message(&values), 1);
+            (&enum_ast.identifier, vec![], checks, v.patterns)
+        }
+        AST::TypeAlias(type_alias_ast) => {
+            // the body of typescript is generated by itself.
+            if matches!(type_alias_ast.body, Node::Split(_)) {
+                return String::new();
             }
+            let mut v = Validator::new(imports, &type_alias_ast.identifier, tabsize, options);
+            let checks = v.check(&type_alias_ast.body, "v", &ValuePath::default(), 1);
+            (
+                &type_alias_ast.identifier,
+                type_alias_ast.type_parameters.clone(),
+                checks,
+                v.patterns,
+            )
+        }
+    };
+
+    let i = " ".repeat(tabsize);
+    let id = capitalize(identifier);
+    // type parameters are not checked.
+    let unknowns = vec![String::from("unknown"); params.len()];
+    format!(
+        "{p}\n\
+        export function validate{id}(v: unknown): string[] {{\n\
+        {i}const errors: string[] = [];\n\
+        {checks}\
+        {i}return errors;\n\
+        }}\n\n\
+        export function isValid{id}(v: unknown): v is {id}{t} {{\n\
+        {i}return validate{id}(v).length === 0;\n\
+        }}\n",
+        p = patterns.concat(),
+        t = type_parameters(&unknowns),
+    )
+}
+
+/// Renders the checks of `generate_validate`, pushing a message to `errors` for each problem.
+struct Validator<'a> {
+    imports: &'a mut Imports,
+    /// `const pattern... = new RegExp(...)`, declared before the functions.
+    patterns: Vec<String>,
+    id: String,
+    tabsize: usize,
+    options: &'a TypescriptOptions,
+    /// Variables declared so far, their names are numbered.
+    vars: usize,
+}
+
+impl<'a> Validator<'a> {
+    fn new(
+        imports: &'a mut Imports,
+        id: &str,
+        tabsize: usize,
+        options: &'a TypescriptOptions,
+    ) -> Self {
+        Validator {
+            imports,
+            patterns: vec![],
+            id: id.to_string(),
+            tabsize,
+            options,
+            vars: 0,
         }
     }
 
-    result
+    /// Statements pushing to `errors` the problems of `expr`, a value of `node` at `path`.
+    fn check(&mut self, node: &Node, expr: &str, path: &ValuePath, deepth: usize) -> String {
+        match node {
+            Node::Keyword(node) => {
+                let (cond, message) = match node.value {
+                    Keywords::Any => return String::new(),
+                    Keywords::Boolean => (
+                        format!("typeof {} !== \"boolean\"", expr),
+                        "must be a boolean",
+                    ),
+                    Keywords::String => (
+                        format!("typeof {} !== \"string\"", expr),
+                        "must be a string",
+                    ),
+                    Keywords::Number => match (node.number_format(), self.options.int64) {
+                        (Some(NumberFormat::Int64), Int64Type::Bigint) => (
+                            format!("typeof {} !== \"bigint\"", expr),
+                            "must be a bigint",
+                        ),
+                        (Some(NumberFormat::Int64), Int64Type::String) => (
+                            format!(
+                                "typeof {e} !== \"string\" || !/^-?\\d+$/.test({e})",
+                                e = expr
+                            ),
+                            "must be an integer string",
+                        ),
+                        (Some(f), _) if f.is_integer() => (
+                            format!(
+                                "typeof {e} !== \"number\" || !Number.isInteger({e})",
+                                e = expr
+                            ),
+                            "must be an integer",
+                        ),
+                        _ => (
+                            format!("typeof {} !== \"number\"", expr),
+                            "must be a number",
+                        ),
+                    },
+                };
+                let number = match node.number_format() {
+                    Some(NumberFormat::Int64) if self.options.int64 != Int64Type::Number => {
+                        format!("Number({})", expr)
+                    }
+                    _ => expr.to_string(),
+                };
+                let mut checks = String::new();
+                for check in checks_of(&node.constraints) {
+                    let cond = match &check {
+                        Check::MinLength(n) => format!("Array.from({}).length < {}", expr, n),
+                        Check::MaxLength(n) => format!("Array.from({}).length > {}", expr, n),
+                        Check::Pattern(p) => format!("!{}.test({})", self.pattern(p, path), expr),
+                        Check::Minimum(n) => format!("{} < {}", number, n),
+                        Check::Maximum(n) => format!("{} > {}", number, n),
+                        Check::MinItems(_) | Check::MaxItems(_) => continue,
+                    };
+                    checks += &self.fail(&cond, path, &check.message(), deepth + 1);
+                }
+                self.fail_else(&cond, path, message, checks, deepth)
+            }
+            Node::StringLiteral(node) => {
+                let value = js_string(&node.value);
+                let cond = format!("{} !== {}", expr, value);
+                self.fail(&cond, path, &literal_message(&value), deepth)
+            }
+            Node::NumberLiteral(node) => {
+                let cond = format!("{} !== {}", expr, number_literal(node, self.options));
+                self.fail(&cond, path, &literal_message(&node.value), deepth)
+            }
+            Node::Enum(node) => {
+                let literals: Vec<String> = node
+                    .members
+                    .iter()
+                    .map(|m| member_literal(node, &m.value, self.options))
+                    .collect();
+                let values: Vec<String> = node
+                    .members
+                    .iter()
+                    .map(|m| match node.r#type {
+                        ast_enum::MembersType::String => js_string(&m.value),
+                        ast_enum::MembersType::Number => m.value.clone(),
+                    })
+                    .collect();
+                let cond = format!(
+                    "!([{}] as unknown[]).includes({})",
+                    literals.join(", "),
+                    expr
+                );
+                self.fail(&cond, path, &one_of_message(&values), deepth)
+            }
+            Node::Object(node) => {
+                let i = " ".repeat((deepth + 1) * self.tabsize);
+                let record = self.var("o");
+                let mut checks = String::new();
+                for base in node.extends.iter() {
+                    checks += &self.call(base, &record, path, deepth + 1);
+                }
+                for p in node.values.iter() {
+                    checks += &self.property(p, &record, path, deepth + 1);
+                }
+                if !checks.is_empty() {
+                    checks =
+                        format!("{i}const {record} = {expr} as Record<string, unknown>;\n{checks}");
+                }
+                let cond = format!(
+                    "typeof {e} !== \"object\" || {e} === null || Array.isArray({e})",
+                    e = expr
+                );
+                self.fail_else(&cond, path, "must be an object", checks, deepth)
+            }
+            Node::Array(node) => {
+                let i = " ".repeat((deepth + 1) * self.tabsize);
+                let mut checks = String::new();
+                for check in checks_of(&node.constraints) {
+                    let cond = match &check {
+                        Check::MinItems(n) => format!("{}.length < {}", expr, n),
+                        Check::MaxItems(n) => format!("{}.length > {}", expr, n),
+                        _ => continue,
+                    };
+                    checks += &self.fail(&cond, path, &check.message(), deepth + 1);
+                }
+                let (idx, item) = (self.var("i"), self.var("x"));
+                let items = self.check(&node.items, &item, &path.index(&idx), deepth + 2);
+                if !items.is_empty() {
+                    checks += &format!(
+                        "{i}for (let {idx} = 0; {idx} < {expr}.length; {idx}++) {{\n\
+                        {i}{t}const {item}: unknown = {expr}[{idx}];\n\
+                        {items}\
+                        {i}}}\n",
+                        t = " ".repeat(self.tabsize),
+                    );
+                }
+                let cond = format!("!Array.isArray({})", expr);
+                self.fail_else(&cond, path, "must be an array", checks, deepth)
+            }
+            Node::Map(node) => {
+                let i = " ".repeat((deepth + 1) * self.tabsize);
+                let t = " ".repeat(self.tabsize);
+                let (record, key, value) = (self.var("o"), self.var("k"), self.var("x"));
+                let values = self.check(&node.values, &value, &path.index(&key), deepth + 2);
+                let checks = match values.is_empty() {
+                    true => String::new(),
+                    false => format!(
+                        "{i}const {record} = {expr} as Record<string, unknown>;\n\
+                        {i}for (const {key} of Object.keys({record})) {{\n\
+                        {i}{t}const {value} = {record}[{key}];\n\
+                        {values}\
+                        {i}}}\n"
+                    ),
+                };
+                let cond = format!(
+                    "typeof {e} !== \"object\" || {e} === null || Array.isArray({e})",
+                    e = expr
+                );
+                self.fail_else(&cond, path, "must be an object", checks, deepth)
+            }
+            Node::Tuple(node) => {
+                let n = node.items.len();
+                let checks: String = node
+                    .items
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, item)| {
+                        let value = format!("{}[{}]", expr, idx);
+                        self.check(item, &value, &path.position(idx), deepth + 1)
+                    })
+                    .collect();
+                let cond = format!("!Array.isArray({e}) || {e}.length !== {n}", e = expr);
+                let message = format!("must be a tuple of {} items", n);
+                self.fail_else(&cond, path, &message, checks, deepth)
+            }
+            Node::Ref(node) => self.call(node, expr, path, deepth),
+            Node::Union(node) => {
+                let i = " ".repeat(deepth * self.tabsize);
+                let t = " ".repeat(self.tabsize);
+                let variants: Vec<String> = node
+                    .types
+                    .iter()
+                    .map(|variant| match variant {
+                        Node::Ref(r) => format!("{}({})", self.validate_fn(r), expr),
+                        _ => {
+                            let checks =
+                                self.check(variant, expr, &ValuePath::default(), deepth + 3);
+                            format!(
+                                "((): string[] => {{\n\
+                                {i}{t}{t}{t}const errors: string[] = [];\n\
+                                {checks}\
+                                {i}{t}{t}{t}return errors;\n\
+                                {i}{t}{t}}})()"
+                            )
+                        }
+                    })
+                    .collect();
+                let variants: String = variants
+                    .iter()
+                    .map(|v| format!("{i}{t}{t}{},\n", v))
+                    .collect();
+                let push = self.push(path, "must match a type of the union");
+                format!(
+                    "{i}if (\n\
+                    {i}{t}![\n\
+                    {variants}\
+                    {i}{t}].some((e) => e.length === 0)\n\
+                    {i}) {{\n\
+                    {i}{t}{push}\n\
+                    {i}}}\n"
+                )
+            }
+            Node::Param(_) | Node::Dyn(_) | Node::Split(_) | Node::Empty => String::new(),
+        }
+    }
+
+    /// Checks of a property of the object `record`, skipped if absent and optional.
+    fn property(&mut self, p: &Property, record: &str, path: &ValuePath, deepth: usize) -> String {
+        let i = " ".repeat(deepth * self.tabsize);
+        let t = " ".repeat(self.tabsize);
+        let key = property_key(p, self.options);
+        let path = path.key(&key);
+        let value = self.var("p");
+        let checks = self.check(&p.body, &value, &path, deepth + 1);
+        let declare = format!("{i}const {value} = {record}[{}];\n", js_string(&key));
+        let null = match p.nullable {
+            true => format!(" && {} !== null", value),
+            false => String::new(),
+        };
+        if p.optional {
+            if checks.is_empty() {
+                return String::new();
+            }
+            return format!("{declare}{i}if ({value} !== undefined{null}) {{\n{checks}{i}}}\n");
+        }
+        let push = self.push(&path, "is required");
+        let mut result = format!("{declare}{i}if ({value} === undefined) {{\n{i}{t}{push}\n{i}}}");
+        match (checks.is_empty(), p.nullable) {
+            (true, _) => {}
+            (false, true) => result += &format!(" else if ({value} !== null) {{\n{checks}{i}}}"),
+            (false, false) => result += &format!(" else {{\n{checks}{i}}}"),
+        }
+        result + "\n"
+    }
+
+    /// `validateX(expr)` of the target of `node`, its messages are prefixed by `path`.
+    fn call(&mut self, node: &RefNode, expr: &str, path: &ValuePath, deepth: usize) -> String {
+        let i = " ".repeat(deepth * self.tabsize);
+        let call = format!("{}({})", self.validate_fn(node), expr);
+        if path.is_empty() {
+            return format!("{i}errors.push(...{call});\n");
+        }
+        let prefix = path.render("", escape_template, template_var);
+        format!("{i}errors.push(...{call}.map((e) => `{prefix}${{e}}`));\n")
+    }
+
+    /// `validateX`, imported if it is in another file.
+    fn validate_fn(&mut self, node: &RefNode) -> String {
+        let name = format!("validate{}", capitalize(&node.name));
+        if !node.path.is_empty() {
+            self.imports.push(Import::Ref(RefImport {
+                name: name.clone(),
+                from: PathBuf::from(&node.path),
+            }));
+        }
+        name
+    }
+
+    /// `if (cond) { errors.push(message) }`, followed by `checks` in a else block if any.
+    fn fail_else(
+        &mut self,
+        cond: &str,
+        path: &ValuePath,
+        message: &str,
+        checks: String,
+        deepth: usize,
+    ) -> String {
+        let i = " ".repeat(deepth * self.tabsize);
+        let mut result = self.fail(cond, path, message, deepth);
+        if !checks.is_empty() {
+            result.pop();
+            result += &format!(" else {{\n{checks}{i}}}\n");
+        }
+        result
+    }
+
+    fn fail(&mut self, cond: &str, path: &ValuePath, message: &str, deepth: usize) -> String {
+        let i = " ".repeat(deepth * self.tabsize);
+        let t = " ".repeat(self.tabsize);
+        let push = self.push(path, message);
+        format!("{i}if ({cond}) {{\n{i}{t}{push}\n{i}}}\n")
+    }
+
+    fn push(&self, path: &ValuePath, text: &str) -> String {
+        format!("errors.push({});", message(path, text))
+    }
+
+    /// A new variable named `prefix` and a number.
+    fn var(&mut self, prefix: &str) -> String {
+        self.vars += 1;
+        format!("{}{}", prefix, self.vars)
+    }
+
+    /// Name of a module level `RegExp` of `pattern`, compiled once.
+    fn pattern(&mut self, pattern: &str, path: &ValuePath) -> String {
+        let hint = format!("pattern{}", path.hint(&self.id));
+        let mut name = hint.clone();
+        let mut n = 1;
+        while self
+            .patterns
+            .iter()
+            .any(|p| p.starts_with(&format!("\nconst {} ", name)))
+        {
+            n += 1;
+            name = format!("{}{}", hint, n);
+        }
+        self.patterns.push(format!(
+            "\nconst {} = new RegExp({});\n",
+            name,
+            js_string(pattern)
+        ));
+        name
+    }
+}
+
+/// `text` prefixed by `path`, a template literal if the path has variables.
+fn message(path: &ValuePath, text: &str) -> String {
+    if path.vars().is_empty() {
+        return js_string(&path.render(text, |s| s.to_string(), |_| String::new()));
+    }
+    format!("`{}`", path.render(text, escape_template, template_var))
+}
+
+fn escape_template(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('`', "\\`")
+        .replace("${", "\\${")
+}
+
+fn template_var(var: &str) -> String {
+    format!("${{{}}}", var)
+}
+
+/// A JavaScript string literal, JSON strings are valid in JavaScript.
+fn js_string(s: &str) -> String {
+    serde_json::to_string(s).unwrap()
 }
 
 // unit test here
 #[cfg(test)]
 mod test {
+    use super::generate_typescript;
     use crate::{
         generator::{lang::for_test, types::DEFAULT_TABSIZE},
-        types::lang::SupportedLang,
+        types::config::{Int64Type, Naming, TypescriptOptions},
     };
 
     #[test]
     fn nested_object() {
         let ast = for_test::nested_object_ast();
-        let result = ast.generator(&SupportedLang::TypeScript, &mut vec![], &DEFAULT_TABSIZE);
+        let result = generate_typescript(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.typescript,
+            &TypescriptOptions::default(),
+        )
+        .unwrap();
         insta::assert_yaml_snapshot!(result);
     }
 
     #[test]
     fn array() {
         let ast = for_test::array_ast();
-        let result = ast.generator(&SupportedLang::TypeScript, &mut vec![], &DEFAULT_TABSIZE);
+        let result = generate_typescript(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.typescript,
+            &TypescriptOptions::default(),
+        )
+        .unwrap();
         insta::assert_yaml_snapshot!(result);
     }
 
     #[test]
     fn nested_array() {
         let ast = for_test::nested_array_ast();
-        let result = ast.generator(&SupportedLang::TypeScript, &mut vec![], &DEFAULT_TABSIZE);
+        let result = generate_typescript(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.typescript,
+            &TypescriptOptions::default(),
+        )
+        .unwrap();
         insta::assert_yaml_snapshot!(result);
     }
 
     #[test]
     fn object_array() {
         let ast = for_test::object_array_ast();
-        let result = ast.generator(&SupportedLang::TypeScript, &mut vec![], &DEFAULT_TABSIZE);
+        let result = generate_typescript(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.typescript,
+            &TypescriptOptions::default(),
+        )
+        .unwrap();
         insta::assert_yaml_snapshot!(result);
     }
 
     #[test]
     fn string_enum() {
         let ast = for_test::string_enum_ast();
-        let result = ast.generator(&SupportedLang::TypeScript, &mut vec![], &DEFAULT_TABSIZE);
+        let result = generate_typescript(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.typescript,
+            &TypescriptOptions::default(),
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn optional_object() {
+        let ast = for_test::optional_object_ast();
+        let result = generate_typescript(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.typescript,
+            &TypescriptOptions::default(),
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn map() {
+        let ast = for_test::map_ast();
+        let result = generate_typescript(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.typescript,
+            &TypescriptOptions::default(),
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn tuple() {
+        let ast = for_test::tuple_ast();
+        let result = generate_typescript(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.typescript,
+            &TypescriptOptions::default(),
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn number_format() {
+        let ast = for_test::number_format_ast();
+        let result = generate_typescript(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.typescript,
+            &TypescriptOptions::default(),
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn number_format_bigint() {
+        let ast = for_test::number_format_ast();
+        let options = TypescriptOptions {
+            int64: Int64Type::Bigint,
+            ..Default::default()
+        };
+        let result =
+            generate_typescript(&ast, &mut vec![], DEFAULT_TABSIZE.typescript, &options).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn string_format() {
+        let ast = for_test::string_format_ast();
+        let result = generate_typescript(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.typescript,
+            &TypescriptOptions::default(),
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn discriminated_union() {
+        let ast = for_test::discriminated_union_ast();
+        let result = generate_typescript(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.typescript,
+            &TypescriptOptions::default(),
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn extends() {
+        let ast = for_test::extends_ast();
+        let result = generate_typescript(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.typescript,
+            &TypescriptOptions::default(),
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn described() {
+        let ast = for_test::described_ast();
+        let result = generate_typescript(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.typescript,
+            &TypescriptOptions::default(),
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn described_enum() {
+        let ast = for_test::described_enum_ast();
+        let result = generate_typescript(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.typescript,
+            &TypescriptOptions::default(),
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn deprecated() {
+        let ast = for_test::deprecated_ast();
+        let result = generate_typescript(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.typescript,
+            &TypescriptOptions::default(),
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn generic() {
+        let ast = for_test::generic_ast();
+        let result = generate_typescript(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.typescript,
+            &TypescriptOptions::default(),
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn generic_ref() {
+        let ast = for_test::generic_ref_ast();
+        let result = generate_typescript(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.typescript,
+            &TypescriptOptions::default(),
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn defaults() {
+        let ast = for_test::defaults_ast();
+        let result = generate_typescript(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.typescript,
+            &TypescriptOptions::default(),
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn inline_enum() {
+        let ast = for_test::inline_enum_ast();
+        let result = generate_typescript(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.typescript,
+            &TypescriptOptions::default(),
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn validate() {
+        let ast = for_test::constrained_ast();
+        let options = TypescriptOptions {
+            validate: true,
+            ..Default::default()
+        };
+        let result =
+            generate_typescript(&ast, &mut vec![], DEFAULT_TABSIZE.typescript, &options).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn validate_enum() {
+        let ast = for_test::string_enum_ast();
+        let options = TypescriptOptions {
+            validate: true,
+            ..Default::default()
+        };
+        let result =
+            generate_typescript(&ast, &mut vec![], DEFAULT_TABSIZE.typescript, &options).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn validate_inline_enum() {
+        let ast = for_test::inline_enum_ast();
+        let options = TypescriptOptions {
+            validate: true,
+            ..Default::default()
+        };
+        let result =
+            generate_typescript(&ast, &mut vec![], DEFAULT_TABSIZE.typescript, &options).unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn wire_name() {
+        let ast = for_test::wire_name_ast();
+        let result = generate_typescript(
+            &ast,
+            &mut vec![],
+            DEFAULT_TABSIZE.typescript,
+            &TypescriptOptions::default(),
+        )
+        .unwrap();
+        insta::assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn naming() {
+        let ast = for_test::wire_name_ast();
+        let options = TypescriptOptions {
+            naming: Some(Naming::Camel),
+            ..Default::default()
+        };
+        let result =
+            generate_typescript(&ast, &mut vec![], DEFAULT_TABSIZE.typescript, &options).unwrap();
         insta::assert_yaml_snapshot!(result);
     }
 }
@@ -1,20 +1,331 @@
-use crate::types::lang::SupportedLang;
+use std::borrow::Cow;
 
-use super::lang::{go, typescript};
-use super::types::{Imports, TabSize, AST};
+use super::types::{
+    ast_type_alias::{
+        ArrayNode, Keywords, MapNode, Node, ObjectNode, Property, RefNode, SplitNode, TupleNode,
+        TypeAliasAst, UnionNode,
+    },
+    AST,
+};
+use super::utils::{capitalize, to_pascal_case};
+use crate::diagnostic::{key_path, Code, Diagnostic};
+
+/// Error of a node a generator can not render at its place, like a split which is not
+/// the body of a definition.
+///
+/// `transformer` does not let these through, it is returned instead of a panic
+/// if one gets to a generator anyway.
+pub fn unexpected(what: &str) -> Diagnostic {
+    Diagnostic::error(Code::Syntax, format!("Unexpected {}.", what))
+}
 
 impl AST {
-    pub fn generator(
-        &self,
-        lang: &SupportedLang,
-        imports: &mut Imports,
-        tabsize: &TabSize,
-    ) -> String {
-        match lang {
-            SupportedLang::Go => go::generate_go(self, imports, tabsize.go),
-            SupportedLang::TypeScript => {
-                typescript::generate_typescript(self, imports, tabsize.typescript)
+    pub fn identifier(&self) -> &str {
+        match self {
+            AST::TypeAlias(ast) => &ast.identifier,
+            AST::Enum(ast) => &ast.identifier,
+        }
+    }
+
+    pub fn deprecated(&self) -> Option<&str> {
+        match self {
+            AST::TypeAlias(ast) => ast.deprecated.as_deref(),
+            AST::Enum(ast) => ast.deprecated.as_deref(),
+        }
+    }
+
+    /// Replace a split body with the node of given language,
+    /// `None` if the language is not in split.
+    ///
+    /// Other ASTs are returned as is.
+    pub fn resolve_split(&self, id: &str) -> Option<Cow<'_, AST>> {
+        match self {
+            AST::TypeAlias(TypeAliasAst {
+                identifier,
+                type_parameters,
+                description,
+                deprecated,
+                body: Node::Split(split),
+            }) => split.nodes.get(id).map(|node| {
+                Cow::Owned(AST::TypeAlias(TypeAliasAst {
+                    identifier: identifier.clone(),
+                    type_parameters: type_parameters.clone(),
+                    description: description.clone(),
+                    deprecated: deprecated.clone(),
+                    body: node.as_ref().clone(),
+                }))
+            }),
+            _ => Some(Cow::Borrowed(self)),
+        }
+    }
+
+    /// `Node::instantiate` of the body.
+    pub fn instantiate(&self) -> AST {
+        match self {
+            AST::TypeAlias(ast) => AST::TypeAlias(TypeAliasAst {
+                body: ast.body.instantiate(),
+                ..ast.clone()
+            }),
+            _ => self.clone(),
+        }
+    }
+}
+
+impl Node {
+    fn children(&self) -> Vec<&Node> {
+        match self {
+            Node::Array(n) => vec![n.items.as_ref()],
+            Node::Object(n) => n.values.iter().map(|p| &p.body).collect(),
+            Node::Map(n) => vec![n.keys.as_ref(), n.values.as_ref()],
+            Node::Tuple(n) => n.items.iter().collect(),
+            Node::Ref(n) => n.args.iter().collect(),
+            Node::Union(n) => n.types.iter().collect(),
+            Node::Split(n) => n.nodes.values().map(|n| n.as_ref()).collect(),
+            Node::StringLiteral(_)
+            | Node::NumberLiteral(_)
+            | Node::Keyword(_)
+            | Node::Param(_)
+            | Node::Dyn(_)
+            | Node::Enum(_)
+            | Node::Empty => vec![],
+        }
+    }
+
+    /// Call `f` with the node and every node in it, with their key paths in the spec file.
+    ///
+    /// `path` is the key path of the node itself.
+    pub fn walk(&self, path: &[String], f: &mut impl FnMut(&Node, &[String])) {
+        f(self, path);
+        let at = |keys: &[&str]| keys.iter().fold(path.to_vec(), |p, key| key_path(&p, key));
+        match self {
+            Node::Array(n) => n.items.walk(&at(&["items"]), f),
+            Node::Object(n) => {
+                for p in n.values.iter() {
+                    p.body.walk(&at(&["properties", &p.identifier]), f);
+                }
+            }
+            Node::Map(n) => {
+                n.keys.walk(&at(&["keys"]), f);
+                n.values.walk(&at(&["values"]), f);
+            }
+            Node::Tuple(n) => {
+                for (idx, t) in n.items.iter().enumerate() {
+                    t.walk(&at(&["items", &idx.to_string()]), f);
+                }
+            }
+            Node::Ref(n) => {
+                for (idx, a) in n.args.iter().enumerate() {
+                    a.walk(&at(&["args", &idx.to_string()]), f);
+                }
+            }
+            Node::Union(n) => {
+                for (idx, t) in n.types.iter().enumerate() {
+                    t.walk(&at(&["types", &idx.to_string()]), f);
+                }
+            }
+            Node::Split(n) => {
+                for (id, node) in n.nodes.iter() {
+                    node.walk(&at(&[id]), f);
+                }
+            }
+            Node::StringLiteral(_)
+            | Node::NumberLiteral(_)
+            | Node::Keyword(_)
+            | Node::Param(_)
+            | Node::Dyn(_)
+            | Node::Enum(_)
+            | Node::Empty => {}
+        }
+    }
+
+    /// Names of type parameters used in the node, in the order they first appear.
+    ///
+    /// Types hoisted out of a generic definition take these as their own parameters.
+    pub fn params(&self) -> Vec<String> {
+        params_of([self])
+    }
+
+    /// The same node with `f` applied to every child.
+    fn map_children(&self, f: &impl Fn(&Node) -> Node) -> Node {
+        match self {
+            Node::Array(n) => Node::Array(ArrayNode {
+                items: Box::new(f(&n.items)),
+                constraints: n.constraints.clone(),
+            }),
+            Node::Object(n) => Node::Object(ObjectNode {
+                extends: n.extends.clone(),
+                values: n
+                    .values
+                    .iter()
+                    .map(|p| Property {
+                        body: f(&p.body),
+                        ..p.clone()
+                    })
+                    .collect(),
+            }),
+            Node::Map(n) => Node::Map(MapNode {
+                keys: Box::new(f(&n.keys)),
+                values: Box::new(f(&n.values)),
+            }),
+            Node::Tuple(n) => Node::Tuple(TupleNode {
+                items: n.items.iter().map(f).collect(),
+            }),
+            Node::Ref(n) => Node::Ref(RefNode {
+                args: n.args.iter().map(f).collect(),
+                ..n.clone()
+            }),
+            Node::Union(n) => Node::Union(UnionNode {
+                types: n.types.iter().map(f).collect(),
+                discriminator: n.discriminator.clone(),
+                descriptions: n.descriptions.clone(),
+            }),
+            Node::Split(n) => Node::Split(SplitNode {
+                nodes: n
+                    .nodes
+                    .iter()
+                    .map(|(id, n)| (id.clone(), Box::new(f(n))))
+                    .collect(),
+            }),
+            Node::StringLiteral(_)
+            | Node::NumberLiteral(_)
+            | Node::Keyword(_)
+            | Node::Param(_)
+            | Node::Dyn(_)
+            | Node::Enum(_)
+            | Node::Empty => self.clone(),
+        }
+    }
+
+    /// Replace each parameter of `params` with the arg at the same position.
+    pub fn substitute(&self, params: &[String], args: &[Node]) -> Node {
+        match self {
+            Node::Param(p) => params
+                .iter()
+                .position(|name| name == &p.name)
+                .and_then(|idx| args.get(idx))
+                .cloned()
+                .unwrap_or_else(|| self.clone()),
+            _ => self.map_children(&|n| n.substitute(params, args)),
+        }
+    }
+
+    /// Replace each `$ref` with args by a `$ref` to its instance, which is in the same file.
+    pub fn instantiate(&self) -> Node {
+        self.instantiate_if(&|_| true)
+    }
+
+    /// Same as `instantiate`, but only the `$ref` for which `f` returns true.
+    pub fn instantiate_if(&self, f: &impl Fn(&RefNode) -> bool) -> Node {
+        match self {
+            Node::Ref(n) if !n.args.is_empty() && f(n) => Node::Ref(RefNode {
+                name: n.instance_name(),
+                path: String::new(),
+                args: vec![],
+            }),
+            _ => self.map_children(&|n| n.instantiate_if(f)),
+        }
+    }
+
+    /// Every `$ref` with args in the node, the ones in args included.
+    pub fn generic_refs(&self) -> Vec<&RefNode> {
+        let mut refs: Vec<&RefNode> = vec![];
+        if let Node::Ref(n) = self {
+            if !n.args.is_empty() {
+                refs.push(n);
+            }
+        }
+        for child in self.children() {
+            refs.extend(child.generic_refs());
+        }
+        refs
+    }
+
+    /// The same node with `f` applied to every `$ref`.
+    pub fn map_refs(&self, f: &impl Fn(&RefNode) -> RefNode) -> Node {
+        match self.map_children(&|n| n.map_refs(f)) {
+            Node::Ref(n) => Node::Ref(f(&n)),
+            node => node,
+        }
+    }
+
+    /// Part of the name of a generic instance this node is an arg of.
+    fn type_name(&self) -> String {
+        match self {
+            Node::Keyword(k) => match &k.format {
+                Some(_) => to_pascal_case(
+                    k.number_format()
+                        .map(|f| f.name())
+                        .or(k.string_format().map(|f| f.name()))
+                        .unwrap_or_default(),
+                ),
+                None => match k.value {
+                    Keywords::String => String::from("String"),
+                    Keywords::Number => String::from("Number"),
+                    Keywords::Boolean => String::from("Boolean"),
+                    Keywords::Any => String::from("Any"),
+                },
+            },
+            Node::StringLiteral(n) => to_pascal_case(&n.value),
+            Node::NumberLiteral(_) => String::from("Literal"),
+            Node::Array(n) => format!("{}List", n.items.type_name()),
+            Node::Map(n) => format!("{}Map", n.values.type_name()),
+            Node::Ref(n) => n.instance_name(),
+            Node::Param(n) => capitalize(&n.name),
+            Node::Dyn(n) => to_pascal_case(&n.name),
+            Node::Object(_) => String::from("Object"),
+            Node::Tuple(_) => String::from("Tuple"),
+            Node::Union(_) => String::from("Union"),
+            Node::Enum(_) => String::from("Enum"),
+            Node::Split(_) | Node::Empty => String::new(),
+        }
+    }
+}
+
+impl Property {
+    /// Key of the property in JSON, `wireName` or the identifier.
+    pub fn wire_name(&self) -> &str {
+        self.wire_name.as_deref().unwrap_or(&self.identifier)
+    }
+}
+
+impl ObjectNode {
+    /// Whether a property has a `default`, the ones of required nested objects included.
+    ///
+    /// Generated constructors fill in these, a optional or nullable object is left absent.
+    pub fn has_defaults(&self) -> bool {
+        self.values.iter().any(|p| match &p.body {
+            _ if p.default.is_some() => true,
+            Node::Object(n) => !p.optional && !p.nullable && n.has_defaults(),
+            _ => false,
+        })
+    }
+}
+
+/// `Node::params` of several nodes.
+pub fn params_of<'a>(nodes: impl IntoIterator<Item = &'a Node>) -> Vec<String> {
+    fn collect(node: &Node, params: &mut Vec<String>) {
+        if let Node::Param(p) = node {
+            if !params.contains(&p.name) {
+                params.push(p.name.clone());
             }
         }
+        for child in node.children() {
+            collect(child, params);
+        }
+    }
+
+    let mut params: Vec<String> = vec![];
+    for node in nodes {
+        collect(node, &mut params);
+    }
+    params
+}
+
+impl RefNode {
+    /// Name of the copy of a generic definition for languages without generics,
+    /// `PageUser` for `page` with a `$ref` to `user`.
+    pub fn instance_name(&self) -> String {
+        let args: String = self.args.iter().map(|a| a.type_name()).collect();
+        format!("{}{}", capitalize(&self.name), args)
     }
 }
@@ -2,4 +2,5 @@ pub mod lang;
 pub mod main;
 pub mod types;
 
-pub(self) mod utils;
+pub(crate) mod utils;
+pub(crate) mod validate;
@@ -1,17 +1,95 @@
 use std::path::PathBuf;
 
+use serde::{Deserialize, Serialize};
+
+/// Version of the serde representation of `AST`, `Node` and `Imports`.
+///
+/// Plugins receive it with every request,
+/// bump it when a change would break an existing plugin or add a field it should know.
+///
+/// 1 had objects, arrays, unions, refs and dyn only, 2 adds every field listed in `docs/config.md`
+/// since then, like `optional`, `format`, `extends`, generics, `constraints` and `default`.
+pub const AST_VERSION: u32 = 2;
+
 pub mod ast_type_alias {
-    #[derive(Debug, Clone)]
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct StringLiteralNode {
         pub value: String,
     }
 
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct NumberLiteralNode {
         pub value: String,
+        #[serde(default)]
+        pub format: Option<NumberFormat>,
     }
 
-    #[derive(Debug, Clone)]
+    /// `format` of a number, languages having sized numbers use the exact type.
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum NumberFormat {
+        Int32,
+        Int64,
+        Uint8,
+        Uint16,
+        Uint32,
+        Uint64,
+        Float32,
+        Float64,
+    }
+
+    impl NumberFormat {
+        pub const ALL: [NumberFormat; 8] = [
+            NumberFormat::Int32,
+            NumberFormat::Int64,
+            NumberFormat::Uint8,
+            NumberFormat::Uint16,
+            NumberFormat::Uint32,
+            NumberFormat::Uint64,
+            NumberFormat::Float32,
+            NumberFormat::Float64,
+        ];
+
+        /// Name in spec files, also the type name in go.
+        pub fn name(&self) -> &'static str {
+            match self {
+                NumberFormat::Int32 => "int32",
+                NumberFormat::Int64 => "int64",
+                NumberFormat::Uint8 => "uint8",
+                NumberFormat::Uint16 => "uint16",
+                NumberFormat::Uint32 => "uint32",
+                NumberFormat::Uint64 => "uint64",
+                NumberFormat::Float32 => "float32",
+                NumberFormat::Float64 => "float64",
+            }
+        }
+
+        pub fn is_integer(&self) -> bool {
+            !matches!(self, NumberFormat::Float32 | NumberFormat::Float64)
+        }
+
+        /// Whether `value` can be stored without losing anything.
+        pub fn accepts(&self, value: &serde_yaml::Number) -> bool {
+            let signed = |min: i64, max: i64| value.as_i64().is_some_and(|v| v >= min && v <= max);
+            let unsigned = |max: u64| value.as_u64().is_some_and(|v| v <= max);
+            match self {
+                NumberFormat::Int32 => signed(i32::MIN.into(), i32::MAX.into()),
+                NumberFormat::Int64 => value.as_i64().is_some(),
+                NumberFormat::Uint8 => unsigned(u8::MAX.into()),
+                NumberFormat::Uint16 => unsigned(u16::MAX.into()),
+                NumberFormat::Uint32 => unsigned(u32::MAX.into()),
+                NumberFormat::Uint64 => value.as_u64().is_some(),
+                NumberFormat::Float32 | NumberFormat::Float64 => true,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
     pub enum Keywords {
         String,
         Number,
@@ -19,60 +97,248 @@ pub mod ast_type_alias {
         Any,
     }
 
-    #[derive(Debug, Clone)]
+    /// `format` of a string, the wire value is always a string.
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub enum StringFormat {
+        /// RFC 3339 date and time.
+        DateTime,
+        /// RFC 3339 full-date.
+        Date,
+        /// ISO 8601 duration.
+        Duration,
+        Uuid,
+        /// Base64 encoded bytes.
+        Byte,
+    }
+
+    impl StringFormat {
+        pub const ALL: [StringFormat; 5] = [
+            StringFormat::DateTime,
+            StringFormat::Date,
+            StringFormat::Duration,
+            StringFormat::Uuid,
+            StringFormat::Byte,
+        ];
+
+        /// Name in spec files.
+        pub fn name(&self) -> &'static str {
+            match self {
+                StringFormat::DateTime => "date-time",
+                StringFormat::Date => "date",
+                StringFormat::Duration => "duration",
+                StringFormat::Uuid => "uuid",
+                StringFormat::Byte => "byte",
+            }
+        }
+    }
+
+    /// `format` of a keyword, depends on its type.
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    #[serde(untagged)]
+    pub enum Format {
+        Number(NumberFormat),
+        String(StringFormat),
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct KeywordNode {
         pub value: Keywords,
+        /// Only for `Keywords::Number` and `Keywords::String`.
+        #[serde(default)]
+        pub format: Option<Format>,
+        /// Length and pattern of strings, range of numbers.
+        #[serde(default, skip_serializing_if = "Constraints::is_empty")]
+        pub constraints: Constraints,
+    }
+
+    impl KeywordNode {
+        pub fn number_format(&self) -> Option<NumberFormat> {
+            match self.format {
+                Some(Format::Number(f)) => Some(f),
+                _ => None,
+            }
+        }
+
+        pub fn string_format(&self) -> Option<StringFormat> {
+            match self.format {
+                Some(Format::String(f)) => Some(f),
+                _ => None,
+            }
+        }
     }
 
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct ArrayNode {
         pub items: Box<Node>,
+        /// Number of items.
+        #[serde(default, skip_serializing_if = "Constraints::is_empty")]
+        pub constraints: Constraints,
+    }
+
+    /// Limits of a value, only checked by the generated validators.
+    ///
+    /// Which ones a node may have depends on its type, see `transformer`.
+    #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+    pub struct Constraints {
+        /// In characters (unicode code points).
+        #[serde(default)]
+        pub min_length: Option<u64>,
+        #[serde(default)]
+        pub max_length: Option<u64>,
+        /// A regular expression the string must contain a match of.
+        #[serde(default)]
+        pub pattern: Option<String>,
+        #[serde(default)]
+        pub minimum: Option<f64>,
+        #[serde(default)]
+        pub maximum: Option<f64>,
+        #[serde(default)]
+        pub min_items: Option<u64>,
+        #[serde(default)]
+        pub max_items: Option<u64>,
+    }
+
+    impl Constraints {
+        pub fn is_empty(&self) -> bool {
+            self == &Constraints::default()
+        }
     }
 
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, Default, Serialize, Deserialize)]
     pub struct Property {
         pub identifier: String,
+        /// Key of the property in JSON if not `identifier`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub wire_name: Option<String>,
         pub body: Node,
+        /// May be absent, `name?: T` in typescript.
+        #[serde(default)]
+        pub optional: bool,
+        /// May be null, `T | null` in typescript.
+        #[serde(default)]
+        pub nullable: bool,
+        #[serde(default)]
+        pub description: Option<String>,
+        /// `Some` if deprecated, with the reason or empty for `deprecated: true`.
+        #[serde(default)]
+        pub deprecated: Option<String>,
+        /// Value set by the generated constructors, it has the type of `body`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub default: Option<DefaultValue>,
+        /// `go.tags`, struct tags of the field by key replacing the generated ones.
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        pub go_tags: BTreeMap<String, String>,
     }
 
-    #[derive(Debug, Clone)]
+    /// `default` of a property, checked against the property type by `transformer`.
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+    pub enum DefaultValue {
+        String(String),
+        /// As written in spec, like `NumberLiteralNode`.
+        Number(String),
+        Boolean(bool),
+        Null,
+        /// Name of a member of the enum a `$ref` points to.
+        Member(String),
+        Array(Vec<DefaultValue>),
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct ObjectNode {
+        /// Objects whose properties are included, `extends` in spec.
+        #[serde(default)]
+        pub extends: Vec<RefNode>,
         pub values: Vec<Property>,
     }
 
-    #[derive(Debug, Clone)]
+    /// Keys are string, number or a `$ref` to a string enum.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct MapNode {
+        pub keys: Box<Node>,
+        pub values: Box<Node>,
+    }
+
+    /// Items by position, encoded as a JSON array.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct TupleNode {
+        pub items: Vec<Node>,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct RefNode {
         pub name: String,
         pub path: String,
+        /// Type arguments of a generic definition, in the order of its parameters.
+        #[serde(default)]
+        pub args: Vec<Node>,
     }
 
-    #[derive(Debug, Clone)]
+    /// A type parameter of the definition, `type: param` in spec.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct ParamNode {
+        pub name: String,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct DynNode {
         pub name: String,
         pub from: String,
     }
 
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct UnionNode {
         pub types: Vec<Node>,
+        /// Only if every type is a `$ref` to an object.
+        #[serde(default)]
+        pub discriminator: Option<Discriminator>,
+        /// `description` of each of `types`, in the same order.
+        #[serde(default)]
+        pub descriptions: Vec<Option<String>>,
     }
 
-    #[derive(Debug, Clone)]
+    /// Variants of a union are told apart by a string literal property.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct Discriminator {
+        pub property: String,
+        /// Value of `property` for each of `UnionNode::types`, in the same order.
+        pub values: Vec<String>,
+    }
+
+    /// Nodes for different languages, keyed by `LanguageBackend::id`.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct SplitNode {
-        pub typescript: Option<Box<Node>>,
-        pub go: Option<Box<Node>>,
+        pub nodes: BTreeMap<String, Box<Node>>,
+    }
+
+    /// `type: enum` of a property or array items, the members are checked like `declaration: enum`.
+    ///
+    /// A literal union in typescript, languages without one declare it as `<Parent><Field>`.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct EnumNode {
+        pub r#type: super::ast_enum::MembersType,
+        /// Only for `MembersType::Number`.
+        #[serde(default)]
+        pub format: Option<NumberFormat>,
+        pub members: super::ast_enum::Members,
     }
 
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(tag = "kind", rename_all = "snake_case")]
     pub enum Node {
         StringLiteral(StringLiteralNode),
         NumberLiteral(NumberLiteralNode),
         Keyword(KeywordNode),
         Array(ArrayNode),
         Object(ObjectNode),
+        Map(MapNode),
+        Tuple(TupleNode),
         Ref(RefNode),
+        Param(ParamNode),
         Dyn(DynNode),
         Union(UnionNode),
+        Enum(EnumNode),
         Split(SplitNode),
         Empty,
     }
@@ -83,56 +349,89 @@ pub mod ast_type_alias {
         }
     }
 
-    #[derive(Debug)]
+    impl Default for Node {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct TypeAliasAst {
         pub identifier: String,
+        /// Names of `typeParameters`, empty if not generic.
+        #[serde(default)]
+        pub type_parameters: Vec<String>,
+        /// `description` in spec, emitted as a doc comment.
+        #[serde(default)]
+        pub description: Option<String>,
+        /// `Some` if deprecated, with the reason or empty for `deprecated: true`.
+        #[serde(default)]
+        pub deprecated: Option<String>,
         pub body: Node,
     }
 }
 
 pub mod ast_enum {
-    #[derive(Debug)]
+    use serde::{Deserialize, Serialize};
+
+    pub use super::ast_type_alias::NumberFormat;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
     pub enum MembersType {
         String,
         Number,
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct Member {
         pub identifier: String,
         pub value: String,
+        #[serde(default)]
+        pub description: Option<String>,
+        #[serde(default)]
+        pub deprecated: Option<String>,
     }
 
     pub type Members = Vec<Member>;
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct EnumAst {
         pub identifier: String,
         pub r#type: MembersType,
+        /// Only for `MembersType::Number`.
+        #[serde(default)]
+        pub format: Option<NumberFormat>,
+        #[serde(default)]
+        pub description: Option<String>,
+        #[serde(default)]
+        pub deprecated: Option<String>,
         pub members: Members,
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "declaration", rename_all = "kebab-case")]
 #[allow(clippy::upper_case_acronyms)]
 pub enum AST {
     TypeAlias(ast_type_alias::TypeAliasAst),
     Enum(ast_enum::EnumAst),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RefImport {
     pub name: String,
     pub from: PathBuf,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DynImport {
     pub name: String,
     pub from: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
 pub enum Import {
     Ref(RefImport),
     Dyn(DynImport),
@@ -143,9 +442,13 @@ pub type Imports = Vec<Import>;
 pub struct TabSize {
     pub go: usize,
     pub typescript: usize,
+    pub rust: usize,
+    pub python: usize,
 }
 
 pub const DEFAULT_TABSIZE: TabSize = TabSize {
     go: 4,
     typescript: 2,
+    rust: 4,
+    python: 4,
 };
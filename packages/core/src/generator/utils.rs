@@ -1,5 +1,4 @@
-use super::types::ast_type_alias::Keywords;
-use crate::types::lang::SupportedLang;
+use crate::types::config::Naming;
 
 pub fn capitalize(s: &str) -> String {
     let mut chars = s.chars();
@@ -9,21 +8,53 @@ pub fn capitalize(s: &str) -> String {
     }
 }
 
-impl Keywords {
-    pub fn to_string(&self, lang: SupportedLang) -> String {
-        match lang {
-            SupportedLang::TypeScript => match &self {
-                Keywords::Any => String::from("any"),
-                Keywords::Boolean => String::from("boolean"),
-                Keywords::Number => String::from("number"),
-                Keywords::String => String::from("string"),
-            },
-            SupportedLang::Go => match &self {
-                Keywords::Any => String::from("interface{}"),
-                Keywords::Boolean => String::from("bool"),
-                Keywords::Number => String::from("int"),
-                Keywords::String => String::from("string"),
-            },
+/// `e.g.` userName -> user_name, HTTPCode -> http_code, dash-case -> dash_case
+pub fn to_snake_case(s: &str) -> String {
+    let chars: Vec<char> = s.chars().collect();
+    let mut result = String::new();
+
+    for (idx, c) in chars.iter().enumerate() {
+        if *c == '-' || *c == ' ' {
+            result.push('_');
+            continue;
+        }
+        if c.is_uppercase() && idx > 0 {
+            let prev = chars[idx - 1];
+            let next_is_lower = chars.get(idx + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next_is_lower)
+            {
+                result.push('_');
+            }
         }
+        result.extend(c.to_lowercase());
+    }
+
+    result
+}
+
+/// `e.g.` user_name -> UserName, dash-case -> DashCase, userName -> UserName
+pub fn to_pascal_case(s: &str) -> String {
+    s.split(['_', '-', ' ']).map(capitalize).collect()
+}
+
+/// `e.g.` user_name -> userName, UserName -> userName
+pub fn to_camel_case(s: &str) -> String {
+    let pascal = to_pascal_case(&to_snake_case(s));
+    let mut chars = pascal.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(f) => f.to_lowercase().collect::<String>() + chars.as_str(),
+    }
+}
+
+/// Name of a property in code by `naming`, `identifier` as is if `None`.
+pub fn rename(identifier: &str, naming: Option<Naming>) -> String {
+    match naming {
+        None => identifier.to_string(),
+        Some(Naming::Camel) => to_camel_case(identifier),
+        Some(Naming::Pascal) => to_pascal_case(&to_snake_case(identifier)),
+        Some(Naming::Snake) => to_snake_case(identifier),
     }
 }
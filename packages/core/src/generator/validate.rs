@@ -0,0 +1,169 @@
+//! Checks of the generated validators.
+//!
+//! Every language renders the same checks with the same messages,
+//! so a value rejected on one side is rejected with the same words on the other.
+
+use super::types::ast_type_alias::Constraints;
+use super::utils::capitalize;
+
+/// A check of `Constraints`, in the order they are rendered.
+pub enum Check<'a> {
+    MinLength(u64),
+    MaxLength(u64),
+    Pattern(&'a str),
+    Minimum(f64),
+    Maximum(f64),
+    MinItems(u64),
+    MaxItems(u64),
+}
+
+impl Check<'_> {
+    /// Error message of a value failing the check, without its path.
+    pub fn message(&self) -> String {
+        match self {
+            Check::MinLength(n) => format!("must be at least {} characters long", n),
+            Check::MaxLength(n) => format!("must be at most {} characters long", n),
+            Check::Pattern(p) => format!("must match {}", p),
+            Check::Minimum(n) => format!("must be at least {}", n),
+            Check::Maximum(n) => format!("must be at most {}", n),
+            Check::MinItems(n) => format!("must have at least {} items", n),
+            Check::MaxItems(n) => format!("must have at most {} items", n),
+        }
+    }
+
+    /// Whether `value` of spec passes the check, values of other types pass every check.
+    pub fn accepts(&self, value: &serde_yaml::Value) -> bool {
+        let chars = value.as_str().map(|s| s.chars().count() as u64);
+        let items = value.as_sequence().map(|s| s.len() as u64);
+        let number = value.as_f64();
+        match (self, chars, items, number) {
+            (Check::MinLength(n), Some(c), _, _) => c >= *n,
+            (Check::MaxLength(n), Some(c), _, _) => c <= *n,
+            (Check::Pattern(p), _, _, _) => match (value.as_str(), regex::Regex::new(p)) {
+                (Some(s), Ok(r)) => r.is_match(s),
+                _ => true,
+            },
+            (Check::Minimum(n), _, _, Some(v)) => v >= *n,
+            (Check::Maximum(n), _, _, Some(v)) => v <= *n,
+            (Check::MinItems(n), _, Some(i), _) => i >= *n,
+            (Check::MaxItems(n), _, Some(i), _) => i <= *n,
+            _ => true,
+        }
+    }
+}
+
+pub fn checks(c: &Constraints) -> Vec<Check<'_>> {
+    let mut result: Vec<Check> = vec![];
+    result.extend(c.min_length.map(Check::MinLength));
+    result.extend(c.max_length.map(Check::MaxLength));
+    result.extend(c.pattern.as_deref().map(Check::Pattern));
+    result.extend(c.minimum.map(Check::Minimum));
+    result.extend(c.maximum.map(Check::Maximum));
+    result.extend(c.min_items.map(Check::MinItems));
+    result.extend(c.max_items.map(Check::MaxItems));
+    result
+}
+
+/// `must be one of "a", "b"`, `values` are already quoted if strings.
+pub fn one_of_message(values: &[String]) -> String {
+    format!("must be one of {}", values.join(", "))
+}
+
+/// `must be "value"` of a literal.
+pub fn literal_message(value: &str) -> String {
+    format!("must be {}", value)
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Key(String),
+    /// A variable of the generated code holding a index or a map key.
+    Index(String),
+    Position(usize),
+}
+
+/// Where a checked value is in the validated one, `owner.tags[0]`.
+///
+/// Empty for the validated value itself, whose messages have no path.
+#[derive(Debug, Clone, Default)]
+pub struct ValuePath(Vec<Segment>);
+
+impl ValuePath {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn key(&self, key: &str) -> ValuePath {
+        self.with(Segment::Key(key.to_string()))
+    }
+
+    pub fn index(&self, var: &str) -> ValuePath {
+        self.with(Segment::Index(var.to_string()))
+    }
+
+    pub fn position(&self, idx: usize) -> ValuePath {
+        self.with(Segment::Position(idx))
+    }
+
+    fn with(&self, segment: Segment) -> ValuePath {
+        let mut segments = self.0.clone();
+        segments.push(segment);
+        ValuePath(segments)
+    }
+
+    /// `message` prefixed by the path, `static_text` escapes the text of the path
+    /// and `var` renders a variable.
+    pub fn render(
+        &self,
+        message: &str,
+        static_text: impl Fn(&str) -> String,
+        var: impl Fn(&str) -> String,
+    ) -> String {
+        let mut result = String::new();
+        for (idx, segment) in self.0.iter().enumerate() {
+            match segment {
+                Segment::Key(k) if idx == 0 => result += &static_text(k),
+                Segment::Key(k) => result += &static_text(&format!(".{}", k)),
+                Segment::Index(v) => {
+                    result += &static_text("[");
+                    result += &var(v);
+                    result += &static_text("]");
+                }
+                Segment::Position(p) => result += &static_text(&format!("[{}]", p)),
+            }
+        }
+        if !self.0.is_empty() {
+            result += &static_text(": ");
+        }
+        result + &static_text(message)
+    }
+
+    /// Variables in the path, in order.
+    pub fn vars(&self) -> Vec<&str> {
+        self.0
+            .iter()
+            .filter_map(|s| match s {
+                Segment::Index(v) => Some(v.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Number of indexes and map keys, loops in the generated code.
+    pub fn loops(&self) -> usize {
+        self.vars().len()
+    }
+
+    /// `UserTagsItem`, names a value declared for the path in a definition named `id`.
+    pub fn hint(&self, id: &str) -> String {
+        let mut result = capitalize(id);
+        for segment in self.0.iter() {
+            match segment {
+                Segment::Key(k) => result += &capitalize(k),
+                Segment::Index(_) => result += "Item",
+                Segment::Position(p) => result += &format!("Item{}", p),
+            }
+        }
+        result
+    }
+}
@@ -1,16 +1,21 @@
-mod compiler;
-mod generator;
+pub mod backend;
+mod checker;
+mod command;
+pub mod compiler;
+pub mod diagnostic;
+pub mod generator;
 mod logger;
 mod path;
 mod prepare;
+pub mod source;
 mod transformer;
-mod types;
+pub mod types;
 
-use log::{info, LevelFilter};
+use log::LevelFilter;
 use types::cli::{Cli, Commands};
 use wasm_bindgen::prelude::*;
 
-use crate::types::lang::SupportedLang;
+use crate::backend::Backends;
 
 #[cfg(feature = "wee_alloc")]
 #[global_allocator]
@@ -23,32 +28,35 @@ pub fn is_dev() -> bool {
     }
 }
 
+/// Exit code of the command, 1 if there is any error.
 #[wasm_bindgen]
-pub fn main() {
+pub fn main() -> u8 {
     console_error_panic_hook::set_once();
+    run(Backends::builtin)
+}
+
+/// Run selien cli with languages given by `backends`.
+///
+/// `backends` is called once for every run, `selien gen --watch` runs again
+/// when the config file changes. Use this to add in-house languages without forking selien:
+///
+/// ```text
+/// selien_wasm::run(|| {
+///     let mut backends = Backends::builtin();
+///     backends.register(Box::new(MyLang::default())).unwrap();
+///     backends
+/// });
+/// ```
+///
+/// Returns the exit code, 1 if there is any error.
+pub fn run<F: Fn() -> Backends>(backends: F) -> u8 {
     logger::main::init(LevelFilter::Debug).expect("Can not init logger.");
 
     let cli = Cli::get_parse();
 
     match cli.command {
-        Commands::Gen(args) => {
-            let (config, spec_list) = prepare::prepare(&args.config);
-
-            match &args.output {
-                Some(output) => {
-                    let lang = SupportedLang::from(output);
-                    lang.compiler(&config, &spec_list);
-                }
-                None => {
-                    SupportedLang::all().iter().for_each(|lang| {
-                        if lang.is_defined_in_config(&config) {
-                            lang.compiler(&config, &spec_list);
-                        }
-                    });
-                }
-            }
-
-            info!("Done.");
-        }
+        Commands::Gen(args) if args.watch => command::watch(&args, backends),
+        Commands::Gen(args) => command::finish(&command::gen(&args, backends())),
+        Commands::Check(args) => command::finish(&command::check(&args, backends())),
     }
 }
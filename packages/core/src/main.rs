@@ -1,15 +1,22 @@
+mod backend;
+mod checker;
+mod command;
 mod compiler;
+mod diagnostic;
 mod generator;
 mod logger;
 mod path;
 mod prepare;
+mod source;
 mod transformer;
 mod types;
 
-use log::{info, LevelFilter};
+use std::process::ExitCode;
+
+use log::LevelFilter;
 use types::cli::{Cli, Commands};
 
-use crate::types::lang::SupportedLang;
+use crate::backend::Backends;
 
 pub fn is_dev() -> bool {
     match std::env::var("SELIEN_ENV") {
@@ -18,30 +25,15 @@ pub fn is_dev() -> bool {
     }
 }
 
-fn main() {
+fn main() -> ExitCode {
     logger::main::init(LevelFilter::Debug).expect("Can not init logger.");
 
     let cli = Cli::get_parse();
 
-    match cli.command {
-        Commands::Gen(args) => {
-            let (config, spec_list) = prepare::prepare(&args.config);
-
-            match &args.output {
-                Some(output) => {
-                    let lang = SupportedLang::from(output);
-                    lang.compiler(&config, &spec_list);
-                }
-                None => {
-                    SupportedLang::all().iter().for_each(|lang| {
-                        if lang.is_defined_in_config(&config) {
-                            lang.compiler(&config, &spec_list);
-                        }
-                    });
-                }
-            }
-
-            info!("Done.");
-        }
-    }
+    let code = match cli.command {
+        Commands::Gen(args) if args.watch => command::watch(&args, Backends::builtin),
+        Commands::Gen(args) => command::finish(&command::gen(&args, Backends::builtin())),
+        Commands::Check(args) => command::finish(&command::check(&args, Backends::builtin())),
+    };
+    ExitCode::from(code)
 }
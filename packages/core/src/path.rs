@@ -1,7 +1,7 @@
 use std::env;
 use std::path::{Component, Path, PathBuf};
 
-use log::{error, warn};
+use log::warn;
 
 /// Process path from input with normalize.
 /// If input is relative path, then join it with current working directory.
@@ -66,17 +66,10 @@ pub fn to_relative(p: &Path) -> PathBuf {
 ///
 /// `e.g.` home/username/selien -> ./home/username/selien
 pub fn add_dot(p: &Path) -> PathBuf {
-    // selien $ref abs path should start with '/', even windows
+    // selien $ref abs path should start with '/', even windows.
+    // it is already usable, nothing to add.
     if p.starts_with("/") {
-        error!(
-            "$ref absolute path should start with '/': {}",
-            p.to_str().unwrap()
-        );
-        if crate::is_dev() {
-            panic!();
-        } else {
-            std::process::exit(1);
-        }
+        return p.to_owned();
     }
 
     if std::env::consts::OS == "windows" {
@@ -115,8 +108,8 @@ where
                 }
                 (None, _) => comps.push(Component::ParentDir),
                 (Some(a), Some(b)) if comps.is_empty() && a == b => (),
-                (Some(a), Some(b)) if b == Component::CurDir => comps.push(a),
-                (Some(_), Some(b)) if b == Component::ParentDir => return None,
+                (Some(a), Some(Component::CurDir)) => comps.push(a),
+                (Some(_), Some(Component::ParentDir)) => return None,
                 (Some(a), Some(_)) => {
                     comps.push(Component::ParentDir);
                     for _ in itb {
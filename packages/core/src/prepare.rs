@@ -1,335 +1,447 @@
-use crate::generator::types::DEFAULT_TABSIZE;
+use crate::backend::{plugin::Plugin, Backends};
+use crate::diagnostic::{key_path, Code, Diagnostic, Diagnostics};
 use crate::path::process_path;
-use crate::types::lang::SupportedLang;
+use crate::source::SourceMap;
 use crate::types::{config, spec};
-use log::error;
-use serde::de::DeserializeOwned;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-pub fn prepare(input: &String) -> (config::Config, spec::SpecList) {
-    let mut path = process_path(input);
+/// Read config file and every spec file.
+///
+/// `Err` if config file can not be used. Otherwise problems of spec files
+/// are returned together, definitions with problems are not in the list.
+pub fn prepare(
+    input: &String,
+    backends: Backends,
+) -> Result<(config::Config, spec::SpecList, Diagnostics), Diagnostics> {
+    let config: config::Config = parse_config_file(config_path(input), backends)?;
+
+    let mut diagnostics: Diagnostics = Vec::new();
+    let spec_list = parse_selien_file(config.spec.root.as_str(), &mut diagnostics);
+
+    Ok((config, spec_list, diagnostics))
+}
+
+/// `input` is the config file or the directory of `selien.config.yaml`.
+pub fn config_path(input: &String) -> PathBuf {
+    let path = process_path(input);
 
     if path.is_dir() {
-        path = path.join("selien.config.yaml");
+        return path.join("selien.config.yaml");
     }
+    path
+}
 
-    let config: config::Config = parse_config_file(&path);
+fn parse_config_file<P: AsRef<Path>>(
+    path: P,
+    mut backends: Backends,
+) -> Result<config::Config, Diagnostics> {
+    let file = path.as_ref().to_str().unwrap().to_string();
+    let (data, source) = parse_yaml_from_file(&path).map_err(|d| vec![d])?;
 
-    let spec_list = parse_selien_file(config.spec.root.as_str());
+    let locate = |d: Diagnostic| d.in_file(&file).locate(&source);
+    let error = |message: String, key_path: &[String]| {
+        locate(Diagnostic::error(Code::Config, message).at(key_path))
+    };
+    let spec_path = vec![String::from("spec")];
+    let output_path = vec![String::from("output")];
 
-    (config, spec_list)
-}
+    let root = match data.get("spec").and_then(|s| s.get("root")) {
+        Some(r) => match r.as_str() {
+            Some(r) => r.to_string(),
+            None => {
+                return Err(vec![error(
+                    String::from("spec.root must be a string."),
+                    &key_path(&spec_path, "root"),
+                )]);
+            }
+        },
+        None => {
+            return Err(vec![error(
+                String::from("Can not find spec.root in config file."),
+                &[],
+            )]);
+        }
+    };
+    let spec = config::Spec { root };
 
-fn parse_config_file<P: AsRef<Path>>(path: P) -> config::Config {
-    let data: serde_yaml::Value = parse_yaml_from_file(path);
-
-    let spec_data = data.get("spec").expect("Can not find spec in config file.");
-    let output_data = data
-        .get("output")
-        .expect("Can not find output in config file.");
-
-    let spec = config::Spec {
-        root: spec_data
-            .get("root")
-            .expect("Can not find spec.root in config file.")
-            .as_str()
-            .expect("Can not parse spec.root to string.")
-            .to_string(),
+    let output_map = match data.get("output") {
+        Some(serde_yaml::Value::Mapping(m)) => m,
+        Some(_) => {
+            return Err(vec![error(
+                String::from("output in config file must be a object (mapping)."),
+                &output_path,
+            )]);
+        }
+        None => {
+            return Err(vec![error(
+                String::from("Can not find output in config file."),
+                &[],
+            )]);
+        }
     };
 
-    let mut output_go: Option<config::OutputGo> = None;
-    let go_aliases = SupportedLang::get_alias(&SupportedLang::Go);
-    for ga in go_aliases {
-        if output_data.get(ga).is_some() {
-            if let Some(ga_value) = output_data.get(ga) {
-                let mod_name = ga_value
-                    .get("mod_name")
-                    .expect("selien golang config is missing in modName.")
-                    .as_str()
-                    .expect("Can not parse modName to string.")
-                    .to_string();
-
-                let output = ga_value
-                    .get("output")
-                    .expect("selien golang config is missing in output.")
-                    .as_str()
-                    .expect("Can not parse output to string.")
-                    .to_string();
-
-                let go_root = ga_value
-                    .get("root")
-                    .expect("selien golang config is missing in output.")
-                    .as_str()
-                    .expect("Can not parse output to string.")
-                    .to_string();
-
-                let tabsize = match ga_value.get("tabsize") {
-                    Some(r) => r.as_u64().expect("Can not parse tabsize to u64.") as usize,
-                    None => DEFAULT_TABSIZE.go,
-                };
-
-                output_go = Some(config::OutputGo {
-                    mod_name,
-                    root: go_root,
-                    output,
-                    tabsize,
-                });
+    let mut diagnostics: Diagnostics = Vec::new();
+    for (key, value) in output_map {
+        let alias = match key.as_str() {
+            Some(a) => a,
+            None => {
+                diagnostics.push(error(
+                    String::from("Language or alias in config output must be a string."),
+                    &output_path,
+                ));
+                continue;
+            }
+        };
+        let alias_path = key_path(&output_path, alias);
+
+        if value.get("plugin").is_some() {
+            if let Err(d) = backends.register(Box::new(Plugin::new(alias))) {
+                diagnostics.push(locate(d.at(&alias_path)));
+                continue;
             }
         }
-    }
-    let mut output_ts: Option<config::OutputTypescript> = None;
-    let ts_aliases = SupportedLang::get_alias(&SupportedLang::TypeScript);
-    for ta in ts_aliases {
-        if output_data.get(ta).is_some() {
-            if let Some(ta_value) = output_data.get(ta) {
-                let output = ta_value
-                    .get("output")
-                    .expect("selien typescript config is missing in output.")
-                    .as_str()
-                    .expect("Can not parse output to string.")
-                    .to_string();
-
-                let tabsize = match ta_value.get("tabsize") {
-                    Some(r) => r.as_u64().expect("Can not parse tabsize to u64.") as usize,
-                    None => DEFAULT_TABSIZE.typescript,
-                };
-
-                output_ts = Some(config::OutputTypescript { output, tabsize });
+        match backends.find_mut(alias) {
+            Some(backend) => {
+                if let Err(d) = backend.parse_config(value) {
+                    diagnostics.push(locate(d.under(&alias_path)));
+                }
             }
+            None => diagnostics.push(error(
+                format!("Unsupported language or alias in config output: {}.", alias),
+                &alias_path,
+            )),
         }
     }
 
-    config::Config {
-        spec,
-        output: config::Output {
-            go: output_go,
-            typescript: output_ts,
-        },
+    if !diagnostics.is_empty() {
+        return Err(diagnostics);
     }
+
+    Ok(config::Config {
+        spec,
+        output: backends,
+    })
 }
 
-fn parse_selien_file<D: AsRef<Path>>(dir: D) -> spec::SpecList {
+fn parse_selien_file<D: AsRef<Path>>(dir: D, diagnostics: &mut Diagnostics) -> spec::SpecList {
     let mut result: Vec<spec::Spec> = Vec::new();
 
-    visit_dirs(dir.as_ref(), &mut result);
+    visit_dirs(dir.as_ref(), &mut result, diagnostics);
     result
 }
 
-fn parse_yaml_from_file<T: DeserializeOwned, P: AsRef<Path>>(p: P) -> T {
-    let string = match fs::read_to_string(&p) {
-        Ok(result) => result,
+/// Parse yaml file, also record positions of the source.
+fn parse_yaml_from_file<P: AsRef<Path>>(
+    p: P,
+) -> Result<(serde_yaml::Value, SourceMap), Diagnostic> {
+    let file = p.as_ref().to_str().unwrap();
+
+    let string = fs::read_to_string(&p).map_err(|err| {
+        Diagnostic::error(
+            Code::Io,
+            format!("Can not read file from given path: {}", err),
+        )
+        .in_file(file)
+    })?;
+    parse_yaml(file, &string)
+}
+
+/// Parse the yaml `string` of `file`, also record positions of the source.
+fn parse_yaml(file: &str, string: &str) -> Result<(serde_yaml::Value, SourceMap), Diagnostic> {
+    SourceMap::parse(string).map_err(|err| {
+        let mut d = Diagnostic::error(Code::Yaml, format!("Can not parse yaml: {}", err.message))
+            .in_file(file);
+        d.position = Some(err.position);
+        d.frame = err.frame;
+        d
+    })
+}
+
+fn visit_dirs(path: &Path, result: &mut Vec<spec::Spec>, diagnostics: &mut Diagnostics) {
+    let file = path.to_str().unwrap();
+
+    if !path.is_dir() {
+        diagnostics
+            .push(Diagnostic::error(Code::Io, "Spec root is not a directory.").in_file(file));
+        return;
+    }
+
+    let root_dir = match fs::read_dir(path) {
+        Ok(r) => r,
         Err(err) => {
-            error!(
-                "Can not read file from given path: {:?}, {}",
-                p.as_ref(),
-                err
+            diagnostics.push(
+                Diagnostic::error(Code::Io, format!("Can not read directory: {}", err))
+                    .in_file(file),
             );
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1)
-            }
+            return;
         }
     };
 
-    match serde_yaml::from_str(&string) {
-        Ok(result) => result,
-        Err(err) => {
-            error!("Can not parse yaml to given type: {}", err);
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1)
+    for entry in root_dir {
+        let entry = match entry {
+            Ok(r) => r,
+            Err(err) => {
+                diagnostics.push(
+                    Diagnostic::error(Code::Io, format!("Can not read entry: {}", err))
+                        .in_file(file),
+                );
+                continue;
+            }
+        };
+        let _path = entry.path();
+        if _path.is_dir() {
+            visit_dirs(&_path, result, diagnostics);
+        } else {
+            if let Some(spec) = parse_spec_file(&_path, diagnostics) {
+                result.push(spec);
             }
         }
     }
 }
 
-fn visit_dirs(path: &Path, result: &mut Vec<spec::Spec>) {
-    if !path.is_dir() {
-        error!("{:?} is not a directory.", path);
-        if crate::is_dev() {
-            panic!();
-        } else {
-            std::process::exit(1);
+/// `None` if the file is not a selien file at all, every problem is pushed to `diagnostics`.
+fn parse_spec_file(path: &Path, diagnostics: &mut Diagnostics) -> Option<spec::Spec> {
+    let file = path.to_str().unwrap();
+    match fs::read_to_string(path) {
+        Ok(string) => parse_spec(file, &string, diagnostics),
+        Err(err) => {
+            diagnostics.push(
+                Diagnostic::error(
+                    Code::Io,
+                    format!("Can not read file from given path: {}", err),
+                )
+                .in_file(file),
+            );
+            None
         }
     }
+}
 
-    let root_dir = match fs::read_dir(path) {
+/// Spec of the yaml `string` read from `file`, see `parse_spec_file`.
+pub fn parse_spec(file: &str, string: &str, diagnostics: &mut Diagnostics) -> Option<spec::Spec> {
+    let (data, source) = match parse_yaml(file, string) {
         Ok(r) => r,
-        Err(err) => {
-            error!("Can not read directory: {}", err);
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1);
-            }
+        Err(d) => {
+            diagnostics.push(d);
+            return None;
         }
     };
 
-    for entry in root_dir {
-        let entry = entry.expect("Can not read entry.");
-        let _path = entry.path();
-        if _path.is_dir() {
-            visit_dirs(&_path, result);
-        } else {
-            let mut spec = spec::Spec::new();
-            let data: serde_yaml::Value = parse_yaml_from_file(&_path);
-
-            if let serde_yaml::Value::Mapping(map) = data {
-                for (key, value) in map {
-                    match key.as_str() {
-                        Some("selien-version") => {
-                            spec.version = value.as_str().unwrap().to_string();
-                            spec.path = _path.to_str().unwrap().to_string();
-                        }
-                        Some("definition") => {
-                            spec.def = parse_def(&value);
-                        }
-                        _ => {
-                            error!("Syntax error in selien file (selien-version or definition missing): {:?}", _path);
-                            if crate::is_dev() {
-                                panic!();
-                            } else {
-                                std::process::exit(1);
-                            }
-                        }
-                    }
-                }
-            } else {
-                error!("Selien file must be a object (mapping) type: {:?}", _path);
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
-                }
+    let map = match data {
+        serde_yaml::Value::Mapping(map) => map,
+        _ => {
+            diagnostics.push(
+                Diagnostic::error(Code::Syntax, "Selien file must be a object (mapping).")
+                    .in_file(file),
+            );
+            return None;
+        }
+    };
+
+    let mut spec = spec::Spec::new();
+    spec.path = file.to_string();
+
+    let mut problems: Diagnostics = Vec::new();
+    for (key, value) in &map {
+        match key.as_str() {
+            Some("selien-version") => match value.as_str() {
+                Some(v) => spec.version = v.to_string(),
+                None => problems.push(
+                    Diagnostic::error(Code::Syntax, "selien-version must be a string.")
+                        .at(&[String::from("selien-version")]),
+                ),
+            },
+            Some("definition") => {
+                spec.def = parse_def(value, &mut problems);
+            }
+            _ => {
+                let key = key.as_str().unwrap_or_default();
+                problems.push(
+                    Diagnostic::error(
+                        Code::Syntax,
+                        format!(
+                            "Unknown key {} in selien file, expected selien-version or definition.",
+                            key
+                        ),
+                    )
+                    .at(&[key.to_string()]),
+                )
             }
-            result.push(spec);
         }
     }
+
+    if !map.contains_key("selien-version") {
+        problems.push(Diagnostic::warning(Code::Syntax, "Missing selien-version."));
+    }
+
+    diagnostics.extend(
+        problems
+            .into_iter()
+            .map(|d| d.in_file(file).locate(&source)),
+    );
+    spec.source = source;
+    Some(spec)
 }
 
-fn parse_def(def: &serde_yaml::Value) -> Vec<spec::Def> {
+fn parse_def(def: &serde_yaml::Value, diagnostics: &mut Diagnostics) -> Vec<spec::Def> {
     let mut result: Vec<spec::Def> = Vec::new();
 
-    let mut parser = |m: &serde_yaml::Mapping| {
-        for (key, value) in m {
-            let declaration = match value.get("declaration") {
-                Some(r) => r,
-                None => {
-                    error!(
-                        "Syntax error: declaration not found in: {:?}",
-                        key.as_str().unwrap()
-                    );
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
+    match def {
+        serde_yaml::Value::Mapping(map) => {
+            for (key, value) in map {
+                match parse_declaration(key, value) {
+                    Ok(d) => result.push(d),
+                    Err(d) => diagnostics.push(d),
                 }
-            };
+            }
+        }
+        _ => diagnostics.push(
+            Diagnostic::error(Code::Syntax, "definition must be a object (mapping).")
+                .at(&[String::from("definition")]),
+        ),
+    }
+
+    result
+}
+
+fn parse_declaration(
+    key: &serde_yaml::Value,
+    value: &serde_yaml::Value,
+) -> Result<spec::Def, Diagnostic> {
+    let def_path = vec![String::from("definition")];
+    let identifier = match key.as_str() {
+        Some(v) => v.to_string(),
+        None => {
+            return Err(
+                Diagnostic::error(Code::Syntax, "Definition name must be a string.").at(&def_path),
+            );
+        }
+    };
+    let path = key_path(&def_path, &identifier);
+
+    let missing =
+        |key: &str| Diagnostic::error(Code::Syntax, format!("Missing {}.", key)).at(&path);
 
-            match declaration.as_str() {
-                Some("type-alias") => {
-                    let body = value.get("body").expect("Syntax error: body not found");
-
-                    result.push(spec::Def::TypeAlias(spec::TypeAliasDef {
-                        identifier: match key.as_str() {
-                            Some(v) => v.to_string(),
-                            None => {
-                                error!("type alias identifier err: {:?}", value);
-                                if crate::is_dev() {
-                                    panic!();
-                                } else {
-                                    std::process::exit(1);
-                                }
-                            }
-                        },
-                        body: match body {
-                            serde_yaml::Value::Mapping(v) => v.to_owned(),
-                            _ => {
-                                error!(
-                                    "type-alias body must be a object (mapping): {}",
-                                    key.as_str().unwrap()
-                                );
-                                if crate::is_dev() {
-                                    panic!();
-                                } else {
-                                    std::process::exit(1);
-                                }
-                            }
-                        },
-                    }))
+    let declaration = value
+        .get("declaration")
+        .ok_or_else(|| missing("declaration"))?;
+    let description = match value.get("description") {
+        Some(v) => Some(v.as_str().map(|v| v.to_string()).ok_or_else(|| {
+            Diagnostic::error(Code::Syntax, "description must be a string.")
+                .at(&key_path(&path, "description"))
+        })?),
+        None => None,
+    };
+    let deprecated = parse_deprecated(value.get("deprecated"))
+        .map_err(|d| d.at(&key_path(&path, "deprecated")))?;
+
+    match declaration.as_str() {
+        Some("type-alias") => {
+            let body = match value.get("body") {
+                Some(serde_yaml::Value::Mapping(v)) => v.to_owned(),
+                Some(_) => {
+                    return Err(Diagnostic::error(
+                        Code::Syntax,
+                        "type-alias body must be a object (mapping).",
+                    )
+                    .at(&key_path(&path, "body")));
                 }
-                Some("enum") => {
-                    let members = value
-                        .get("members")
-                        .expect("Syntax error: members not found");
-                    result.push(spec::Def::Enum(spec::EnumDef {
-                        identifier: match key.as_str() {
-                            Some(v) => v.to_string(),
-                            None => {
-                                error!("enum identifier err: {:?}", value);
-                                if crate::is_dev() {
-                                    panic!();
-                                } else {
-                                    std::process::exit(1);
-                                }
-                            }
-                        },
-                        r#type: match value.get("type") {
-                            Some(v) => v.as_str().unwrap().to_string(),
-                            None => {
-                                error!("enum type err: {:?}", value);
-                                if crate::is_dev() {
-                                    panic!();
-                                } else {
-                                    std::process::exit(1);
-                                }
-                            }
-                        },
-                        members: match members {
-                            serde_yaml::Value::Sequence(v) => v.to_owned(),
-                            _ => {
-                                error!(
-                                    "enum member must be a array (list): {}",
-                                    key.as_str().unwrap()
-                                );
-                                if crate::is_dev() {
-                                    panic!();
-                                } else {
-                                    std::process::exit(1);
-                                }
-                            }
-                        },
-                    }))
+                None => return Err(missing("body")),
+            };
+            let params_path = key_path(&path, "typeParameters");
+            let type_parameters = match value.get("typeParameters") {
+                Some(serde_yaml::Value::Sequence(v)) => v
+                    .iter()
+                    .map(|p| p.as_str().map(|p| p.to_string()))
+                    .collect::<Option<Vec<String>>>()
+                    .ok_or_else(|| {
+                        Diagnostic::error(Code::Syntax, "typeParameters must be a list of names.")
+                            .at(&params_path)
+                    })?,
+                Some(_) => {
+                    return Err(Diagnostic::error(
+                        Code::Syntax,
+                        "typeParameters must be a array (sequence).",
+                    )
+                    .at(&params_path));
                 }
-                _ => {
-                    error!("Syntax error in selien file: {:?}", value);
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
+                None => vec![],
+            };
+
+            Ok(spec::Def::TypeAlias(spec::TypeAliasDef {
+                identifier,
+                type_parameters,
+                description,
+                deprecated,
+                body,
+            }))
+        }
+        Some("enum") => {
+            let r#type = match value.get("type") {
+                Some(v) => match v.as_str() {
+                    Some(v) => v.to_string(),
+                    None => {
+                        return Err(Diagnostic::error(
+                            Code::InvalidEnum,
+                            "enum type must be a string.",
+                        )
+                        .at(&key_path(&path, "type")));
                     }
+                },
+                None => return Err(missing("type")),
+            };
+            let format = match value.get("format") {
+                Some(v) => match v.as_str() {
+                    Some(v) => Some(v.to_string()),
+                    None => {
+                        return Err(Diagnostic::error(
+                            Code::InvalidEnum,
+                            "enum format must be a string.",
+                        )
+                        .at(&key_path(&path, "format")));
+                    }
+                },
+                None => None,
+            };
+            let members = match value.get("members") {
+                Some(serde_yaml::Value::Sequence(v)) => v.to_owned(),
+                Some(_) => {
+                    return Err(Diagnostic::error(
+                        Code::InvalidEnum,
+                        "enum members must be a array (sequence).",
+                    )
+                    .at(&key_path(&path, "members")));
                 }
-            }
-        }
-    };
+                None => return Err(missing("members")),
+            };
 
-    match def {
-        serde_yaml::Value::Mapping(map) => {
-            parser(map);
-        }
-        _ => {
-            error!("Selien file must be a object (mapping): {:?}", def);
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1);
-            }
+            Ok(spec::Def::Enum(spec::EnumDef {
+                identifier,
+                r#type,
+                format,
+                description,
+                deprecated,
+                members,
+            }))
         }
+        _ => Err(Diagnostic::error(
+            Code::Syntax,
+            "Unknown declaration, expected type-alias or enum.",
+        )
+        .at(&key_path(&path, "declaration"))),
     }
+}
 
-    result
+/// `deprecated: true` is `Some("")`, `deprecated: <reason>` is `Some(reason)`.
+pub fn parse_deprecated(value: Option<&serde_yaml::Value>) -> Result<Option<String>, Diagnostic> {
+    match value {
+        None | Some(serde_yaml::Value::Bool(false)) => Ok(None),
+        Some(serde_yaml::Value::Bool(true)) => Ok(Some(String::new())),
+        Some(serde_yaml::Value::String(reason)) => Ok(Some(reason.clone())),
+        Some(_) => Err(Diagnostic::error(
+            Code::Syntax,
+            "deprecated must be a boolean or a reason (string).",
+        )),
+    }
 }
@@ -0,0 +1,363 @@
+use std::collections::HashMap;
+
+use serde_yaml::{Mapping, Number, Value};
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser, Tag};
+use yaml_rust2::scanner::{Marker, TScalarStyle};
+
+/// 1-based line and column in a yaml file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: u32,
+    pub col: u32,
+}
+
+/// Positions of every key path in a yaml file, used to point diagnostics to the source.
+///
+/// serde_yaml drops positions while parsing, so files are parsed by `SourceMap::parse`,
+/// which builds the `serde_yaml::Value` and records where each node starts at once.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    lines: Vec<String>,
+    positions: HashMap<Vec<String>, Position>,
+}
+
+/// Invalid yaml, with the code frame of `position`.
+#[derive(Debug)]
+pub struct YamlError {
+    pub message: String,
+    pub position: Position,
+    pub frame: Option<String>,
+}
+
+impl SourceMap {
+    /// Parse the first document of `source`, an empty one is `Value::Null`.
+    pub fn parse(source: &str) -> Result<(Value, SourceMap), YamlError> {
+        let mut loader = Loader::default();
+        let result = Parser::new_from_str(source).load(&mut loader, false);
+
+        let map = SourceMap {
+            lines: source.lines().map(|l| l.to_string()).collect(),
+            positions: loader.positions,
+        };
+        let error = match (result, loader.error) {
+            (Err(err), _) => Some((err.info().to_string(), position(*err.marker()))),
+            (Ok(()), error) => error,
+        };
+        match error {
+            Some((message, mut position)) => {
+                // errors at the end of file point after the last line.
+                if let Some(last) = map
+                    .lines
+                    .last()
+                    .filter(|_| position.line as usize > map.lines.len())
+                {
+                    position = Position {
+                        line: map.lines.len() as u32,
+                        col: last.chars().count() as u32 + 1,
+                    };
+                }
+                Err(YamlError {
+                    message,
+                    position,
+                    frame: map.frame(position),
+                })
+            }
+            None => Ok((loader.value.unwrap_or(Value::Null), map)),
+        }
+    }
+
+    /// Position of `key_path`, or of its nearest parent if the key is missing.
+    ///
+    /// Keys of mappings point to the key, items of sequences point to the item.
+    pub fn find(&self, key_path: &[String]) -> Option<Position> {
+        (0..=key_path.len())
+            .rev()
+            .find_map(|len| self.positions.get(&key_path[..len]).copied())
+    }
+
+    /// Lines around `position` with a caret under the column.
+    ///
+    /// ```text
+    ///    |
+    ///  7 |       username:
+    ///  8 |         type: strin
+    ///    |         ^
+    /// ```
+    pub fn frame(&self, position: Position) -> Option<String> {
+        let (line_no, col) = (position.line as usize, position.col as usize);
+        let line = self.lines.get(line_no.checked_sub(1)?)?;
+
+        let width = line_no.to_string().len();
+        let gutter = " ".repeat(width);
+        let mut result = format!("{} |\n", gutter);
+        if line_no > 1 {
+            let prev = &self.lines[line_no - 2];
+            if !prev.trim().is_empty() {
+                result += &format!("{:>w$} | {}\n", line_no - 1, prev, w = width);
+            }
+        }
+        result += &format!("{:>w$} | {}\n", line_no, line, w = width);
+        result += &format!("{} | {}^", gutter, " ".repeat(col.saturating_sub(1)));
+        Some(result)
+    }
+}
+
+fn position(mark: Marker) -> Position {
+    Position {
+        line: mark.line() as u32,
+        col: mark.col() as u32 + 1,
+    }
+}
+
+enum Frame {
+    Mapping {
+        path: Vec<String>,
+        mapping: Mapping,
+        /// Key waiting for its value, `None` when waiting for a key.
+        key: Option<Value>,
+        /// Text of the key being parsed, the last item of key paths under it.
+        key_text: String,
+        anchor: usize,
+    },
+    Sequence {
+        path: Vec<String>,
+        sequence: Vec<Value>,
+        anchor: usize,
+    },
+}
+
+/// Builds the value of a document from parser events.
+#[derive(Default)]
+struct Loader {
+    stack: Vec<Frame>,
+    /// Values of anchors, by the id of the parser.
+    anchors: HashMap<usize, Value>,
+    positions: HashMap<Vec<String>, Position>,
+    value: Option<Value>,
+    /// The first error, later events are ignored.
+    error: Option<(String, Position)>,
+}
+
+impl Loader {
+    /// Key path of the node starting at `position`, `text` is the scalar if it is one.
+    fn start(&mut self, position: Position, text: &str) -> Vec<String> {
+        match self.stack.last_mut() {
+            None => {
+                self.positions.insert(vec![], position);
+                vec![]
+            }
+            Some(Frame::Mapping {
+                path,
+                key,
+                key_text,
+                ..
+            }) => {
+                let mut p = path.clone();
+                if key.is_none() {
+                    // complex keys are not used by selien.
+                    *key_text = text.to_string();
+                    p.push(key_text.clone());
+                    self.positions.insert(p.clone(), position);
+                } else {
+                    p.push(key_text.clone());
+                }
+                p
+            }
+            Some(Frame::Sequence { path, sequence, .. }) => {
+                let mut p = path.clone();
+                p.push(sequence.len().to_string());
+                self.positions.insert(p.clone(), position);
+                p
+            }
+        }
+    }
+
+    /// Put the value of a node that ends at `position` into its parent.
+    fn finish(&mut self, value: Value, anchor: usize, position: Position) {
+        if anchor > 0 {
+            self.anchors.insert(anchor, value.clone());
+        }
+        match self.stack.last_mut() {
+            None => self.value = Some(value),
+            Some(Frame::Mapping {
+                mapping,
+                key: key @ None,
+                key_text,
+                ..
+            }) => {
+                if mapping.contains_key(&value) {
+                    let message = format!("duplicate entry with key {:?}", key_text);
+                    self.fail(message, position);
+                    return;
+                }
+                *key = Some(value);
+            }
+            Some(Frame::Mapping { mapping, key, .. }) => {
+                mapping.insert(key.take().unwrap(), value);
+            }
+            Some(Frame::Sequence { sequence, .. }) => sequence.push(value),
+        }
+    }
+
+    fn fail(&mut self, message: String, position: Position) {
+        if self.error.is_none() {
+            self.error = Some((message, position));
+        }
+    }
+}
+
+impl MarkedEventReceiver for Loader {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        if self.error.is_some() {
+            return;
+        }
+        let position = position(mark);
+        match ev {
+            Event::Scalar(text, style, anchor, tag) => {
+                self.start(position, &text);
+                let value = scalar(text, style, tag.as_ref());
+                self.finish(value, anchor, position);
+            }
+            Event::Alias(id) => {
+                self.start(position, "");
+                match self.anchors.get(&id).cloned() {
+                    Some(value) => self.finish(value, 0, position),
+                    None => self.fail(String::from("unknown anchor"), position),
+                }
+            }
+            Event::MappingStart(anchor, _) => {
+                let path = self.start(position, "");
+                self.stack.push(Frame::Mapping {
+                    path,
+                    mapping: Mapping::new(),
+                    key: None,
+                    key_text: String::new(),
+                    anchor,
+                });
+            }
+            Event::SequenceStart(anchor, _) => {
+                let path = self.start(position, "");
+                self.stack.push(Frame::Sequence {
+                    path,
+                    sequence: vec![],
+                    anchor,
+                });
+            }
+            Event::MappingEnd | Event::SequenceEnd => match self.stack.pop() {
+                Some(Frame::Mapping {
+                    mapping, anchor, ..
+                }) => self.finish(Value::Mapping(mapping), anchor, position),
+                Some(Frame::Sequence {
+                    sequence, anchor, ..
+                }) => self.finish(Value::Sequence(sequence), anchor, position),
+                None => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+/// Value of a scalar by the core schema of yaml 1.2, like serde_yaml.
+///
+/// Quoted and block scalars and `!!str` are always strings.
+fn scalar(text: String, style: TScalarStyle, tag: Option<&Tag>) -> Value {
+    if style != TScalarStyle::Plain || tag.is_some_and(|t| t.suffix == "str") {
+        return Value::String(text);
+    }
+    match text.as_str() {
+        "" | "~" | "null" | "Null" | "NULL" => return Value::Null,
+        "true" | "True" | "TRUE" => return Value::Bool(true),
+        "false" | "False" | "FALSE" => return Value::Bool(false),
+        ".inf" | ".Inf" | ".INF" | "+.inf" | "+.Inf" | "+.INF" => {
+            return Value::Number(Number::from(f64::INFINITY))
+        }
+        "-.inf" | "-.Inf" | "-.INF" => return Value::Number(Number::from(f64::NEG_INFINITY)),
+        ".nan" | ".NaN" | ".NAN" => return Value::Number(Number::from(f64::NAN)),
+        _ => {}
+    }
+
+    let unsigned = text.strip_prefix('+').unwrap_or(&text);
+    let radix = |prefix: &str, radix: u32| {
+        unsigned
+            .strip_prefix(prefix)
+            .and_then(|digits| u64::from_str_radix(digits, radix).ok())
+    };
+    if let Some(n) = radix("0x", 16).or_else(|| radix("0o", 8)) {
+        return Value::Number(Number::from(n));
+    }
+    if let Ok(n) = text.parse::<i64>() {
+        return Value::Number(Number::from(n));
+    }
+    if let Ok(n) = unsigned.parse::<u64>() {
+        return Value::Number(Number::from(n));
+    }
+    // `inf` and `nan` are strings in yaml.
+    if text.bytes().any(|b| b.is_ascii_digit()) {
+        if let Ok(n) = text.parse::<f64>() {
+            return Value::Number(Number::from(n));
+        }
+    }
+    Value::String(text)
+}
+
+// unit test here
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SOURCE: &str = "selien-version: 0.1.0
+definition:
+  user:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        username:
+          type: strin
+  union:
+    declaration: type-alias
+    body:
+      type: union
+      types:
+        - type: string
+        - type: union
+";
+
+    fn path(p: &str) -> Vec<String> {
+        p.split('.').map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn find_key_and_item() {
+        let (_, source) = SourceMap::parse(SOURCE).unwrap();
+
+        let key = source.find(&path("definition.user.body.properties.username.type"));
+        assert_eq!(key, Some(Position { line: 9, col: 11 }));
+
+        let item = source.find(&path("definition.union.body.types.1.type"));
+        assert_eq!(item, Some(Position { line: 16, col: 11 }));
+
+        let missing = source.find(&path("definition.user.body.items"));
+        assert_eq!(missing, Some(Position { line: 5, col: 5 }));
+    }
+
+    #[test]
+    fn code_frame() {
+        let (_, source) = SourceMap::parse(SOURCE).unwrap();
+        let position = source
+            .find(&path("definition.user.body.properties.username.type"))
+            .unwrap();
+        insta::assert_snapshot!(source.frame(position).unwrap());
+    }
+
+    #[test]
+    fn invalid_yaml() {
+        let err = SourceMap::parse("a: 1\na: 2\n").unwrap_err();
+        assert_eq!(err.message, "duplicate entry with key \"a\"");
+        assert_eq!(err.position, Position { line: 2, col: 1 });
+
+        let err = SourceMap::parse("a: [1\n").unwrap_err();
+        assert_eq!(err.position, Position { line: 1, col: 6 });
+        assert!(err.frame.is_some());
+    }
+}
@@ -0,0 +1,154 @@
+//! Copies of generic definitions, for languages without generics.
+//!
+//! A `$ref` with args like `page<user>` gets a copy of `page` named `PageUser`
+//! with `T` replaced by `user`, placed in the file using it. Go without generics
+//! needs every one of them, python only the ones of unions.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::checker::{module_path, target_path};
+use crate::diagnostic::{Code, Diagnostic};
+use crate::generator::{
+    types::{
+        ast_type_alias::{Node, RefNode, TypeAliasAst},
+        AST,
+    },
+    utils::capitalize,
+};
+use crate::path::normalize_path;
+use crate::transformer::main::transformer;
+use crate::types::{
+    config::Config,
+    spec::{Spec, SpecList},
+};
+
+/// Instances in one file at most, a generic definition using itself
+/// with bigger args expands forever.
+const MAX_INSTANCES: usize = 256;
+
+/// Instances of generic definitions `ast_list` of `spec` uses, the ones a configured
+/// language `instantiates` and the ones they use. Generic definitions in other files
+/// are looked up in `spec_list`.
+///
+/// A `$ref` which can not be resolved is left to the checker.
+pub fn instances(
+    config: &Config,
+    spec_list: &SpecList,
+    spec: &Spec,
+    ast_list: &[AST],
+) -> Result<Vec<AST>, Diagnostic> {
+    let root = normalize_path(Path::new(&config.spec.root));
+    let current = module_path(&spec.path);
+    let files: HashMap<PathBuf, &Spec> = spec_list
+        .iter()
+        .map(|s| (module_path(&s.path), s))
+        .collect();
+    let mut transformed: HashMap<PathBuf, Vec<AST>> = HashMap::new();
+    transformed.insert(current.clone(), ast_list.to_vec());
+
+    // refs in generic definitions have params in args, they are instantiated
+    // through the refs to the definitions.
+    let mut queue: Vec<(RefNode, bool)> = ast_list
+        .iter()
+        .filter_map(|ast| match ast {
+            AST::TypeAlias(t) if t.type_parameters.is_empty() => Some(t.body.generic_refs()),
+            _ => None,
+        })
+        .flatten()
+        .map(|r| (r.clone(), false))
+        .collect();
+    let mut names: HashSet<String> = HashSet::new();
+    let mut result: Vec<AST> = vec![];
+
+    let mut idx = 0;
+    // `used` if a instance refers to it, every ref in a instance is to another one.
+    while let Some((r, used)) = queue.get(idx).cloned() {
+        idx += 1;
+        let mut name = r.instance_name();
+        if names.contains(&name) {
+            continue;
+        }
+
+        let target = target_path(&r.path, spec, &root);
+        let Some(target_spec) = files.get(&target) else {
+            continue;
+        };
+        let asts = transformed
+            .entry(target.clone())
+            .or_insert_with(|| transformer(target_spec, &config.output).unwrap_or_default());
+        let Some(generic) = asts.iter().find_map(|ast| match ast {
+            AST::TypeAlias(t) if t.identifier == r.name => Some(t),
+            _ => None,
+        }) else {
+            continue;
+        };
+        if !used && !config.output.configured().any(|b| b.instantiates(generic)) {
+            continue;
+        }
+        names.insert(name.clone());
+
+        // `pageUser: page<user>` is the instance itself, other definitions
+        // can not take the name.
+        if let Some(def) = spec.def.iter().find(|d| capitalize(d.identifier()) == name) {
+            let is_alias = ast_list.iter().any(|ast| {
+                matches!(ast, AST::TypeAlias(t)
+                    if t.identifier == def.identifier()
+                        && matches!(&t.body, Node::Ref(b) if b.instance_name() == name))
+            });
+            if !is_alias {
+                return Err(Diagnostic::error(
+                    Code::DuplicateIdentifier,
+                    format!(
+                        "{} is also the name of a instance of {} in languages without generics.",
+                        def.identifier(),
+                        capitalize(&r.name)
+                    ),
+                )
+                .in_file(&spec.path)
+                .at(&[String::from("definition"), def.identifier().to_string()])
+                .locate(&spec.source));
+            }
+            name = def.identifier().to_string();
+        }
+        if result.len() >= MAX_INSTANCES {
+            return Err(Diagnostic::error(
+                Code::Syntax,
+                format!(
+                    "{} has more than {} instances, a generic definition may use itself with growing args.",
+                    capitalize(&r.name),
+                    MAX_INSTANCES
+                ),
+            )
+            .in_file(&spec.path));
+        }
+
+        // paths in the body are relative to the file of the generic definition.
+        let mut body = generic.body.clone();
+        if target != current {
+            body = body.map_refs(&|n| RefNode {
+                path: absolute(&n.path, target_spec, &root),
+                ..n.clone()
+            });
+        }
+        let body = body.substitute(&generic.type_parameters, &r.args);
+
+        queue.extend(body.generic_refs().into_iter().map(|r| (r.clone(), true)));
+        result.push(AST::TypeAlias(TypeAliasAst {
+            identifier: name,
+            type_parameters: vec![],
+            description: generic.description.clone(),
+            deprecated: generic.deprecated.clone(),
+            body: body.instantiate(),
+        }));
+    }
+
+    Ok(result)
+}
+
+/// `$ref` path from selien-root of `path` in `spec`.
+fn absolute(path: &str, spec: &Spec, root: &Path) -> String {
+    let target = target_path(path, spec, root);
+    let relative = target.strip_prefix(root).unwrap_or(&target);
+    format!("/{}", relative.to_str().unwrap().replace('\\', "/"))
+}
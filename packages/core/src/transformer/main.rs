@@ -1,1386 +1,1531 @@
-use log::error;
+use std::collections::BTreeMap;
 
+use crate::backend::{go, Backends};
+use crate::diagnostic::{key_path, Code, Diagnostic, Diagnostics};
 use crate::generator::types::{ast_enum, ast_type_alias, AST};
-use crate::types::lang::SupportedLang;
+use crate::generator::validate::checks;
+use crate::prepare;
 use crate::types::spec;
 
-pub fn transformer(spec: &spec::Spec) -> Vec<AST> {
+/// Transform every definition of a spec file.
+///
+/// Definitions with problems are skipped, problems of all definitions are returned.
+pub fn transformer(spec: &spec::Spec, backends: &Backends) -> Result<Vec<AST>, Diagnostics> {
     let mut result: Vec<AST> = vec![];
+    let mut diagnostics: Diagnostics = vec![];
 
-    transform(spec, &mut result);
+    transform(spec, backends, &mut result, &mut diagnostics);
 
-    result
+    if diagnostics.is_empty() {
+        Ok(result)
+    } else {
+        Err(diagnostics)
+    }
 }
 
-fn transform(spec: &spec::Spec, ast_list: &mut Vec<AST>) {
+fn transform(
+    spec: &spec::Spec,
+    backends: &Backends,
+    ast_list: &mut Vec<AST>,
+    diagnostics: &mut Diagnostics,
+) {
     for def in spec.def.iter() {
-        match def {
+        let result = match def {
             spec::Def::TypeAlias(def) => {
-                transfrom_type_alias(def, ast_list, None);
+                let path = def_path(&def.identifier, "body");
+                transfrom_type_alias(def, &path, backends, ast_list, None)
             }
-            spec::Def::Enum(def) => {
-                let members_type: ast_enum::MembersType = match def.r#type.as_str() {
-                    "string" => ast_enum::MembersType::String,
-                    "number" => ast_enum::MembersType::Number,
-                    _ => {
-                        error!(
-                            "Syntax error: invalid type in enum definition: {}.",
-                            def.identifier
-                        );
-                        if crate::is_dev() {
-                            panic!();
-                        } else {
-                            std::process::exit(1);
-                        }
-                    }
-                };
-
-                let mut members: Vec<ast_enum::Member> = vec![];
-
-                for member in def.members.iter() {
-                    if let serde_yaml::Value::Mapping(m) = member {
-                        for (key, value) in m {
-                            match &members_type {
-                                ast_enum::MembersType::String => {
-                                    if !value.is_string() {
-                                        error!(
-                                            "Syntax error: string type enum member must be a string: {}.",
-                                            def.identifier
-                                        );
-                                        if crate::is_dev() {
-                                            panic!();
-                                        } else {
-                                            std::process::exit(1);
-                                        }
-                                    }
-
-                                    members.push(ast_enum::Member {
-                                        identifier: key.as_str().unwrap().to_string(),
-                                        value: value.as_str().unwrap().to_string(),
-                                    });
-                                }
-                                ast_enum::MembersType::Number => {
-                                    if value.is_i64() {
-                                        members.push(ast_enum::Member {
-                                            identifier: key.as_str().unwrap().to_string(),
-                                            value: format!("{}", value.as_i64().expect("Not i64.")),
-                                        });
-                                    } else if value.is_f64() {
-                                        members.push(ast_enum::Member {
-                                            identifier: key.as_str().unwrap().to_string(),
-                                            value: format!("{}", value.as_f64().expect("Not f64.")),
-                                        });
-                                    } else {
-                                        error!(
-                                            "Syntax error: number type enum member must be a number: {}.",
-                                            def.identifier
-                                        );
-                                        if crate::is_dev() {
-                                            panic!();
-                                        } else {
-                                            std::process::exit(1);
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    } else {
-                        error!(
-                            "Syntax error: enum member must be a object (mapping): {}.",
-                            def.identifier
-                        );
-                        if crate::is_dev() {
-                            panic!();
-                        } else {
-                            std::process::exit(1);
-                        }
-                    }
-                }
-
-                let enum_ast = ast_enum::EnumAst {
-                    identifier: def.identifier.clone(),
-                    r#type: members_type,
-                    members,
-                };
+            spec::Def::Enum(def) => transform_enum(def).map(|ast| ast_list.push(ast)),
+        };
 
-                ast_list.push(AST::Enum(enum_ast));
-            }
+        if let Err(d) = result {
+            diagnostics.extend(
+                d.into_iter()
+                    .map(|d| d.in_file(&spec.path).locate(&spec.source)),
+            );
         }
     }
 }
 
-fn transfrom_type_alias(
-    def: &spec::TypeAliasDef,
-    ast_list: &mut Vec<AST>,
-    split: Option<&SupportedLang>,
-) {
-    let body_type = match def.body.get("type") {
-        Some(r) => r,
-        None => {
-            error!(
-                "Syntax error: missing type in definition: {}.",
-                def.identifier
-            );
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1);
-            }
+/// The value of `result`, its problems are added to `diagnostics`.
+///
+/// Siblings are visited even if one has problems, so all of them are reported at once.
+fn collect<T, E: Into<Diagnostics>>(
+    result: Result<T, E>,
+    diagnostics: &mut Diagnostics,
+) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(d) => {
+            diagnostics.extend(d.into());
+            None
         }
-    };
+    }
+}
 
-    match body_type.as_str() {
-        Some("string") => {
-            let mut body = ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
-                value: ast_type_alias::Keywords::String,
-            });
-            body = change_body_if_split(body, split);
+/// `value` if there is no problem.
+fn finish<T>(value: T, diagnostics: Diagnostics) -> Result<T, Diagnostics> {
+    if diagnostics.is_empty() {
+        Ok(value)
+    } else {
+        Err(diagnostics)
+    }
+}
 
-            ast_list.push(AST::TypeAlias(ast_type_alias::TypeAliasAst {
-                identifier: def.identifier.clone(),
-                body,
-            }))
-        }
-        Some("number") => {
-            let mut body = ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
-                value: ast_type_alias::Keywords::Number,
-            });
-            body = change_body_if_split(body, split);
+/// `definition.<identifier>.<key>`
+fn def_path(identifier: &str, key: &str) -> Vec<String> {
+    vec![
+        String::from("definition"),
+        identifier.to_string(),
+        key.to_string(),
+    ]
+}
 
-            ast_list.push(AST::TypeAlias(ast_type_alias::TypeAliasAst {
-                identifier: def.identifier.clone(),
-                body,
-            }))
+fn transform_enum(def: &spec::EnumDef) -> Result<AST, Diagnostics> {
+    let members_type: ast_enum::MembersType = match def.r#type.as_str() {
+        "string" => ast_enum::MembersType::String,
+        "number" => ast_enum::MembersType::Number,
+        _ => {
+            return Err(Diagnostic::error(
+                Code::InvalidEnum,
+                format!(
+                    "Invalid enum type {}, expected string or number.",
+                    def.r#type
+                ),
+            )
+            .at(&def_path(&def.identifier, "type"))
+            .into());
         }
-        Some("boolean") => {
-            let mut body = ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
-                value: ast_type_alias::Keywords::Boolean,
-            });
-            body = change_body_if_split(body, split);
+    };
+    let mut diagnostics: Diagnostics = vec![];
 
-            ast_list.push(AST::TypeAlias(ast_type_alias::TypeAliasAst {
-                identifier: def.identifier.clone(),
-                body,
-            }))
+    let format_path = def_path(&def.identifier, "format");
+    let format = match (&members_type, &def.format) {
+        (_, None) => None,
+        (ast_enum::MembersType::Number, Some(name)) => {
+            collect(number_format(name, &format_path), &mut diagnostics)
         }
-        Some("any") => {
-            let mut body = ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
-                value: ast_type_alias::Keywords::Any,
-            });
-            body = change_body_if_split(body, split);
-
-            ast_list.push(AST::TypeAlias(ast_type_alias::TypeAliasAst {
-                identifier: def.identifier.clone(),
-                body,
-            }))
+        (ast_enum::MembersType::String, Some(_)) => {
+            diagnostics.push(
+                Diagnostic::error(Code::InvalidEnum, "format is only for number type enums.")
+                    .at(&format_path),
+            );
+            None
         }
-        Some("object") => {
-            let mut obj_node = ast_type_alias::ObjectNode { values: vec![] };
-            let properties = match def.body.get("properties") {
-                Some(r) => r,
-                None => {
-                    error!(
-                        "Syntax error: missing properties in definition: {}.",
-                        def.identifier
-                    );
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
+    };
 
-            if let serde_yaml::Value::Mapping(map) = properties {
-                visit_properties(map, &mut obj_node);
-            } else {
-                error!(
-                    "Syntax error: invalid properties in definition: {}.",
-                    def.identifier
+    let members = collect(
+        visit_members(
+            &def.members,
+            &members_type,
+            format,
+            &def_path(&def.identifier, "members"),
+        ),
+        &mut diagnostics,
+    );
+
+    match members {
+        Some(members) if diagnostics.is_empty() => Ok(AST::Enum(ast_enum::EnumAst {
+            identifier: def.identifier.clone(),
+            r#type: members_type,
+            format,
+            description: def.description.clone(),
+            deprecated: def.deprecated.clone(),
+            members,
+        })),
+        _ => Err(diagnostics),
+    }
+}
+
+/// Members of a `declaration: enum` or a `type: enum`, at least one.
+fn visit_members(
+    seq: &serde_yaml::Sequence,
+    members_type: &ast_enum::MembersType,
+    format: Option<ast_type_alias::NumberFormat>,
+    members_path: &[String],
+) -> Result<ast_enum::Members, Diagnostics> {
+    let mut members: Vec<ast_enum::Member> = vec![];
+    let mut diagnostics: Diagnostics = vec![];
+
+    for (idx, member) in seq.iter().enumerate() {
+        let path = key_path(members_path, &idx.to_string());
+
+        let m = match member {
+            serde_yaml::Value::Mapping(m) => m,
+            _ => {
+                diagnostics.push(
+                    Diagnostic::error(Code::InvalidEnum, "Enum member must be a object (mapping).")
+                        .at(&path),
                 );
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
-                }
+                continue;
             }
+        };
 
-            let mut body = ast_type_alias::Node::Object(obj_node);
-            body = change_body_if_split(body, split);
-
-            ast_list.push(AST::TypeAlias(ast_type_alias::TypeAliasAst {
-                identifier: def.identifier.clone(),
-                body,
-            }));
+        // `description` and `deprecated` are of the only member in the same item.
+        let description = collect(get_description(m, &path), &mut diagnostics).flatten();
+        let deprecated = collect(get_deprecated(m, &path), &mut diagnostics).flatten();
+        let meta =
+            usize::from(m.contains_key("description")) + usize::from(m.contains_key("deprecated"));
+        if meta > 0 && m.len() - meta > 1 {
+            diagnostics.push(
+                Diagnostic::error(
+                    Code::InvalidEnum,
+                    "A enum member with description or deprecated must be the only one in its item.",
+                )
+                .at(&path),
+            );
+            continue;
         }
-        Some("array") => {
-            let mut array_node = ast_type_alias::ArrayNode {
-                items: Box::new(ast_type_alias::Node::new()),
-            };
 
-            let items = match def.body.get("items") {
-                Some(r) => r,
+        for (key, value) in m {
+            let identifier = match key.as_str() {
+                Some("description" | "deprecated") => continue,
+                Some(k) => k.to_string(),
                 None => {
-                    error!(
-                        "Syntax error: missing items in array definition: {}.",
-                        def.identifier
+                    diagnostics.push(
+                        Diagnostic::error(Code::InvalidEnum, "Enum member name must be a string.")
+                            .at(&path),
                     );
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
+                    continue;
                 }
             };
-
-            if let serde_yaml::Value::Mapping(map) = items {
-                visit_items(map, &def.identifier, &mut array_node);
-            } else {
-                error!(
-                    "Syntax error: invalid array items in definition: {}.",
-                    def.identifier
-                );
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
-                }
-            }
-
-            let mut body = ast_type_alias::Node::Array(array_node);
-            body = change_body_if_split(body, split);
-
-            ast_list.push(AST::TypeAlias(ast_type_alias::TypeAliasAst {
-                identifier: def.identifier.clone(),
-                body,
-            }));
-        }
-        Some("literal") => {
-            let value = match def.body.get("value") {
-                Some(v) => v,
-                None => {
-                    error!(
-                        "Syntax error: missing value in definition: {}.",
-                        def.identifier
-                    );
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
+            let member_path = key_path(&path, &identifier);
+
+            let value = match (members_type, value) {
+                (ast_enum::MembersType::String, serde_yaml::Value::String(v)) => v.clone(),
+                (ast_enum::MembersType::Number, serde_yaml::Value::Number(v)) => {
+                    if let Some(f) = format.filter(|f| !f.accepts(v)) {
+                        diagnostics.push(
+                            Diagnostic::error(
+                                Code::InvalidEnum,
+                                format!("Enum member {} is not a valid {}.", identifier, f.name()),
+                            )
+                            .at(&member_path),
+                        );
+                        continue;
                     }
+                    v.to_string()
                 }
-            };
-            match value {
-                serde_yaml::Value::String(v) => {
-                    let mut body =
-                        ast_type_alias::Node::StringLiteral(ast_type_alias::StringLiteralNode {
-                            value: v.clone(),
-                        });
-                    body = change_body_if_split(body, split);
-
-                    ast_list.push(AST::TypeAlias(ast_type_alias::TypeAliasAst {
-                        identifier: def.identifier.clone(),
-                        body,
-                    }));
-                }
-                serde_yaml::Value::Number(v) => {
-                    let mut body =
-                        ast_type_alias::Node::NumberLiteral(ast_type_alias::NumberLiteralNode {
-                            value: v.to_string(),
-                        });
-                    body = change_body_if_split(body, split);
-
-                    ast_list.push(AST::TypeAlias(ast_type_alias::TypeAliasAst {
-                        identifier: def.identifier.clone(),
-                        body,
-                    }));
-                }
-                _ => {
-                    error!(
-                        "Syntax error: invalid value in definition: {}.",
-                        def.identifier
+                (ast_enum::MembersType::String, _) => {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            Code::InvalidEnum,
+                            format!("String type enum member {} must be a string.", identifier),
+                        )
+                        .at(&member_path),
                     );
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
+                    continue;
                 }
-            }
-        }
-        Some("$ref") => {
-            let v_uri = match def.body.get("uri") {
-                Some(v) => v,
-                None => {
-                    error!(
-                        "Syntax error: missing uri in ref definition: {}.",
-                        def.identifier
+                (ast_enum::MembersType::Number, _) => {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            Code::InvalidEnum,
+                            format!("Number type enum member {} must be a number.", identifier),
+                        )
+                        .at(&member_path),
                     );
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
+                    continue;
                 }
             };
-            if let serde_yaml::Value::String(uri) = v_uri {
-                let name = uri.split('/').last().unwrap().to_string();
-                let path = uri.split('#').next().unwrap().to_string();
-
-                let mut body = ast_type_alias::Node::Ref(ast_type_alias::RefNode { name, path });
-                body = change_body_if_split(body, split);
-
-                ast_list.push(AST::TypeAlias(ast_type_alias::TypeAliasAst {
-                    identifier: def.identifier.clone(),
-                    body,
-                }));
-            } else {
-                error!(
-                    "Syntax error: invalid uri in definition: {}.",
-                    def.identifier
+
+            if let Some(same) = members.iter().find(|m| m.identifier == identifier) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        Code::InvalidEnum,
+                        format!("Enum member {} is declared twice.", same.identifier),
+                    )
+                    .at(&member_path),
                 );
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
-                }
+                continue;
             }
-        }
-        Some("$dyn") => {
-            let v_name = match def.body.get("name") {
-                Some(v) => v,
-                None => {
-                    error!(
-                        "Syntax error: missing name in dyn definition: {}.",
-                        def.identifier
-                    );
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
-            let v_from = match def.body.get("from") {
-                Some(v) => v,
-                None => {
-                    error!(
-                        "Syntax error: missing from in dyn definition: {}.",
-                        def.identifier
-                    );
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
-
-            let name = match v_name {
-                serde_yaml::Value::String(v) => v,
-                _ => {
-                    error!(
-                        "Syntax error: invalid name in dyn definition: {}.",
-                        def.identifier
-                    );
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
-            let from = match v_from {
-                serde_yaml::Value::String(v) => v,
-                _ => {
-                    error!(
-                        "Syntax error: invalid from in dyn definition: {}.",
-                        def.identifier
-                    );
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
-
-            let mut body = ast_type_alias::Node::Dyn(ast_type_alias::DynNode {
-                name: name.to_string(),
-                from: from.to_string(),
-            });
-            body = change_body_if_split(body, split);
-
-            ast_list.push(AST::TypeAlias(ast_type_alias::TypeAliasAst {
-                identifier: def.identifier.clone(),
-                body,
-            }));
-        }
-        Some("split") => {
-            if split.is_some() {
-                error!("Syntax error: split can only be used in root definition.");
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
-                }
+            if let Some(same) = members.iter().find(|m| m.value == value) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        Code::InvalidEnum,
+                        format!(
+                            "Enum member {} has the same value as {}.",
+                            identifier, same.identifier
+                        ),
+                    )
+                    .at(&member_path),
+                );
+                continue;
             }
 
-            let all_lang = SupportedLang::all();
-            for lang in all_lang {
-                let aliases = SupportedLang::get_alias(&lang);
-                for alias in aliases {
-                    if let Some(value) = def.body.get(alias) {
-                        if let serde_yaml::Value::Mapping(map) = value {
-                            let new_def = spec::TypeAliasDef {
-                                identifier: def.identifier.to_string(),
-                                body: map.clone(),
-                            };
-                            transfrom_type_alias(&new_def, ast_list, Some(&lang));
-                        } else {
-                            error!(
-                                "Syntax error: invalid split in definition: {}.",
-                                def.identifier
-                            );
-                            if crate::is_dev() {
-                                panic!();
-                            } else {
-                                std::process::exit(1);
-                            }
-                        }
-                    }
-                }
-            }
+            members.push(ast_enum::Member {
+                identifier,
+                value,
+                description: description.clone(),
+                deprecated: deprecated.clone(),
+            });
         }
-        Some("union") => {
-            let mut union_node = ast_type_alias::UnionNode { types: vec![] };
+    }
 
-            let v_types = match def.body.get("types") {
-                Some(v) => v,
-                None => {
-                    error!(
-                        "Syntax error: missing types in union definition: {}.",
-                        def.identifier
-                    );
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
+    if members.is_empty() && diagnostics.is_empty() {
+        diagnostics.push(
+            Diagnostic::error(Code::InvalidEnum, "Enum must have at least one member.")
+                .at(members_path),
+        );
+    }
 
-            if let serde_yaml::Value::Sequence(seq) = v_types {
-                for v in seq {
-                    if let serde_yaml::Value::Mapping(m) = v {
-                        visit_union_types(m, &def.identifier, &mut union_node);
-                    } else {
-                        error!(
-                            "Syntax error: invalid types in union definition: {}.",
-                            def.identifier
-                        );
-                        if crate::is_dev() {
-                            panic!();
-                        } else {
-                            std::process::exit(1);
-                        }
-                    }
-                }
-            } else {
-                error!(
-                    "Syntax error: invalid types in union definition: {}.",
-                    def.identifier
-                );
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
-                }
-            }
+    finish(members, diagnostics)
+}
 
-            let mut body = ast_type_alias::Node::Union(union_node);
-            body = change_body_if_split(body, split);
+/// `type: enum` with `members` like a `declaration: enum`,
+/// which are numbers if the first one is and strings otherwise.
+fn visit_enum(
+    m: &serde_yaml::Mapping,
+    path: &[String],
+) -> Result<ast_type_alias::EnumNode, Diagnostics> {
+    let seq = get_sequence(m, "members", path)?;
+    let is_number = seq
+        .first()
+        .and_then(|member| member.as_mapping())
+        .and_then(|member| {
+            member
+                .iter()
+                .find(|(k, _)| !matches!(k.as_str(), Some("description" | "deprecated")))
+        })
+        .is_some_and(|(_, v)| v.is_number());
+    let members_type = if is_number {
+        ast_enum::MembersType::Number
+    } else {
+        ast_enum::MembersType::String
+    };
+    let mut diagnostics: Diagnostics = vec![];
 
-            ast_list.push(AST::TypeAlias(ast_type_alias::TypeAliasAst {
-                identifier: def.identifier.clone(),
-                body,
-            }));
+    let format = match (&members_type, m.get("format")) {
+        (_, None) => None,
+        (ast_enum::MembersType::Number, Some(_)) => {
+            collect(get_number_format(m, path), &mut diagnostics).flatten()
         }
-        _ => {
-            error!(
-                "Syntax error: invalid type {} in definition: {}.",
-                body_type.as_str().expect("Syntax error in definition."),
-                def.identifier
+        (ast_enum::MembersType::String, Some(_)) => {
+            diagnostics.push(
+                Diagnostic::error(Code::InvalidEnum, "format is only for number type enums.")
+                    .at(&key_path(path, "format")),
             );
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1);
-            }
+            None
         }
+    };
+
+    let members = collect(
+        visit_members(seq, &members_type, format, &key_path(path, "members")),
+        &mut diagnostics,
+    );
+    match members {
+        Some(members) if diagnostics.is_empty() => Ok(ast_type_alias::EnumNode {
+            r#type: members_type,
+            format,
+            members,
+        }),
+        _ => Err(diagnostics),
     }
 }
 
-fn change_body_if_split(
-    mut body: ast_type_alias::Node,
-    split: Option<&SupportedLang>,
-) -> ast_type_alias::Node {
-    if let Some(lang) = split {
-        match lang {
-            SupportedLang::Go => {
-                body = ast_type_alias::Node::Split(ast_type_alias::SplitNode {
-                    go: Some(Box::new(body)),
-                    typescript: None,
-                });
-            }
-            SupportedLang::TypeScript => {
-                body = ast_type_alias::Node::Split(ast_type_alias::SplitNode {
-                    go: None,
-                    typescript: Some(Box::new(body)),
-                })
+fn transfrom_type_alias(
+    def: &spec::TypeAliasDef,
+    path: &[String],
+    backends: &Backends,
+    ast_list: &mut Vec<AST>,
+    split: Option<&str>,
+) -> Result<(), Diagnostics> {
+    let mut diagnostics: Diagnostics = vec![];
+    if split.is_none() {
+        collect(check_type_parameters(def), &mut diagnostics);
+    }
+
+    if get(&def.body, "type", path)?.as_str() != Some("split") {
+        let Some(mut body) = collect(visit_node(&def.body, path), &mut diagnostics) else {
+            return Err(diagnostics);
+        };
+        if split.is_none() {
+            let used = body.params();
+            if let Some(p) = def.type_parameters.iter().find(|p| !used.contains(p)) {
+                diagnostics.push(
+                    Diagnostic::error(Code::Syntax, format!("Type parameter {} is not used.", p))
+                        .at(&def_path(&def.identifier, "typeParameters")),
+                );
             }
         }
+        if matches!(body, ast_type_alias::Node::Param(_)) {
+            diagnostics.push(
+                Diagnostic::error(
+                    Code::Syntax,
+                    "A type parameter can not be the body of a definition.",
+                )
+                .at(&key_path(path, "type")),
+            );
+        }
+        if matches!(body, ast_type_alias::Node::Enum(_)) {
+            diagnostics.push(
+                Diagnostic::error(
+                    Code::Syntax,
+                    "A enum definition is declared by declaration: enum, type: enum is for properties and items.",
+                )
+                .at(&key_path(path, "type")),
+            );
+        }
+        if !diagnostics.is_empty() {
+            return Err(diagnostics);
+        }
+        body = change_body_if_split(body, split);
+
+        ast_list.push(AST::TypeAlias(ast_type_alias::TypeAliasAst {
+            identifier: def.identifier.clone(),
+            type_parameters: def.type_parameters.clone(),
+            description: def.description.clone(),
+            deprecated: def.deprecated.clone(),
+            body,
+        }));
+        return Ok(());
     }
-    body
-}
 
-fn visit_properties(p: &serde_yaml::Mapping, node: &mut ast_type_alias::ObjectNode) {
-    for (key, value) in p {
-        let mut property = ast_type_alias::Property {
-            identifier: key.as_str().unwrap().to_string(),
-            body: ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
-                value: ast_type_alias::Keywords::String,
-            }),
-        };
+    if split.is_some() {
+        return Err(
+            Diagnostic::error(Code::Syntax, "split can only be used in root definition.")
+                .at(&key_path(path, "type"))
+                .into(),
+        );
+    }
+
+    // every language of a split is transformed, a definition with problems is not pushed.
+    let mut split_list: Vec<AST> = vec![];
+    for (key, value) in def.body.iter() {
+        let alias = key.as_str().unwrap_or_default();
+        if alias == "type" {
+            continue;
+        }
+        let split_path = key_path(path, alias);
 
-        let property_type = match value.get("type") {
-            Some(r) => r,
+        let id = match backends.find(alias) {
+            Some(b) => b.id().to_string(),
             None => {
-                error!("Syntax error: missing type in definition: {:?}.", key);
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
-                }
+                diagnostics.push(
+                    Diagnostic::error(
+                        Code::Syntax,
+                        format!("Unsupported language {} in split.", alias),
+                    )
+                    .at(&split_path),
+                );
+                continue;
             }
         };
-        match property_type.as_str() {
-            Some("string") => {
-                property.body = ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
-                    value: ast_type_alias::Keywords::String,
-                });
-            }
-            Some("number") => {
-                property.body = ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
-                    value: ast_type_alias::Keywords::Number,
-                });
-            }
-            Some("boolean") => {
-                property.body = ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
-                    value: ast_type_alias::Keywords::Boolean,
-                });
-            }
-            Some("any") => {
-                property.body = ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
-                    value: ast_type_alias::Keywords::Any,
-                });
-            }
-            Some("object") => {
-                let mut obj_node = ast_type_alias::ObjectNode { values: vec![] };
-                let properties = match value.get("properties") {
-                    Some(r) => r,
-                    None => {
-                        error!(
-                            "Syntax error: missing properties in definition: {:?}",
-                            value
-                        );
-                        if crate::is_dev() {
-                            panic!();
-                        } else {
-                            std::process::exit(1);
-                        }
-                    }
-                };
-
-                if let serde_yaml::Value::Mapping(map) = properties {
-                    visit_properties(map, &mut obj_node);
-                } else {
-                    error!(
-                        "Syntax error: invalid properties in definition: {:?}",
-                        value
-                    );
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
 
-                property.body = ast_type_alias::Node::Object(obj_node);
-            }
-            Some("array") => {
-                let mut array_node = ast_type_alias::ArrayNode {
-                    items: Box::new(ast_type_alias::Node::new()),
-                };
-
-                let items = match value.get("items") {
-                    Some(r) => r,
-                    None => {
-                        error!(
-                            "Syntax error: missing items in object array properties: {:?}",
-                            value
-                        );
-                        if crate::is_dev() {
-                            panic!();
-                        } else {
-                            std::process::exit(1);
-                        }
-                    }
-                };
-
-                if let serde_yaml::Value::Mapping(map) = items {
-                    visit_items(map, key.as_str().unwrap(), &mut array_node);
-                } else {
-                    error!(
-                        "Syntax error: invalid items in object array properties: {:?}",
-                        value
-                    );
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
+        if let serde_yaml::Value::Mapping(map) = value {
+            let new_def = spec::TypeAliasDef {
+                identifier: def.identifier.to_string(),
+                type_parameters: def.type_parameters.clone(),
+                description: def.description.clone(),
+                deprecated: def.deprecated.clone(),
+                body: map.clone(),
+            };
+            collect(
+                transfrom_type_alias(&new_def, &split_path, backends, &mut split_list, Some(&id)),
+                &mut diagnostics,
+            );
+        } else {
+            diagnostics.push(
+                Diagnostic::error(Code::Syntax, "split must be a object (mapping).")
+                    .at(&split_path),
+            );
+        }
+    }
 
-                property.body = ast_type_alias::Node::Array(array_node);
-            }
-            Some("literal") => {
-                let literal_value = match value.get("value") {
-                    Some(v) => v,
-                    None => {
-                        error!(
-                            "Syntax error: missing value in object literal properties: {:?}.",
-                            value
-                        );
-                        if crate::is_dev() {
-                            panic!();
-                        } else {
-                            std::process::exit(1);
+    if diagnostics.is_empty() {
+        ast_list.append(&mut split_list);
+    }
+    finish((), diagnostics)
+}
+
+/// Names of `typeParameters` are unique, every `type: param` in body is one of them.
+fn check_type_parameters(def: &spec::TypeAliasDef) -> Result<(), Diagnostics> {
+    let mut diagnostics: Diagnostics = vec![];
+    let params_path = def_path(&def.identifier, "typeParameters");
+    for (idx, p) in def.type_parameters.iter().enumerate() {
+        if p.is_empty() || !p.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            diagnostics.push(
+                Diagnostic::error(Code::Syntax, format!("Invalid type parameter name {}.", p))
+                    .at(&key_path(&params_path, &idx.to_string())),
+            );
+        } else if def.type_parameters[..idx].contains(p) {
+            diagnostics.push(
+                Diagnostic::error(
+                    Code::Syntax,
+                    format!("Type parameter {} is declared twice.", p),
+                )
+                .at(&key_path(&params_path, &idx.to_string())),
+            );
+        }
+    }
+
+    // a `name` which is not a string is reported by `visit_node`.
+    fn visit(
+        value: &serde_yaml::Value,
+        path: &[String],
+        params: &[String],
+        diagnostics: &mut Diagnostics,
+    ) {
+        match value {
+            serde_yaml::Value::Mapping(m) => {
+                if m.get("type").and_then(|t| t.as_str()) == Some("param") {
+                    if let Some(name) = m.get("name").and_then(|n| n.as_str()) {
+                        if !params.iter().any(|p| p == name) {
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    Code::Syntax,
+                                    format!("Type parameter {} is not declared.", name),
+                                )
+                                .at(&key_path(path, "name")),
+                            );
                         }
                     }
-                };
-                match literal_value {
-                    serde_yaml::Value::String(v) => {
-                        property.body = ast_type_alias::Node::StringLiteral(
-                            ast_type_alias::StringLiteralNode { value: v.clone() },
-                        );
-                    }
-                    serde_yaml::Value::Number(v) => {
-                        property.body = ast_type_alias::Node::NumberLiteral(
-                            ast_type_alias::NumberLiteralNode {
-                                value: v.to_string(),
-                            },
-                        );
-                    }
-                    _ => {
-                        error!(
-                            "Syntax error: invalid value in object literal properties: {:?}.",
-                            value
-                        );
-                        if crate::is_dev() {
-                            panic!();
-                        } else {
-                            std::process::exit(1);
-                        }
+                }
+                for (key, v) in m {
+                    if let Some(key) = key.as_str() {
+                        visit(v, &key_path(path, key), params, diagnostics);
                     }
                 }
             }
-            Some("$ref") => {
-                let v_uri = match value.get("uri") {
-                    Some(v) => v,
-                    None => {
-                        error!(
-                            "Syntax error: missing uri in object ref proerties: {:?}.",
-                            value
-                        );
-                        if crate::is_dev() {
-                            panic!();
-                        } else {
-                            std::process::exit(1);
-                        }
-                    }
-                };
-                if let serde_yaml::Value::String(uri) = v_uri {
-                    let name = uri.split('/').last().unwrap().to_string();
-                    let path = uri.split('#').next().unwrap().to_string();
-
-                    property.body =
-                        ast_type_alias::Node::Ref(ast_type_alias::RefNode { name, path });
-                } else {
-                    error!(
-                        "Syntax error: invalid uri in object ref proerties: {:?}.",
-                        value
-                    );
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
+            serde_yaml::Value::Sequence(seq) => {
+                for (idx, v) in seq.iter().enumerate() {
+                    visit(v, &key_path(path, &idx.to_string()), params, diagnostics);
                 }
             }
-            Some("$dyn") => {
-                let v_name = match value.get("name") {
-                    Some(v) => v,
-                    None => {
-                        error!(
-                            "Syntax error: missing name in object dyn properties: {:?}.",
-                            value
-                        );
-                        if crate::is_dev() {
-                            panic!();
-                        } else {
-                            std::process::exit(1);
-                        }
-                    }
-                };
-                let v_from = match value.get("from") {
-                    Some(v) => v,
-                    None => {
-                        error!(
-                            "Syntax error: missing from in object dyn properties: {:?}.",
-                            value
-                        );
-                        if crate::is_dev() {
-                            panic!();
-                        } else {
-                            std::process::exit(1);
-                        }
-                    }
-                };
-
-                let name = match v_name {
-                    serde_yaml::Value::String(v) => v,
-                    _ => {
-                        error!(
-                            "Syntax error: invalid name in object dyn properties: {:?}.",
-                            value
-                        );
-                        if crate::is_dev() {
-                            panic!();
-                        } else {
-                            std::process::exit(1);
-                        }
-                    }
-                };
-                let from = match v_from {
-                    serde_yaml::Value::String(v) => v,
-                    _ => {
-                        error!(
-                            "Syntax error: invalid from in object dyn properties: {:?}.",
-                            value
-                        );
-                        if crate::is_dev() {
-                            panic!();
-                        } else {
-                            std::process::exit(1);
-                        }
-                    }
-                };
+            _ => {}
+        }
+    }
 
-                property.body = ast_type_alias::Node::Dyn(ast_type_alias::DynNode {
-                    name: name.to_string(),
-                    from: from.to_string(),
-                });
+    let body = serde_yaml::Value::Mapping(def.body.clone());
+    visit(
+        &body,
+        &def_path(&def.identifier, "body"),
+        &def.type_parameters,
+        &mut diagnostics,
+    );
+    finish((), diagnostics)
+}
+
+fn change_body_if_split(
+    mut body: ast_type_alias::Node,
+    split: Option<&str>,
+) -> ast_type_alias::Node {
+    if let Some(id) = split {
+        body = ast_type_alias::Node::Split(ast_type_alias::SplitNode {
+            nodes: BTreeMap::from([(id.to_string(), Box::new(body))]),
+        });
+    }
+    body
+}
+
+/// Transform a mapping which has `type` at `path`.
+///
+/// Definition body, properties, array items and union types are all the same.
+fn visit_node(
+    m: &serde_yaml::Mapping,
+    path: &[String],
+) -> Result<ast_type_alias::Node, Diagnostics> {
+    let node = match get(m, "type", path)?.as_str() {
+        Some("string") => ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+            value: ast_type_alias::Keywords::String,
+            format: get_string_format(m, path)?.map(ast_type_alias::Format::String),
+            constraints: Default::default(),
+        }),
+        Some("number") => ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+            value: ast_type_alias::Keywords::Number,
+            format: get_number_format(m, path)?.map(ast_type_alias::Format::Number),
+            constraints: Default::default(),
+        }),
+        Some("boolean") => ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+            value: ast_type_alias::Keywords::Boolean,
+            format: None,
+            constraints: Default::default(),
+        }),
+        Some("any") => ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
+            value: ast_type_alias::Keywords::Any,
+            format: None,
+            constraints: Default::default(),
+        }),
+        Some("object") => {
+            let mut diagnostics: Diagnostics = vec![];
+            let extends = match m.get("extends") {
+                Some(_) => collect(
+                    get_sequence(m, "extends", path)
+                        .map_err(Diagnostics::from)
+                        .and_then(|e| visit_extends(e, &key_path(path, "extends"))),
+                    &mut diagnostics,
+                ),
+                None => Some(vec![]),
+            };
+            // properties can be left out if everything is from bases.
+            let has_bases = extends.as_ref().is_none_or(|e| !e.is_empty());
+            let node = match (m.get("properties"), has_bases) {
+                (None, true) => Some(ast_type_alias::ObjectNode {
+                    extends: vec![],
+                    values: vec![],
+                }),
+                _ => collect(
+                    get_mapping(m, "properties", path)
+                        .map_err(Diagnostics::from)
+                        .and_then(|p| visit_properties(p, &key_path(path, "properties"))),
+                    &mut diagnostics,
+                ),
+            };
+            match (node, extends) {
+                (Some(mut node), Some(extends)) if diagnostics.is_empty() => {
+                    node.extends = extends;
+                    ast_type_alias::Node::Object(node)
+                }
+                _ => return Err(diagnostics),
             }
-            Some("union") => {
-                let mut union_node = ast_type_alias::UnionNode { types: vec![] };
-
-                let v_types = match value.get("types") {
-                    Some(v) => v,
-                    None => {
-                        error!(
-                            "Syntax error: missing types in object union properties: {:?}.",
-                            value
-                        );
-                        if crate::is_dev() {
-                            panic!();
-                        } else {
-                            std::process::exit(1);
+        }
+        Some("array") => {
+            let items = get_mapping(m, "items", path)?;
+            ast_type_alias::Node::Array(visit_items(items, &key_path(path, "items"))?)
+        }
+        Some("map") => {
+            let mut diagnostics: Diagnostics = vec![];
+            let values = collect(
+                get_mapping(m, "values", path)
+                    .map_err(Diagnostics::from)
+                    .and_then(|v| visit_node(v, &key_path(path, "values"))),
+                &mut diagnostics,
+            );
+            let keys_path = key_path(path, "keys");
+            let keys = collect(
+                get_mapping(m, "keys", path)
+                    .map_err(Diagnostics::from)
+                    .and_then(|k| visit_node(k, &keys_path)),
+                &mut diagnostics,
+            );
+            let (Some(keys), Some(values)) = (keys, values) else {
+                return Err(diagnostics);
+            };
+            let is_valid_key = match &keys {
+                ast_type_alias::Node::Keyword(node) => match node.value {
+                    // `[]byte` can not be a key in go.
+                    ast_type_alias::Keywords::String => match node.string_format() {
+                        Some(ast_type_alias::StringFormat::Byte) => {
+                            return Err(Diagnostic::error(
+                                Code::Syntax,
+                                "String map keys can not be a byte format.",
+                            )
+                            .at(&key_path(&keys_path, "format"))
+                            .into());
                         }
-                    }
-                };
-
-                if let serde_yaml::Value::Sequence(types) = v_types {
-                    for t in types {
-                        if let serde_yaml::Value::Mapping(map) = t {
-                            visit_union_types(map, key.as_str().unwrap(), &mut union_node);
-                        } else {
-                            error!(
-                                "Syntax error: invalid types in object union properties: {:?}",
-                                value
-                            );
-                            if crate::is_dev() {
-                                panic!();
-                            } else {
-                                std::process::exit(1);
-                            }
+                        _ => true,
+                    },
+                    ast_type_alias::Keywords::Number => match node.number_format() {
+                        Some(f) if !f.is_integer() => {
+                            return Err(Diagnostic::error(
+                                Code::Syntax,
+                                "Number map keys must be a integer format.",
+                            )
+                            .at(&key_path(&keys_path, "format"))
+                            .into());
                         }
-                    }
-                } else {
-                    error!(
-                        "Syntax error: invalid types in object union properties: {:?}",
-                        value
-                    );
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
+                        _ => true,
+                    },
+                    _ => false,
+                },
+                // target must be a string enum, see `checker`.
+                ast_type_alias::Node::Ref(_) => true,
+                _ => false,
+            };
+            if !is_valid_key {
+                return Err(Diagnostic::error(
+                    Code::Syntax,
+                    "Map keys must be string, number or a $ref to a string enum.",
+                )
+                .at(&key_path(&keys_path, "type"))
+                .into());
+            }
 
-                property.body = ast_type_alias::Node::Union(union_node);
+            ast_type_alias::Node::Map(ast_type_alias::MapNode {
+                keys: Box::new(keys),
+                values: Box::new(values),
+            })
+        }
+        Some("tuple") => {
+            let items = get_sequence(m, "items", path)?;
+            ast_type_alias::Node::Tuple(visit_tuple_items(items, &key_path(path, "items"))?)
+        }
+        Some("literal") => match get(m, "value", path)? {
+            serde_yaml::Value::String(v) => {
+                ast_type_alias::Node::StringLiteral(ast_type_alias::StringLiteralNode {
+                    value: v.clone(),
+                })
             }
-            Some("split") => {
-                error!(
-                    "Syntax error: split must used top-level. But you use it in a object {:?}.",
-                    key
-                );
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
+            serde_yaml::Value::Number(v) => {
+                let format = get_number_format(m, path)?;
+                if let Some(f) = format.filter(|f| !f.accepts(v)) {
+                    return Err(Diagnostic::error(
+                        Code::Syntax,
+                        format!("{} is not a valid {}.", v, f.name()),
+                    )
+                    .at(&key_path(path, "value"))
+                    .into());
                 }
+                ast_type_alias::Node::NumberLiteral(ast_type_alias::NumberLiteralNode {
+                    value: v.to_string(),
+                    format,
+                })
             }
             _ => {
-                error!(
-                    "Syntax error: invalid type in object definition: {:?}.",
-                    key
-                );
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
-                }
+                return Err(Diagnostic::error(
+                    Code::Syntax,
+                    "value of literal must be a string or number.",
+                )
+                .at(&key_path(path, "value"))
+                .into());
+            }
+        },
+        Some("$ref") => {
+            let mut node = ref_node(get_string(m, "uri", path)?, &key_path(path, "uri"))?;
+            if m.get("args").is_some() {
+                node.args = visit_args(get_sequence(m, "args", path)?, &key_path(path, "args"))?;
+            }
+            ast_type_alias::Node::Ref(node)
+        }
+        Some("param") => ast_type_alias::Node::Param(ast_type_alias::ParamNode {
+            name: get_string(m, "name", path)?.to_string(),
+        }),
+        Some("$dyn") => ast_type_alias::Node::Dyn(ast_type_alias::DynNode {
+            name: get_string(m, "name", path)?.to_string(),
+            from: get_string(m, "from", path)?.to_string(),
+        }),
+        Some("union") => {
+            let types = get_sequence(m, "types", path)?;
+            let types_path = key_path(path, "types");
+            let mut node = visit_union_types(types, &types_path)?;
+            if m.get("discriminator").is_some() {
+                let property = get_string(m, "discriminator", path)?;
+                node.discriminator =
+                    Some(visit_discriminator(property, types, &node, &types_path)?);
             }
+            ast_type_alias::Node::Union(node)
+        }
+        Some("enum") => ast_type_alias::Node::Enum(visit_enum(m, path)?),
+        Some("split") => {
+            return Err(Diagnostic::error(
+                Code::Syntax,
+                "split can only be used in root definition.",
+            )
+            .at(&key_path(path, "type"))
+            .into());
+        }
+        Some(t) => {
+            return Err(
+                Diagnostic::error(Code::UnknownType, format!("Unknown type {}.", t))
+                    .at(&key_path(path, "type"))
+                    .into(),
+            );
+        }
+        None => {
+            return Err(Diagnostic::error(Code::Syntax, "type must be a string.")
+                .at(&key_path(path, "type"))
+                .into());
         }
+    };
 
-        node.values.push(property);
-    }
+    Ok(with_constraints(node, m, path)?)
 }
 
-fn visit_items(i: &serde_yaml::Mapping, id: &str, node: &mut ast_type_alias::ArrayNode) {
-    let items_type = match i.get("type") {
-        Some(r) => r,
-        None => {
-            error!("Syntax error: missing type in array: {}.", id);
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1);
+/// Keys of `ast_type_alias::Constraints` in spec, with the values they are for.
+const CONSTRAINTS: [(&str, &str); 7] = [
+    ("minLength", "strings without a format"),
+    ("maxLength", "strings without a format"),
+    ("pattern", "strings without a format"),
+    ("minimum", "numbers"),
+    ("maximum", "numbers"),
+    ("minItems", "arrays"),
+    ("maxItems", "arrays"),
+];
+
+/// `node` with the constraints in `m`, a key not for the type of `node` is a error.
+///
+/// A string with a `format` is not a string in every language, it has no length or pattern.
+fn with_constraints(
+    mut node: ast_type_alias::Node,
+    m: &serde_yaml::Mapping,
+    path: &[String],
+) -> Result<ast_type_alias::Node, Diagnostic> {
+    let (constraints, kind) = match &mut node {
+        ast_type_alias::Node::Keyword(k) => match k.value {
+            ast_type_alias::Keywords::String if k.format.is_none() => {
+                (Some(&mut k.constraints), "strings without a format")
             }
-        }
+            ast_type_alias::Keywords::Number => (Some(&mut k.constraints), "numbers"),
+            _ => (None, ""),
+        },
+        ast_type_alias::Node::Array(a) => (Some(&mut a.constraints), "arrays"),
+        _ => (None, ""),
+    };
+    if let Some((key, for_kind)) = CONSTRAINTS
+        .into_iter()
+        .find(|(key, for_kind)| m.get(*key).is_some() && *for_kind != kind)
+    {
+        return Err(
+            Diagnostic::error(Code::Syntax, format!("{} is only for {}.", key, for_kind))
+                .at(&key_path(path, key)),
+        );
+    }
+    let Some(constraints) = constraints else {
+        return Ok(node);
     };
 
-    match items_type.as_str() {
-        Some("string") => {
-            node.items = Box::new(ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
-                value: ast_type_alias::Keywords::String,
-            }));
-        }
-        Some("number") => {
-            node.items = Box::new(ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
-                value: ast_type_alias::Keywords::Number,
-            }));
+    constraints.min_length = get_count(m, "minLength", path)?;
+    constraints.max_length = get_count(m, "maxLength", path)?;
+    constraints.minimum = get_bound(m, "minimum", path)?;
+    constraints.maximum = get_bound(m, "maximum", path)?;
+    constraints.min_items = get_count(m, "minItems", path)?;
+    constraints.max_items = get_count(m, "maxItems", path)?;
+    if m.get("pattern").is_some() {
+        let pattern = get_string(m, "pattern", path)?;
+        // the syntax of the regex crate is nearly the same as go `regexp`.
+        if regex::Regex::new(pattern).is_err() {
+            return Err(Diagnostic::error(
+                Code::Syntax,
+                format!("pattern {} is not a valid regular expression.", pattern),
+            )
+            .at(&key_path(path, "pattern")));
         }
-        Some("boolean") => {
-            node.items = Box::new(ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
-                value: ast_type_alias::Keywords::Boolean,
-            }));
-        }
-        Some("any") => {
-            node.items = Box::new(ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
-                value: ast_type_alias::Keywords::Any,
-            }));
+        constraints.pattern = Some(pattern.to_string());
+    }
+
+    let ranges = [
+        (
+            "minLength",
+            "maxLength",
+            constraints.min_length.map(|v| v as f64),
+            constraints.max_length.map(|v| v as f64),
+        ),
+        (
+            "minimum",
+            "maximum",
+            constraints.minimum,
+            constraints.maximum,
+        ),
+        (
+            "minItems",
+            "maxItems",
+            constraints.min_items.map(|v| v as f64),
+            constraints.max_items.map(|v| v as f64),
+        ),
+    ];
+    for (min_key, max_key, min, max) in ranges {
+        if min.zip(max).is_some_and(|(min, max)| min > max) {
+            return Err(Diagnostic::error(
+                Code::Syntax,
+                format!("{} is greater than {}.", min_key, max_key),
+            )
+            .at(&key_path(path, min_key)));
         }
-        Some("object") => {
-            let mut obj_node = ast_type_alias::ObjectNode { values: vec![] };
-            let properties = match i.get("properties") {
-                Some(r) => r,
-                None => {
-                    error!("Syntax error: missing properties in definition: {}.", id);
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
+    }
 
-            if let serde_yaml::Value::Mapping(map) = properties {
-                visit_properties(map, &mut obj_node);
-            } else {
-                error!("Syntax error: invalid properties in definition: {}.", id);
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
-                }
-            }
+    Ok(node)
+}
 
-            node.items = Box::new(ast_type_alias::Node::Object(obj_node));
-        }
-        Some("array") => {
-            let mut arr_node = ast_type_alias::ArrayNode {
-                items: Box::new(ast_type_alias::Node::new()),
-            };
+fn visit_properties(
+    p: &serde_yaml::Mapping,
+    path: &[String],
+) -> Result<ast_type_alias::ObjectNode, Diagnostics> {
+    let mut node = ast_type_alias::ObjectNode {
+        extends: vec![],
+        values: vec![],
+    };
+    let mut diagnostics: Diagnostics = vec![];
 
-            let items = match i.get("items") {
-                Some(r) => r,
-                None => {
-                    error!("Syntax error: missing nested items in definition: {}.", id);
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
+    for (key, value) in p {
+        let identifier = match key.as_str() {
+            Some(k) => k.to_string(),
+            None => {
+                diagnostics.push(
+                    Diagnostic::error(Code::Syntax, "Property name must be a string.").at(path),
+                );
+                continue;
+            }
+        };
+        let property_path = key_path(path, &identifier);
 
-            if let serde_yaml::Value::Mapping(map) = items {
-                visit_items(map, id, &mut arr_node);
-            } else {
-                error!("Syntax error: invalid nested items in definition: {}.", id);
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
-                }
+        let m = match value {
+            serde_yaml::Value::Mapping(m) => m,
+            _ => {
+                diagnostics.push(
+                    Diagnostic::error(
+                        Code::Syntax,
+                        format!("Property {} must be a object (mapping).", identifier),
+                    )
+                    .at(&property_path),
+                );
+                continue;
             }
+        };
 
-            node.items = Box::new(ast_type_alias::Node::Array(arr_node));
+        if let Some(property) = collect(
+            visit_property(identifier, m, &node.values, &property_path),
+            &mut diagnostics,
+        ) {
+            node.values.push(property);
         }
-        Some("lietral") => {
-            let literal_value = match i.get("value") {
-                Some(v) => v,
-                None => {
-                    error!("Syntax error: missing value in array literal item: {}.", id);
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
-            match literal_value {
-                serde_yaml::Value::String(v) => {
-                    node.items = Box::new(ast_type_alias::Node::StringLiteral(
-                        ast_type_alias::StringLiteralNode { value: v.clone() },
-                    ));
-                }
-                serde_yaml::Value::Number(v) => {
-                    node.items = Box::new(ast_type_alias::Node::NumberLiteral(
-                        ast_type_alias::NumberLiteralNode {
-                            value: v.to_string(),
-                        },
-                    ));
-                }
-                _ => {
-                    error!("Syntax error: invalid value in array literal item: {}.", id);
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
+    }
+
+    finish(node, diagnostics)
+}
+
+/// A property named `identifier`, whose wire name is not one of `siblings`.
+fn visit_property(
+    identifier: String,
+    m: &serde_yaml::Mapping,
+    siblings: &[ast_type_alias::Property],
+    path: &[String],
+) -> Result<ast_type_alias::Property, Diagnostics> {
+    let mut diagnostics: Diagnostics = vec![];
+
+    let body = collect(visit_node(m, path), &mut diagnostics);
+    let nullable = collect(get_flag(m, "nullable", path), &mut diagnostics).unwrap_or_default();
+    let default = match (m.get("default"), &body) {
+        (Some(v), Some(body)) => collect(
+            default_value(v, body, nullable, &key_path(path, "default")),
+            &mut diagnostics,
+        ),
+        _ => None,
+    };
+    let wire_name = match m.get("wireName") {
+        Some(_) => match collect(get_string(m, "wireName", path), &mut diagnostics) {
+            Some("") => {
+                diagnostics.push(
+                    Diagnostic::error(Code::Syntax, "wireName can not be empty.")
+                        .at(&key_path(path, "wireName")),
+                );
+                None
             }
+            name => name.map(|name| name.to_string()),
+        },
+        None => None,
+    };
+    let key = wire_name.as_deref().unwrap_or(&identifier);
+    if let Some(same) = siblings.iter().find(|p| p.wire_name() == key) {
+        diagnostics.push(
+            Diagnostic::error(
+                Code::Syntax,
+                format!(
+                    "Properties {} and {} have the same wire name {}.",
+                    same.identifier, identifier, key
+                ),
+            )
+            .at(path),
+        );
+    }
+    let optional = collect(get_flag(m, "optional", path), &mut diagnostics);
+    let description = collect(get_description(m, path), &mut diagnostics);
+    let deprecated = collect(get_deprecated(m, path), &mut diagnostics);
+    let go_tags = collect(get_go_tags(m, path), &mut diagnostics);
+
+    match (body, optional, description, deprecated, go_tags) {
+        (Some(body), Some(optional), Some(description), Some(deprecated), Some(go_tags))
+            if diagnostics.is_empty() =>
+        {
+            Ok(ast_type_alias::Property {
+                wire_name,
+                optional,
+                description,
+                deprecated,
+                body,
+                nullable,
+                default,
+                go_tags,
+                identifier,
+            })
         }
-        Some("$ref") => {
-            let v_uri = match i.get("uri") {
-                Some(v) => v,
-                None => {
-                    error!("Syntax error: missing uri in array ref items: {:?}.", i);
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
-            if let serde_yaml::Value::String(uri) = v_uri {
-                let name = uri.split('/').last().unwrap().to_string();
-                let path = uri.split('#').next().unwrap().to_string();
-
-                node.items = Box::new(ast_type_alias::Node::Ref(ast_type_alias::RefNode {
-                    name,
-                    path,
-                }));
-            } else {
-                error!("Syntax error: invalid uri in array ref items: {:?}.", i);
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
-                }
+        _ => Err(diagnostics),
+    }
+}
+
+/// `go.tags` of a property, values of struct tags by key.
+fn get_go_tags(
+    m: &serde_yaml::Mapping,
+    path: &[String],
+) -> Result<BTreeMap<String, String>, Diagnostic> {
+    let mut tags: BTreeMap<String, String> = BTreeMap::new();
+    if m.get("go").is_none() {
+        return Ok(tags);
+    }
+    let go_path = key_path(path, "go");
+    let go = get_mapping(m, "go", path)?;
+    if go.get("tags").is_none() {
+        return Ok(tags);
+    }
+    let tags_path = key_path(&go_path, "tags");
+    for (key, value) in get_mapping(go, "tags", &go_path)? {
+        let key = match key.as_str() {
+            Some(k) if go::is_tag_key(k) => k,
+            _ => {
+                return Err(Diagnostic::error(
+                    Code::Syntax,
+                    format!("Invalid tag key: {:?}.", key.as_str().unwrap_or_default()),
+                )
+                .at(&tags_path));
             }
+        };
+        let path = key_path(&tags_path, key);
+        if key == "json" {
+            return Err(Diagnostic::error(
+                Code::Syntax,
+                "The json tag is always emitted, set the key in JSON by wireName.",
+            )
+            .at(&path));
         }
-        Some("$dyn") => {
-            let v_name = match i.get("name") {
-                Some(v) => v,
-                None => {
-                    error!("Syntax error: missing name in array dyn items: {:?}.", i);
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
-            let v_from = match i.get("from") {
-                Some(v) => v,
-                None => {
-                    error!("Syntax error: missing from in array dyn items: {:?}.", i);
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
+        match value.as_str() {
+            Some(v) if !v.contains(['"', '`']) => tags.insert(key.to_string(), v.to_string()),
+            Some(_) => {
+                return Err(Diagnostic::error(
+                    Code::Syntax,
+                    format!("Tag {} can not contain quotes or backticks.", key),
+                )
+                .at(&path));
+            }
+            None => {
+                return Err(Diagnostic::error(
+                    Code::Syntax,
+                    format!("Tag {} must be a string.", key),
+                )
+                .at(&path));
+            }
+        };
+    }
+    Ok(tags)
+}
 
-            let name = match v_name {
-                serde_yaml::Value::String(v) => v,
-                _ => {
-                    error!("Syntax error: invalid name in array dyn items: {:?}.", i);
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
-            let from = match v_from {
-                serde_yaml::Value::String(v) => v,
-                _ => {
-                    error!("Syntax error: invalid from in array dyn items: {:?}.", i);
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
+/// `default` of a property of type `node`.
+///
+/// A `$ref` takes the name of a enum member, which is checked by `checker`
+/// as the enum may be in another file.
+fn default_value(
+    v: &serde_yaml::Value,
+    node: &ast_type_alias::Node,
+    nullable: bool,
+    path: &[String],
+) -> Result<ast_type_alias::DefaultValue, Diagnostics> {
+    use ast_type_alias::{DefaultValue, Keywords, Node, StringFormat};
+    use serde_yaml::Value;
+
+    let invalid = |message: String| Err(Diagnostic::error(Code::Syntax, message).at(path).into());
+    if v.is_null() && !nullable {
+        return invalid(String::from("default can only be null if nullable."));
+    }
+    if v.is_null() {
+        return Ok(DefaultValue::Null);
+    }
 
-            node.items = Box::new(ast_type_alias::Node::Dyn(ast_type_alias::DynNode {
-                name: name.to_string(),
-                from: from.to_string(),
-            }));
+    let value = match (node, v) {
+        (Node::StringLiteral(n), Value::String(s)) if s == &n.value => {
+            DefaultValue::String(s.clone())
         }
-        Some("union") => {
-            let mut union_node = ast_type_alias::UnionNode { types: vec![] };
-
-            let v_types = match i.get("types") {
-                Some(r) => r,
-                None => {
-                    error!("Syntax error: missing types in union definition: {}.", id);
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
+        (Node::StringLiteral(n), _) => return invalid(format!("default must be \"{}\".", n.value)),
+        (Node::NumberLiteral(n), Value::Number(x)) if x.as_f64() == n.value.parse::<f64>().ok() => {
+            DefaultValue::Number(n.value.clone())
+        }
+        (Node::NumberLiteral(n), _) => return invalid(format!("default must be {}.", n.value)),
+        (Node::Keyword(k), _) => match (&k.value, v) {
+            (Keywords::String, Value::String(s)) => match k.string_format() {
+                // not a string in every language.
+                Some(f @ (StringFormat::DateTime | StringFormat::Byte)) => {
+                    return invalid(format!("A {} string can not have a default.", f.name()));
                 }
-            };
-
-            if let serde_yaml::Value::Sequence(seq) = v_types {
-                for t in seq {
-                    if let serde_yaml::Value::Mapping(map) = t {
-                        visit_union_types(map, id, &mut union_node);
-                    } else {
-                        error!("Syntax error: invalid types in union definition: {}.", id);
-                        if crate::is_dev() {
-                            panic!();
-                        } else {
-                            std::process::exit(1);
-                        }
-                    }
+                _ => DefaultValue::String(s.clone()),
+            },
+            (Keywords::Number, Value::Number(x)) => match k.number_format() {
+                Some(f) if !f.accepts(x) => {
+                    return invalid(format!("default {} is not a valid {}.", x, f.name()));
                 }
-            } else {
-                error!("Syntax error: invalid types in union definition: {}.", id);
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
+                // a number without format is a integer in some languages.
+                None if !x.is_i64() && !x.is_u64() => {
+                    return invalid(format!(
+                        "default {} is not a integer, use format float64 for fractions.",
+                        x
+                    ));
                 }
+                _ => DefaultValue::Number(x.to_string()),
+            },
+            (Keywords::Boolean, Value::Bool(b)) => DefaultValue::Boolean(*b),
+            (Keywords::String, _) => return invalid(String::from("default must be a string.")),
+            (Keywords::Number, _) => return invalid(String::from("default must be a number.")),
+            (Keywords::Boolean, _) => return invalid(String::from("default must be a boolean.")),
+            (Keywords::Any, _) => {
+                return invalid(String::from("A any type can not have a default."))
             }
-
-            node.items = Box::new(ast_type_alias::Node::Union(union_node));
+        },
+        (Node::Array(a), Value::Sequence(seq)) => {
+            let mut diagnostics: Diagnostics = vec![];
+            let items: Vec<DefaultValue> = seq
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, item)| {
+                    collect(
+                        default_value(item, &a.items, false, &key_path(path, &idx.to_string())),
+                        &mut diagnostics,
+                    )
+                })
+                .collect();
+            DefaultValue::Array(finish(items, diagnostics)?)
         }
-        Some("split") => {
-            error!(
-                "Syntax error: split must used top-level. But you use it in a array {}.",
-                id
-            );
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1);
-            }
+        (Node::Array(_), _) => return invalid(String::from("default must be a array (sequence).")),
+        (Node::Ref(r), Value::String(name)) if r.args.is_empty() => {
+            DefaultValue::Member(name.clone())
+        }
+        (Node::Ref(_), _) => {
+            return invalid(String::from(
+                "default of a $ref must be the name of a member of the enum.",
+            ));
+        }
+        (Node::Enum(e), Value::String(name)) if e.members.iter().any(|m| &m.identifier == name) => {
+            DefaultValue::Member(name.clone())
+        }
+        (Node::Enum(e), _) => {
+            let names: Vec<&str> = e.members.iter().map(|m| m.identifier.as_str()).collect();
+            return invalid(format!("default must be one of {}.", names.join(", ")));
+        }
+        (Node::Object(_), _) => {
+            return invalid(String::from(
+                "A object can not have a default, set the defaults of its properties.",
+            ));
         }
         _ => {
-            error!("Syntax error: invalid type in array definition: {}.", id);
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1);
+            return invalid(String::from(
+                "default is only for strings, numbers, booleans, literals, arrays and $ref to enums.",
+            ));
+        }
+    };
+
+    let constraints = match node {
+        Node::Keyword(k) => Some(&k.constraints),
+        Node::Array(a) => Some(&a.constraints),
+        _ => None,
+    };
+    if let Some(c) = constraints
+        .into_iter()
+        .flat_map(checks)
+        .find(|c| !c.accepts(v))
+    {
+        return invalid(format!("default {}.", c.message()));
+    }
+
+    Ok(value)
+}
+
+/// Bases of a object, property conflicts between them are checked by `checker::check_extends`.
+fn visit_extends(
+    e: &serde_yaml::Sequence,
+    path: &[String],
+) -> Result<Vec<ast_type_alias::RefNode>, Diagnostics> {
+    let mut result: Vec<ast_type_alias::RefNode> = vec![];
+    let mut diagnostics: Diagnostics = vec![];
+    for (idx, v) in e.iter().enumerate() {
+        match v.as_str() {
+            Some(uri) => {
+                let path = key_path(path, &idx.to_string());
+                if let Some(node) = collect(ref_node(uri, &path), &mut diagnostics) {
+                    result.push(node);
+                }
             }
+            None => diagnostics.push(
+                Diagnostic::error(Code::Syntax, "extends must be a list of $ref uri.")
+                    .at(&key_path(path, &idx.to_string())),
+            ),
         }
     }
+    finish(result, diagnostics)
 }
 
-fn visit_union_types(t: &serde_yaml::Mapping, id: &str, node: &mut ast_type_alias::UnionNode) {
-    let tp = match t.get("type") {
-        Some(v) => v,
-        None => {
-            error!("Syntax error: missing type in union definition: {}.", id);
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1);
+/// Whether the number of args is the same as type parameters is checked by `checker`.
+fn visit_args(
+    a: &serde_yaml::Sequence,
+    path: &[String],
+) -> Result<Vec<ast_type_alias::Node>, Diagnostics> {
+    let mut result: Vec<ast_type_alias::Node> = vec![];
+    let mut diagnostics: Diagnostics = vec![];
+    for (idx, v) in a.iter().enumerate() {
+        let arg_path = key_path(path, &idx.to_string());
+        match v {
+            serde_yaml::Value::Mapping(m) => {
+                if let Some(node) = collect(visit_node(m, &arg_path), &mut diagnostics) {
+                    result.push(node);
+                }
             }
+            _ => diagnostics.push(
+                Diagnostic::error(Code::Syntax, "Type argument must be a object (mapping).")
+                    .at(&arg_path),
+            ),
         }
+    }
+    finish(result, diagnostics)
+}
+
+fn visit_items(
+    i: &serde_yaml::Mapping,
+    path: &[String],
+) -> Result<ast_type_alias::ArrayNode, Diagnostics> {
+    Ok(ast_type_alias::ArrayNode {
+        items: Box::new(visit_node(i, path)?),
+        constraints: Default::default(),
+    })
+}
+
+fn visit_union_types(
+    t: &serde_yaml::Sequence,
+    path: &[String],
+) -> Result<ast_type_alias::UnionNode, Diagnostics> {
+    let mut node = ast_type_alias::UnionNode {
+        types: vec![],
+        discriminator: None,
+        descriptions: vec![],
     };
+    let mut diagnostics: Diagnostics = vec![];
 
-    match tp.as_str() {
-        Some("string") => {
-            node.types
-                .push(ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
-                    value: ast_type_alias::Keywords::String,
-                }));
-        }
-        Some("number") => {
-            node.types
-                .push(ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
-                    value: ast_type_alias::Keywords::Number,
-                }));
-        }
-        Some("boolean") => {
-            node.types
-                .push(ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
-                    value: ast_type_alias::Keywords::Boolean,
-                }));
-        }
-        Some("any") => {
-            node.types
-                .push(ast_type_alias::Node::Keyword(ast_type_alias::KeywordNode {
-                    value: ast_type_alias::Keywords::Any,
-                }));
-        }
-        Some("object") => {
-            let mut obj_node = ast_type_alias::ObjectNode { values: vec![] };
-            let properties = match t.get("properties") {
-                Some(v) => v,
-                None => {
-                    error!(
-                        "Syntax error: missing properties in object definition: {}.",
-                        id
-                    );
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
+    for (idx, v) in t.iter().enumerate() {
+        let type_path = key_path(path, &idx.to_string());
 
-            if let serde_yaml::Value::Mapping(map) = properties {
-                visit_properties(map, &mut obj_node);
-            } else {
-                error!(
-                    "Syntax error: invalid properties in object definition: {}.",
-                    id
+        let m = match v {
+            serde_yaml::Value::Mapping(m) => m,
+            _ => {
+                diagnostics.push(
+                    Diagnostic::error(Code::Syntax, "Union type must be a object (mapping).")
+                        .at(&type_path),
                 );
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
-                }
+                continue;
             }
+        };
 
-            node.types.push(ast_type_alias::Node::Object(obj_node));
+        if m.get("type").and_then(|t| t.as_str()) == Some("union") {
+            diagnostics.push(
+                Diagnostic::error(Code::Syntax, "union can not be nested in a union.")
+                    .at(&key_path(&type_path, "type")),
+            );
+            continue;
         }
-        Some("array") => {
-            let mut arr_node = ast_type_alias::ArrayNode {
-                items: Box::new(ast_type_alias::Node::new()),
-            };
-            let items = match t.get("items") {
-                Some(v) => v,
-                None => {
-                    error!("Syntax error: missing items in union.array: {}.", id);
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
-
-            if let serde_yaml::Value::Mapping(map) = items {
-                visit_items(map, id, &mut arr_node);
-            } else {
-                error!("Syntax error: invalid items in array definition: {}.", id);
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
-                }
-            }
 
-            node.types.push(ast_type_alias::Node::Array(arr_node));
+        let item = collect(visit_node(m, &type_path), &mut diagnostics);
+        let description = collect(get_description(m, &type_path), &mut diagnostics);
+        if let (Some(item), Some(description)) = (item, description) {
+            node.types.push(item);
+            node.descriptions.push(description);
         }
-        Some("literal") => {
-            let literal_value = match t.get("value") {
-                Some(v) => v,
-                None => {
-                    error!("Syntax error: missing value in union.literal: {}.", id);
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
+    }
 
-            match literal_value {
-                serde_yaml::Value::String(v) => {
-                    node.types.push(ast_type_alias::Node::StringLiteral(
-                        ast_type_alias::StringLiteralNode { value: v.clone() },
-                    ));
-                }
-                serde_yaml::Value::Number(v) => {
-                    node.types.push(ast_type_alias::Node::NumberLiteral(
-                        ast_type_alias::NumberLiteralNode {
-                            value: v.to_string(),
-                        },
-                    ));
-                }
-                _ => {
-                    error!("Syntax error: invalid value in union.literal: {}.", id);
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            }
+    finish(node, diagnostics)
+}
+
+/// `value` of each type, the name of the referred definition by default.
+///
+/// Whether the definitions are objects having `property` is checked by `checker`.
+fn visit_discriminator(
+    property: &str,
+    t: &serde_yaml::Sequence,
+    node: &ast_type_alias::UnionNode,
+    path: &[String],
+) -> Result<ast_type_alias::Discriminator, Diagnostic> {
+    let mut values: Vec<String> = vec![];
+    for (idx, (v, n)) in t.iter().zip(node.types.iter()).enumerate() {
+        let type_path = key_path(path, &idx.to_string());
+        let ast_type_alias::Node::Ref(r) = n else {
+            return Err(Diagnostic::error(
+                Code::Syntax,
+                "Types of a discriminated union must be $ref to objects.",
+            )
+            .at(&key_path(&type_path, "type")));
+        };
+
+        let value = match v.get("value") {
+            Some(value) => value.as_str().map(|s| s.to_string()).ok_or_else(|| {
+                Diagnostic::error(Code::Syntax, "value must be a string.")
+                    .at(&key_path(&type_path, "value"))
+            })?,
+            None => r.name.clone(),
+        };
+        if values.contains(&value) {
+            return Err(Diagnostic::error(
+                Code::Syntax,
+                format!("Discriminator value {} is used twice.", value),
+            )
+            .at(&type_path));
         }
-        Some("$ref") => {
-            let v_uri = match t.get("uri") {
-                Some(v) => v,
-                None => {
-                    error!("Syntax error: missing uri in union.$ref: {}.", id);
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
-            if let serde_yaml::Value::String(uri) = v_uri {
-                let name = uri.split('/').last().unwrap().to_string();
-                let path = uri.split('#').next().unwrap().to_string();
-
-                node.types
-                    .push(ast_type_alias::Node::Ref(ast_type_alias::RefNode {
-                        name,
-                        path,
-                    }));
-            } else {
-                error!("Syntax error: invalid uri in union.$ref: {}.", id);
-                if crate::is_dev() {
-                    panic!();
-                } else {
-                    std::process::exit(1);
+        values.push(value);
+    }
+
+    Ok(ast_type_alias::Discriminator {
+        property: property.to_string(),
+        values,
+    })
+}
+
+fn visit_tuple_items(
+    i: &serde_yaml::Sequence,
+    path: &[String],
+) -> Result<ast_type_alias::TupleNode, Diagnostics> {
+    if i.is_empty() {
+        return Err(
+            Diagnostic::error(Code::Syntax, "Tuple must have at least one item.")
+                .at(path)
+                .into(),
+        );
+    }
+
+    let mut node = ast_type_alias::TupleNode { items: vec![] };
+    let mut diagnostics: Diagnostics = vec![];
+    for (idx, v) in i.iter().enumerate() {
+        let item_path = key_path(path, &idx.to_string());
+
+        match v {
+            serde_yaml::Value::Mapping(m) => {
+                if let Some(item) = collect(visit_node(m, &item_path), &mut diagnostics) {
+                    node.items.push(item);
                 }
             }
+            _ => diagnostics.push(
+                Diagnostic::error(Code::Syntax, "Tuple item must be a object (mapping).")
+                    .at(&item_path),
+            ),
         }
-        Some("$dyn") => {
-            let v_name = match t.get("name") {
-                Some(v) => v,
-                None => {
-                    error!("Syntax error: missing name in union.$dyn: {}.", id);
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
-            let v_from = match t.get("from") {
-                Some(v) => v,
-                None => {
-                    error!("Syntax error: missing from in union.$dyn: {}.", id);
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
+    }
 
-            let name = match v_name {
-                serde_yaml::Value::String(v) => v,
-                _ => {
-                    error!("Syntax error: invalid name in union.$dyn: {}.", id);
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
-            let from = match v_from {
-                serde_yaml::Value::String(v) => v,
-                _ => {
-                    error!("Syntax error: invalid from in union.$dyn: {}.", id);
-                    if crate::is_dev() {
-                        panic!();
-                    } else {
-                        std::process::exit(1);
-                    }
-                }
-            };
+    finish(node, diagnostics)
+}
 
-            node.types
-                .push(ast_type_alias::Node::Dyn(ast_type_alias::DynNode {
-                    name: name.to_string(),
-                    from: from.to_string(),
-                }));
-        }
-        Some("uniton") => {
-            error!("Syntax error: use uniton nested in a union: {}.", id);
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1);
-            }
-        }
-        Some("split") => {
-            error!(
-                "Syntax error: split must used top-level. But you use it in a union: {}.",
-                id
-            );
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1);
-            }
+/// `<path>#/definition/<name>` at `path`, whether it points to a definition is checked by `checker`.
+fn ref_node(uri: &str, path: &[String]) -> Result<ast_type_alias::RefNode, Diagnostic> {
+    let parts = uri
+        .split_once('#')
+        .and_then(|(file, fragment)| Some((file, fragment.strip_prefix("/definition/")?)));
+    match parts {
+        Some((file, name)) if !name.is_empty() && !name.contains('/') => {
+            Ok(ast_type_alias::RefNode {
+                name: name.to_string(),
+                path: file.to_string(),
+                args: vec![],
+            })
         }
-        _ => {
-            error!("Syntax error: invalid type in union definition: {}.", id);
-            if crate::is_dev() {
-                panic!();
-            } else {
-                std::process::exit(1);
-            }
+        _ => Err(Diagnostic::error(
+            Code::InvalidRef,
+            format!(
+                "Invalid $ref uri {}, expected <path>#/definition/<name>.",
+                uri
+            ),
+        )
+        .at(path)),
+    }
+}
+
+fn get<'a>(
+    m: &'a serde_yaml::Mapping,
+    key: &str,
+    path: &[String],
+) -> Result<&'a serde_yaml::Value, Diagnostic> {
+    m.get(key)
+        .ok_or_else(|| Diagnostic::error(Code::Syntax, format!("Missing {}.", key)).at(path))
+}
+
+fn get_string<'a>(
+    m: &'a serde_yaml::Mapping,
+    key: &str,
+    path: &[String],
+) -> Result<&'a str, Diagnostic> {
+    get(m, key, path)?.as_str().ok_or_else(|| {
+        Diagnostic::error(Code::Syntax, format!("{} must be a string.", key))
+            .at(&key_path(path, key))
+    })
+}
+
+/// `format` of a number, `None` if missing.
+fn get_number_format(
+    m: &serde_yaml::Mapping,
+    path: &[String],
+) -> Result<Option<ast_type_alias::NumberFormat>, Diagnostic> {
+    match m.get("format") {
+        Some(_) => {
+            number_format(get_string(m, "format", path)?, &key_path(path, "format")).map(Some)
         }
+        None => Ok(None),
+    }
+}
+
+fn number_format(name: &str, path: &[String]) -> Result<ast_type_alias::NumberFormat, Diagnostic> {
+    let all = ast_type_alias::NumberFormat::ALL;
+    all.into_iter()
+        .find(|f| f.name() == name)
+        .ok_or_else(|| invalid_format("number", name, all.map(|f| f.name()), path))
+}
+
+/// `format` of a string, `None` if missing.
+fn get_string_format(
+    m: &serde_yaml::Mapping,
+    path: &[String],
+) -> Result<Option<ast_type_alias::StringFormat>, Diagnostic> {
+    if m.get("format").is_none() {
+        return Ok(None);
+    }
+    let name = get_string(m, "format", path)?;
+    let all = ast_type_alias::StringFormat::ALL;
+    all.into_iter()
+        .find(|f| f.name() == name)
+        .map(Some)
+        .ok_or_else(|| {
+            invalid_format(
+                "string",
+                name,
+                all.map(|f| f.name()),
+                &key_path(path, "format"),
+            )
+        })
+}
+
+fn invalid_format(
+    kind: &str,
+    name: &str,
+    expected: impl IntoIterator<Item = &'static str>,
+    path: &[String],
+) -> Diagnostic {
+    Diagnostic::error(
+        Code::Syntax,
+        format!(
+            "Invalid {} format {}, expected {}.",
+            kind,
+            name,
+            expected.into_iter().collect::<Vec<&str>>().join(", ")
+        ),
+    )
+    .at(path)
+}
+
+/// `false` if `key` is missing.
+fn get_flag(m: &serde_yaml::Mapping, key: &str, path: &[String]) -> Result<bool, Diagnostic> {
+    match m.get(key) {
+        Some(v) => v.as_bool().ok_or_else(|| {
+            Diagnostic::error(Code::Syntax, format!("{} must be a boolean.", key))
+                .at(&key_path(path, key))
+        }),
+        None => Ok(false),
+    }
+}
+
+/// A length or a number of items, `None` if missing.
+fn get_count(
+    m: &serde_yaml::Mapping,
+    key: &str,
+    path: &[String],
+) -> Result<Option<u64>, Diagnostic> {
+    match m.get(key) {
+        Some(v) => v.as_u64().map(Some).ok_or_else(|| {
+            Diagnostic::error(
+                Code::Syntax,
+                format!("{} must be a non-negative integer.", key),
+            )
+            .at(&key_path(path, key))
+        }),
+        None => Ok(None),
+    }
+}
+
+/// `minimum` or `maximum` of a number, `None` if missing.
+fn get_bound(
+    m: &serde_yaml::Mapping,
+    key: &str,
+    path: &[String],
+) -> Result<Option<f64>, Diagnostic> {
+    match m.get(key) {
+        Some(v) => v.as_f64().map(Some).ok_or_else(|| {
+            Diagnostic::error(Code::Syntax, format!("{} must be a number.", key))
+                .at(&key_path(path, key))
+        }),
+        None => Ok(None),
+    }
+}
+
+fn get_description(m: &serde_yaml::Mapping, path: &[String]) -> Result<Option<String>, Diagnostic> {
+    match m.get("description") {
+        Some(v) => v.as_str().map(|v| Some(v.to_string())).ok_or_else(|| {
+            Diagnostic::error(Code::Syntax, "description must be a string.")
+                .at(&key_path(path, "description"))
+        }),
+        None => Ok(None),
+    }
+}
+
+fn get_deprecated(m: &serde_yaml::Mapping, path: &[String]) -> Result<Option<String>, Diagnostic> {
+    prepare::parse_deprecated(m.get("deprecated")).map_err(|d| d.at(&key_path(path, "deprecated")))
+}
+
+fn get_mapping<'a>(
+    m: &'a serde_yaml::Mapping,
+    key: &str,
+    path: &[String],
+) -> Result<&'a serde_yaml::Mapping, Diagnostic> {
+    get(m, key, path)?.as_mapping().ok_or_else(|| {
+        Diagnostic::error(Code::Syntax, format!("{} must be a object (mapping).", key))
+            .at(&key_path(path, key))
+    })
+}
+
+fn get_sequence<'a>(
+    m: &'a serde_yaml::Mapping,
+    key: &str,
+    path: &[String],
+) -> Result<&'a serde_yaml::Sequence, Diagnostic> {
+    get(m, key, path)?.as_sequence().ok_or_else(|| {
+        Diagnostic::error(Code::Syntax, format!("{} must be a array (sequence).", key))
+            .at(&key_path(path, key))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::diagnostic::Diagnostics;
+    use crate::prepare::parse_spec;
+
+    /// `key path: message` of every problem of a spec.
+    fn problems(source: &str) -> Vec<String> {
+        let mut diagnostics: Diagnostics = vec![];
+        let spec = parse_spec("spec/a.yaml", source, &mut diagnostics).unwrap();
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        transformer(&spec, &Backends::default())
+            .unwrap_err()
+            .into_iter()
+            .map(|d| format!("{}: {}", d.key_path.join("."), d.message))
+            .collect()
+    }
+
+    #[test]
+    fn every_problem() {
+        let result = problems(
+            "selien-version: 0.1.0
+definition:
+  user:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        name:
+          type: string
+          default: 1
+        age:
+          type: number
+          default: 1.5
+        tags:
+          type: array
+          items:
+            type: string
+          default: [a, 2, 3]
+        role:
+          type: unknown
+  level:
+    declaration: enum
+    type: number
+    members:
+      - low: 1
+      - high: high
+      - top: 1
+",
+        );
+        insta::assert_yaml_snapshot!(result);
     }
 }
@@ -1 +1,2 @@
+pub mod generics;
 pub mod main;
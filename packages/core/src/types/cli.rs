@@ -1,4 +1,4 @@
-use clap::{arg, command, Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 
 /// Selien a ssot-type-specification compiler.
 #[derive(Debug, Parser)]
@@ -13,6 +13,8 @@ pub enum Commands {
     /// Generate code from your spec file.
     /// By default, Selien will generate code for all languages in your config file.
     Gen(GenArgs),
+    /// Check your spec file for every language in your config file, without writing any file.
+    Check(CheckArgs),
 }
 
 #[derive(Debug, Args)]
@@ -21,6 +23,21 @@ pub struct GenArgs {
     #[arg(short, long)]
     pub output: Option<String>,
 
+    /// Do not write files, print the diff of every generated file that is out of date and exit with 1.
+    #[arg(long)]
+    pub check: bool,
+
+    /// Keep running, regenerate when spec files or the config file change.
+    #[arg(long, conflicts_with = "check")]
+    pub watch: bool,
+
+    /// Path to your config file. Default is current directory.
+    #[arg(short, long, default_value = ".")]
+    pub config: String,
+}
+
+#[derive(Debug, Args)]
+pub struct CheckArgs {
     /// Path to your config file. Default is current directory.
     #[arg(short, long, default_value = ".")]
     pub config: String,
@@ -1,32 +1,140 @@
 use serde::Deserialize;
 
+use crate::backend::Backends;
+
 #[derive(Debug, Deserialize)]
 pub struct Spec {
     pub root: String,
 }
 
+/// Names of fields generated for properties, `wireName` or the identifier is kept as the key in JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum Naming {
+    /// `userId`
+    Camel,
+    /// `UserId`, go upper-cases initialisms like `UserID`.
+    Pascal,
+    /// `user_id`
+    Snake,
+}
+
+impl Naming {
+    pub const ALL: [Naming; 3] = [Naming::Camel, Naming::Pascal, Naming::Snake];
+
+    /// Name in config file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Naming::Camel => "camelCase",
+            Naming::Pascal => "PascalCase",
+            Naming::Snake => "snake_case",
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct OutputGo {
     pub mod_name: String,
     pub root: String,
     pub output: String,
     pub tabsize: usize,
+    /// Emit type parameters, or a copy of generic definitions for each set of args.
+    pub generics: bool,
+    /// Emit a `Validate() error` method checking constraints.
+    pub validate: bool,
+    pub options: GoOptions,
+}
+
+/// Options of `output.go` changing the generated types.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GoOptions {
+    /// Field names, the identifier capitalized if `None`.
+    pub naming: Option<Naming>,
+    /// Struct tags following `json`, in order.
+    pub tags: Vec<GoTag>,
+}
+
+/// A struct tag of every field, `yaml:"name"`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GoTag {
+    pub key: String,
+    /// Names in the tag, the key in JSON if `None`.
+    pub naming: Option<Naming>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct OutputTypescript {
     pub output: String,
     pub tabsize: usize,
+    pub options: TypescriptOptions,
+}
+
+/// Type of `int64` numbers in typescript, as `number` loses precision above 2^53.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+pub enum Int64Type {
+    #[default]
+    Number,
+    Bigint,
+    String,
+}
+
+/// Type of strings with a `format` in typescript.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+pub enum StringFormatType {
+    /// `type DateTimeString = string`
+    #[default]
+    Alias,
+    /// `type DateTimeString = string & { readonly __format: "date-time" }`
+    Branded,
+}
+
+/// Options of `output.ts` changing the generated types.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TypescriptOptions {
+    pub int64: Int64Type,
+    pub string_format: StringFormatType,
+    /// Emit `validateX` and `isValidX` functions checking shapes and constraints.
+    pub validate: bool,
+    /// Keys of properties, the key in JSON if `None`.
+    pub naming: Option<Naming>,
 }
 
 #[derive(Debug, Deserialize)]
-pub struct Output {
-    pub go: Option<OutputGo>,
-    pub typescript: Option<OutputTypescript>,
+pub struct OutputRust {
+    pub root: String,
+    pub output: String,
+    pub tabsize: usize,
+    /// Field names, snake_case if `None`.
+    pub naming: Option<Naming>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum PythonModel {
+    Dataclass,
+    Pydantic,
 }
 
 #[derive(Debug, Deserialize)]
+pub struct OutputPython {
+    pub output: String,
+    pub tabsize: usize,
+    pub model: PythonModel,
+    /// Field names, the identifier if `None`.
+    pub naming: Option<Naming>,
+}
+
+/// `output.<name>` with a `plugin` key.
+#[derive(Debug)]
+pub struct OutputPlugin {
+    /// Path of the plugin executable.
+    pub plugin: String,
+    pub output: String,
+    /// The whole `output.<name>`, sent to the plugin as is.
+    pub options: serde_yaml::Value,
+}
+
+#[derive(Debug)]
 pub struct Config {
     pub spec: Spec,
-    pub output: Output,
+    /// Every known language, only those defined in config file are configured.
+    pub output: Backends,
 }
@@ -1,8 +1,15 @@
 use serde::Deserialize;
 
+use crate::source::SourceMap;
+
 #[derive(Debug, Deserialize)]
 pub struct TypeAliasDef {
     pub identifier: String,
+    /// `typeParameters`, empty if not generic.
+    pub type_parameters: Vec<String>,
+    pub description: Option<String>,
+    /// `Some` if deprecated, with the reason or empty for `deprecated: true`.
+    pub deprecated: Option<String>,
     pub body: serde_yaml::Mapping,
 }
 
@@ -10,6 +17,9 @@ pub struct TypeAliasDef {
 pub struct EnumDef {
     pub identifier: String,
     pub r#type: String,
+    pub format: Option<String>,
+    pub description: Option<String>,
+    pub deprecated: Option<String>,
     pub members: serde_yaml::Sequence,
 }
 
@@ -19,11 +29,23 @@ pub enum Def {
     Enum(EnumDef),
 }
 
+impl Def {
+    pub fn identifier(&self) -> &str {
+        match self {
+            Def::TypeAlias(def) => &def.identifier,
+            Def::Enum(def) => &def.identifier,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Spec {
     pub version: String,
     pub path: String,
     pub def: Vec<Def>,
+    /// Positions of the spec file, to locate diagnostics.
+    #[serde(skip)]
+    pub source: SourceMap,
 }
 
 impl Spec {
@@ -32,8 +54,15 @@ impl Spec {
             version: String::new(),
             path: String::new(),
             def: vec![],
+            source: SourceMap::default(),
         }
     }
 }
 
+impl Default for Spec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub type SpecList = Vec<Spec>;
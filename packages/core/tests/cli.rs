@@ -0,0 +1,447 @@
+use std::fs;
+use std::path::PathBuf;
+
+use assert_cmd::Command;
+
+const CONFIG: &str = "spec:
+  root: spec
+output:
+  ts:
+    output: dist/ts
+";
+
+const SPEC: &str = "selien-version: 0.1.0
+definition:
+  user:
+    declaration: type-alias
+    body:
+      type: string
+";
+
+/// A new directory with a config file and `spec/a.yaml`, named by the test.
+fn workspace(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("selien-cli-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("spec")).unwrap();
+    fs::write(dir.join("selien.config.yaml"), CONFIG).unwrap();
+    fs::write(dir.join("spec/a.yaml"), SPEC).unwrap();
+    dir
+}
+
+fn selien(dir: &PathBuf, args: &[&str]) -> Command {
+    let mut cmd = Command::cargo_bin("selien").unwrap();
+    cmd.current_dir(dir).args(args).env_remove("SELIEN_ENV");
+    cmd
+}
+
+fn stderr(output: &std::process::Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+#[test]
+fn gen_check_created() {
+    let dir = workspace("gen-check-created");
+
+    let output = selien(&dir, &["gen", "--check"]).assert().code(1);
+    let err = stderr(output.get_output());
+    assert!(err.contains("[E300]"), "{}", err);
+    assert!(err.contains("dist/ts/a.ts would be created."), "{}", err);
+    assert!(!dir.join("dist").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn gen_check_diff() {
+    let dir = workspace("gen-check-diff");
+    selien(&dir, &["gen"]).assert().success();
+
+    // up to date.
+    selien(&dir, &["gen", "--check"]).assert().success();
+
+    fs::write(dir.join("spec/a.yaml"), SPEC.replace("string", "number")).unwrap();
+    let output = selien(&dir, &["gen", "--check"]).assert().code(1);
+    let err = stderr(output.get_output());
+    assert!(err.contains("dist/ts/a.ts is out of date."), "{}", err);
+    assert!(
+        err.contains("-export type User = string;\n+export type User = number;"),
+        "{}",
+        err
+    );
+    // nothing is written.
+    let content = fs::read_to_string(dir.join("dist/ts/a.ts")).unwrap();
+    assert!(content.contains("export type User = string;"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn gen_language_check() {
+    let dir = workspace("gen-language-check");
+    let config = format!(
+        "{}  rust:\n    root: dist/rs\n    output: dist/rs/src\n",
+        CONFIG
+    );
+    fs::write(dir.join("selien.config.yaml"), config).unwrap();
+    fs::write(
+        dir.join("spec/a.yaml"),
+        "selien-version: 0.1.0
+definition:
+  level:
+    declaration: type-alias
+    body:
+      type: split
+      ts:
+        type: string
+      rs:
+        type: object
+        properties:
+          level:
+            type: enum
+            members:
+              - half: 0.5
+",
+    )
+    .unwrap();
+
+    // only the language having the problem fails.
+    selien(&dir, &["gen", "-o", "ts"]).assert().success();
+    let output = selien(&dir, &["gen", "-o", "rs"]).assert().code(1);
+    let err = stderr(output.get_output());
+    assert!(err.contains("[E102]"), "{}", err);
+    assert!(
+        err.contains("(definition.level.body.rs.properties.level.members)"),
+        "{}",
+        err
+    );
+    assert!(!dir.join("dist/rs").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn check_clean() {
+    let dir = workspace("check-clean");
+
+    selien(&dir, &["check"]).assert().success();
+    // nothing is written.
+    assert!(!dir.join("dist").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn check_refs() {
+    let dir = workspace("check-refs");
+    fs::write(
+        dir.join("spec/b.yaml"),
+        "selien-version: 0.1.0
+definition:
+  admin:
+    declaration: type-alias
+    body:
+      type: $ref
+      uri: \"./a#/definition/none\"
+",
+    )
+    .unwrap();
+
+    let output = selien(&dir, &["check"]).assert().code(1);
+    let err = stderr(output.get_output());
+    assert!(err.contains("[E103]"), "{}", err);
+    assert!(
+        err.contains("none is not defined in spec/a.yaml."),
+        "{}",
+        err
+    );
+    assert!(err.contains("(definition.admin.body.uri)"), "{}", err);
+    assert!(!dir.join("dist").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn check_go_package() {
+    let dir = workspace("check-go-package");
+    let config = format!(
+        "{}  go:\n    mod_name: example.com/app\n    root: dist/go\n    output: dist/go\n",
+        CONFIG
+    );
+    fs::write(dir.join("selien.config.yaml"), config).unwrap();
+    fs::write(
+        dir.join("spec/b.yaml"),
+        "selien-version: 0.1.0
+definition:
+  User:
+    declaration: type-alias
+    body:
+      type: number
+",
+    )
+    .unwrap();
+
+    let output = selien(&dir, &["check"]).assert().code(1);
+    let err = stderr(output.get_output());
+    assert!(
+        err.contains("User is declared twice in go package spec, also at spec/a.yaml"),
+        "{}",
+        err
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+const GENERIC: &str = "selien-version: 0.1.0
+definition:
+  page:
+    declaration: type-alias
+    typeParameters:
+      - T
+    body:
+      type: object
+      properties:
+        items:
+          type: array
+          items:
+            type: param
+            name: T
+  result:
+    declaration: type-alias
+    typeParameters:
+      - T
+      - E
+    body:
+      type: union
+      types:
+        - type: object
+          properties:
+            ok:
+              type: param
+              name: T
+        - type: object
+          properties:
+            err:
+              type: param
+              name: E
+  search:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        pages:
+          type: $ref
+          uri: \"#/definition/result\"
+          args:
+            - type: $ref
+              uri: \"#/definition/page\"
+              args:
+                - type: string
+            - type: string
+";
+
+#[test]
+fn gen_go_instances_once() {
+    let dir = workspace("gen-go-instances-once");
+    let config = "spec:
+  root: spec
+output:
+  go:
+    mod_name: example.com/app
+    root: dist
+    output: dist/api
+    generics: false
+";
+    fs::write(dir.join("selien.config.yaml"), config).unwrap();
+    fs::write(dir.join("spec/a.yaml"), GENERIC).unwrap();
+    fs::write(
+        dir.join("spec/b.yaml"),
+        GENERIC.replace("search:", "other:"),
+    )
+    .unwrap();
+
+    selien(&dir, &["check"]).assert().success();
+    selien(&dir, &["gen"]).assert().success();
+    let a = fs::read_to_string(dir.join("dist/api/a.go")).unwrap();
+    let b = fs::read_to_string(dir.join("dist/api/b.go")).unwrap();
+    assert!(a.contains("type PageString struct"), "{}", a);
+    assert!(!b.contains("type PageString struct"), "{}", b);
+    assert!(b.contains("Pages ResultPageStringString"), "{}", b);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn gen_python_union_instances() {
+    let dir = workspace("gen-python-union-instances");
+    let config = "spec:
+  root: spec
+output:
+  python:
+    output: dist/py
+    model: pydantic
+";
+    fs::write(dir.join("selien.config.yaml"), config).unwrap();
+    fs::write(dir.join("spec/a.yaml"), GENERIC).unwrap();
+
+    selien(&dir, &["gen"]).assert().success();
+    let a = fs::read_to_string(dir.join("dist/py/a.py")).unwrap();
+    // a alias of a union can not be subscripted.
+    assert!(a.contains("pages: ResultPageStringString\n"), "{}", a);
+    assert!(
+        a.contains("ResultPageStringString = Union[ResultPageStringStringVariant0, ResultPageStringStringVariant1]"),
+        "{}",
+        a
+    );
+    // with the instances it uses.
+    assert!(a.contains("ok: PageString\n"), "{}", a);
+    assert!(a.contains("class PageString(BaseModel):"), "{}", a);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn gen_go_identifiers() {
+    let dir = workspace("gen-go-identifiers");
+    let config = format!(
+        "{}  go:\n    mod_name: example.com/app\n    root: dist/go\n    output: dist/go\n",
+        CONFIG
+    );
+    fs::write(dir.join("selien.config.yaml"), config).unwrap();
+    fs::write(
+        dir.join("spec/a.yaml"),
+        "selien-version: 0.1.0
+definition:
+  subscriptionState:
+    declaration: type-alias
+    body:
+      type: string
+  subscription:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        state:
+          type: enum
+          members:
+            - active: active
+",
+    )
+    .unwrap();
+
+    // the hoisted enum is only a type in go.
+    selien(&dir, &["gen", "-o", "ts"]).assert().success();
+    let output = selien(&dir, &["gen", "-o", "go"]).assert().code(1);
+    let err = stderr(output.get_output());
+    assert!(err.contains("[E104]"), "{}", err);
+    assert!(
+        err.contains("SubscriptionState is declared twice in go package spec, also at spec/a.yaml (definition.subscriptionState)."),
+        "{}",
+        err
+    );
+    assert!(
+        err.contains("(definition.subscription.body.properties.state)"),
+        "{}",
+        err
+    );
+    assert!(!dir.join("dist/go").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn gen_enum_default() {
+    let dir = workspace("gen-enum-default");
+    fs::write(
+        dir.join("spec/a.yaml"),
+        "selien-version: 0.1.0
+definition:
+  color:
+    declaration: enum
+    type: string
+    members:
+      - red: red
+      - blue: blue
+  palette:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        primary:
+          type: $ref
+          uri: \"#/definition/color\"
+          default: green
+",
+    )
+    .unwrap();
+
+    let output = selien(&dir, &["gen"]).assert().code(1);
+    let err = stderr(output.get_output());
+    assert!(
+        err.contains("default green is not a member of color."),
+        "{}",
+        err
+    );
+    assert!(
+        err.contains("(definition.palette.body.properties.primary.uri)"),
+        "{}",
+        err
+    );
+    assert!(!dir.join("dist/ts").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn gen_field_names() {
+    let dir = workspace("gen-field-names");
+    let config = format!(
+        "{}  go:\n    mod_name: example.com/app\n    root: dist/go\n    output: dist/go\n    naming: PascalCase\n  python:\n    root: dist/py\n    output: dist/py\n",
+        CONFIG
+    );
+    fs::write(dir.join("selien.config.yaml"), config).unwrap();
+    fs::write(
+        dir.join("spec/a.yaml"),
+        "selien-version: 0.1.0
+definition:
+  user:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        userId:
+          type: string
+        userID:
+          type: string
+        user-name:
+          type: string
+        user_name:
+          type: string
+",
+    )
+    .unwrap();
+
+    for command in ["gen", "check"] {
+        let output = selien(&dir, &[command]).assert().code(1);
+        let err = stderr(output.get_output());
+        assert!(
+            err.contains("Properties userId and userID are both UserID in go."),
+            "{}",
+            err
+        );
+        assert!(
+            err.contains("(definition.user.body.properties.userID)"),
+            "{}",
+            err
+        );
+        assert!(
+            err.contains("Properties user-name and user_name are both user_name in python."),
+            "{}",
+            err
+        );
+        // typescript keeps the keys as they are.
+        assert!(!err.contains("in typescript"), "{}", err);
+    }
+    assert!(!dir.join("dist").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,527 @@
+//! Generated code is built by the compiler of its language.
+//!
+//! A language whose toolchain is not installed is skipped with a message,
+//! the checks of the generated text still run.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command as Process;
+
+use assert_cmd::Command;
+
+/// A new directory with `config` and each of `specs` under `spec/`, named by the test.
+fn workspace(name: &str, config: &str, specs: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("selien-compile-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("spec")).unwrap();
+    fs::write(dir.join("selien.config.yaml"), config).unwrap();
+    for (path, source) in specs {
+        fs::write(dir.join("spec").join(path), source).unwrap();
+    }
+    dir
+}
+
+fn has(program: &str) -> bool {
+    Process::new(program).arg("--version").output().is_ok()
+}
+
+fn gen(dir: &Path) {
+    Command::cargo_bin("selien")
+        .unwrap()
+        .current_dir(dir)
+        .arg("gen")
+        .env_remove("SELIEN_ENV")
+        .assert()
+        .success();
+}
+
+/// `cargo check` the crate at `dir/dist/rs`, generated into `src/spec`.
+fn check_rust(dir: &Path) {
+    cargo(dir, "check", "");
+}
+
+/// `cargo <command>` the crate at `dir/dist/rs`, `lib` follows `pub mod spec;` in `lib.rs`.
+///
+/// The lock file of selien is reused, dependencies are the versions selien is built with.
+fn cargo(dir: &Path, command: &str, lib: &str) {
+    let krate = dir.join("dist/rs");
+    fs::write(
+        krate.join("Cargo.toml"),
+        "[package]
+name = \"generated\"
+version = \"0.0.0\"
+edition = \"2021\"
+
+[dependencies]
+serde = { version = \"1.0\", features = [\"derive\"] }
+serde_json = \"1.0\"
+serde_repr = \"0.1\"
+",
+    )
+    .unwrap();
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    fs::copy(manifest_dir.join("Cargo.lock"), krate.join("Cargo.lock")).unwrap();
+    fs::write(krate.join("src/lib.rs"), format!("pub mod spec;\n{}", lib)).unwrap();
+
+    let output = Process::new(env!("CARGO"))
+        .current_dir(&krate)
+        .args([command, "--quiet"])
+        .env("CARGO_TARGET_DIR", manifest_dir.join("target/generated"))
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+const RUST: &str = "spec:
+  root: spec
+output:
+  rust:
+    root: dist/rs/src
+    output: dist/rs/src/spec
+";
+
+#[test]
+fn rust_cycles() {
+    let dir = workspace(
+        "rust-cycles",
+        RUST,
+        &[
+            (
+                "a.yaml",
+                "selien-version: 0.1.0
+definition:
+  treeNode:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        parent:
+          type: $ref
+          uri: \"#/definition/treeNode\"
+          optional: true
+        children:
+          type: array
+          items:
+            type: $ref
+            uri: \"#/definition/treeNode\"
+  a:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        b:
+          type: $ref
+          uri: \"./b#/definition/b\"
+  expr:
+    declaration: type-alias
+    body:
+      type: union
+      types:
+        - type: string
+        - type: $ref
+          uri: \"#/definition/binary\"
+  binary:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        left:
+          type: $ref
+          uri: \"#/definition/expr\"
+        pair:
+          type: tuple
+          items:
+            - type: $ref
+              uri: \"#/definition/expr\"
+            - type: string
+",
+            ),
+            (
+                "b.yaml",
+                "selien-version: 0.1.0
+definition:
+  b:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        a:
+          type: $ref
+          uri: \"./a#/definition/a\"
+          optional: true
+",
+            ),
+        ],
+    );
+    gen(&dir);
+
+    let a = fs::read_to_string(dir.join("dist/rs/src/spec/a.rs")).unwrap();
+    assert!(a.contains("pub parent: Option<Box<TreeNode>>,"), "{}", a);
+    // an array is out of line already.
+    assert!(a.contains("pub children: Vec<TreeNode>,"), "{}", a);
+    check_rust(&dir);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn rust_enum_members() {
+    let dir = workspace(
+        "rust-enum-members",
+        RUST,
+        &[(
+            "a.yaml",
+            "selien-version: 0.1.0
+definition:
+  color:
+    declaration: enum
+    type: string
+    members:
+      - self: self
+      - foo_bar: foo
+      - fooBar: bar
+      - blue: 'bl\"ue\\'
+  level:
+    declaration: enum
+    type: number
+    members:
+      - low_level: 1
+      - lowLevel: 2
+",
+        )],
+    );
+    gen(&dir);
+
+    let a = fs::read_to_string(dir.join("dist/rs/src/spec/a.rs")).unwrap();
+    assert!(a.contains("    Self_,\n"), "{}", a);
+    assert!(a.contains("    FooBar2,\n"), "{}", a);
+    assert!(a.contains(r#"#[serde(rename = "bl\"ue\\")]"#), "{}", a);
+    assert!(a.contains("    LowLevel2 = 2,"), "{}", a);
+    check_rust(&dir);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn rust_wire_names() {
+    let dir = workspace(
+        "rust-wire-names",
+        RUST,
+        &[(
+            "a.yaml",
+            "selien-version: 0.1.0
+definition:
+  profile:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        user_id:
+          type: string
+        displayName:
+          type: string
+        type:
+          type: string
+        avatar:
+          type: string
+          wireName: avatar-url
+          optional: true
+",
+        )],
+    );
+    gen(&dir);
+
+    // keys are kept on the wire, an optional one is left out if it is not set.
+    cargo(
+        &dir,
+        "test",
+        r##"
+#[test]
+fn wire_names() {
+    let json = r#"{"user_id":"a","displayName":"b","type":"c"}"#;
+    let p: spec::a::Profile = serde_json::from_str(json).unwrap();
+    assert_eq!((p.display_name.as_str(), p.r#type.as_str()), ("b", "c"));
+    assert_eq!(serde_json::to_string(&p).unwrap(), json);
+
+    let json = r#"{"user_id":"a","displayName":"b","type":"c","avatar-url":"d"}"#;
+    let p: spec::a::Profile = serde_json::from_str(json).unwrap();
+    assert_eq!(p.avatar.as_deref(), Some("d"));
+    assert_eq!(serde_json::to_string(&p).unwrap(), json);
+}
+"##,
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Run `script` by python in `dir/dist/py`, the output of the config `PYTHON`.
+fn run_python(dir: &Path, script: &str) {
+    let output = Process::new("python3")
+        .current_dir(dir.join("dist/py"))
+        .args(["-c", script])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+const PYTHON: &str = "spec:
+  root: spec
+output:
+  python:
+    root: dist/py
+    output: dist/py
+    model: pydantic
+";
+
+#[test]
+fn python_strings() {
+    if !has("python3") {
+        eprintln!("python3 is not installed, skipped.");
+        return;
+    }
+    let dir = workspace(
+        "python-strings",
+        PYTHON,
+        &[(
+            "a.yaml",
+            "selien-version: 0.1.0
+definition:
+  color:
+    declaration: enum
+    type: string
+    members:
+      - blue: 'bl\"ue\\'
+  user:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        name:
+          type: string
+          wireName: 'user\"name'
+        greeting:
+          type: literal
+          value: 'say \"hi\"'
+        shade:
+          type: enum
+          members:
+            - dark: 'da\"rk'
+",
+        )],
+    );
+    gen(&dir);
+
+    run_python(
+        &dir,
+        r#"
+from a import Color, User
+assert Color.Blue.value == 'bl"ue\\'
+u = User.model_validate({'user"name': 'x', 'greeting': 'say "hi"', 'shade': 'da"rk'})
+assert u.name == 'x'
+"#,
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn python_dataclass_wire_names() {
+    if !has("python3") {
+        eprintln!("python3 is not installed, skipped.");
+        return;
+    }
+    let config = PYTHON.replace("pydantic", "dataclass\n    naming: snake_case");
+    let dir = workspace(
+        "python-dataclass-wire-names",
+        &config,
+        &[(
+            "a.yaml",
+            "selien-version: 0.1.0
+definition:
+  profile:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        avatar:
+          type: string
+          wireName: avatar-url
+          optional: true
+        user_id:
+          type: string
+        displayName:
+          type: string
+        from:
+          type: string
+",
+        )],
+    );
+    gen(&dir);
+
+    run_python(
+        &dir,
+        r#"
+from dataclasses import fields
+from a import Profile
+p = Profile(user_id='a', display_name='b', from_='c')
+assert p.avatar is None
+aliases = {f.name: f.metadata.get('alias') for f in fields(Profile)}
+assert aliases == {'avatar': 'avatar-url', 'user_id': None, 'display_name': 'displayName', 'from_': 'from'}, aliases
+"#,
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+/// `go vet` the module at `dir/dist/go`, generated into `app`.
+fn check_go(dir: &Path) {
+    let module = dir.join("dist/go");
+    fs::write(module.join("go.mod"), "module example.com/app\n\ngo 1.21\n").unwrap();
+    let output = Process::new("go")
+        .current_dir(&module)
+        .args(["vet", "./..."])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+const GO: &str = "spec:
+  root: spec
+output:
+  go:
+    mod_name: example.com/app
+    root: dist/go
+    output: dist/go/app
+    validate: true
+";
+
+#[test]
+fn go_blank_lines() {
+    let dir = workspace(
+        "go-blank-lines",
+        GO,
+        &[(
+            "a.yaml",
+            "selien-version: 0.1.0
+definition:
+  point:
+    declaration: type-alias
+    body:
+      type: tuple
+      items:
+        - type: number
+        - type: string
+  shape:
+    declaration: type-alias
+    body:
+      type: union
+      discriminator: kind
+      types:
+        - type: $ref
+          uri: \"#/definition/circle\"
+  circle:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        kind:
+          type: literal
+          value: circle
+        center:
+          type: tuple
+          items:
+            - type: number
+            - type: number
+",
+        )],
+    );
+    gen(&dir);
+
+    let a = fs::read_to_string(dir.join("dist/go/app/a.go")).unwrap();
+    assert!(!a.contains("\n\n\n"), "{}", a);
+    if has("go") {
+        check_go(&dir);
+    } else {
+        eprintln!("go is not installed, only blank lines are checked.");
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn go_naming_tags() {
+    let config = GO.replace(
+        "    validate: true\n",
+        "    naming: PascalCase\n    tags:\n      - yaml\n      - key: db\n        naming: snake_case\n      - validate\n",
+    );
+    let dir = workspace(
+        "go-naming-tags",
+        &config,
+        &[(
+            "a.yaml",
+            "selien-version: 0.1.0
+definition:
+  treeNode:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        parent:
+          type: $ref
+          uri: \"#/definition/treeNode\"
+          optional: true
+        children:
+          type: array
+          items:
+            type: $ref
+            uri: \"#/definition/treeNode\"
+  session:
+    declaration: type-alias
+    body:
+      type: object
+      properties:
+        userId:
+          type: string
+        avatarUrl:
+          type: string
+          optional: true
+        token:
+          type: string
+          wireName: access-token
+          go:
+            tags:
+              validate: required
+",
+        )],
+    );
+    gen(&dir);
+
+    let a = fs::read_to_string(dir.join("dist/go/app/a.go")).unwrap();
+    for field in [
+        "Parent *TreeNode `json:\"parent,omitempty\" yaml:\"parent,omitempty\" db:\"parent,omitempty\" validate:\"omitempty\"`",
+        "UserID string `json:\"userId\" yaml:\"userId\" db:\"user_id\"`",
+        "AvatarURL *string `json:\"avatarUrl,omitempty\" yaml:\"avatarUrl,omitempty\" db:\"avatar_url,omitempty\" validate:\"omitempty\"`",
+        "Token string `json:\"access-token\" yaml:\"access-token\" db:\"access_token\" validate:\"required\"`",
+    ] {
+        assert!(a.contains(field), "{}\n{}", field, a);
+    }
+    if has("go") {
+        check_go(&dir);
+    } else {
+        eprintln!("go is not installed, only fields are checked.");
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}